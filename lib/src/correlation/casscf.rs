@@ -0,0 +1,554 @@
+use crate::{
+    correlation::ci::{
+        ci, determinants::DeterminantSpace, hamiltonian::ActiveSpace, rdm::density_matrices,
+        CIOptions,
+    },
+    data::orbitals::Orbitals,
+    gto_integrals::mo_transform::{mo_transform, orbital_space, MOIntegrals, TransformBuffer},
+    linear_algebra::{
+        diagonalize::DiagonalizeSym, diis::DIIS, exponential::exp_antisymmetric, matrix::FMatrix,
+        matrix_container::FMatrixContainer, vector::FVector,
+    },
+    scf::fock::fock,
+};
+
+use std::time::Instant;
+
+/// Convergence settings of the macro iterations
+#[derive(Clone, Debug, PartialEq)]
+pub struct CASSCFOptions {
+    pub max_iter: usize,
+    pub e_threshold: f64,
+    /// largest element of the orbital gradient
+    pub g_threshold: f64,
+    /// largest norm of an orbital rotation step
+    pub max_step: f64,
+    /// residual norm of the CI eigensolver
+    pub ci_threshold: f64,
+    pub diis_dim_max: usize,
+    /// largest gradient element for DIIS
+    pub diis_threshold: f64,
+}
+
+impl Default for CASSCFOptions {
+    fn default() -> Self {
+        Self {
+            max_iter: 100,
+            e_threshold: 1e-8,
+            g_threshold: 1e-5,
+            max_step: 0.5,
+            ci_threshold: 1e-7,
+            diis_dim_max: 8,
+            diis_threshold: 1e-3,
+        }
+    }
+}
+
+/// Complete active space SCF in the two-step formulation: a full CI in the active
+/// orbitals followed by an augmented-Hessian step of the orbital rotations between the
+/// core, active and virtual spaces with a diagonal approximation of the Hessian,
+/// accelerated by DIIS on the total rotation
+#[derive(Clone, Debug, Default)]
+pub struct CASSCF {
+    n_core: usize,
+    n_active: usize,
+    n_alpha: usize,
+    n_beta: usize,
+
+    c: FMatrix,
+    e_casci: f64,
+    e_casscf: f64,
+    s2: f64,
+    orbital_energies: FVector,
+    occupations: FVector,
+    converged: bool,
+}
+
+/// CASCI solution and Fock matrices of one set of orbitals
+struct MacroIteration {
+    energy: f64,
+    s2: f64,
+    gamma: FMatrix,
+    /// F^I + F^A in the MO basis
+    f: FMatrix,
+    /// generalized Fock matrix, only core and active columns are non-zero
+    f_gen: FMatrix,
+}
+
+/// Getters
+impl CASSCF {
+    /// electronic energy of the CASCI in the initial orbitals
+    pub fn e_casci(&self) -> f64 {
+        self.e_casci
+    }
+
+    /// electronic energy of the optimized orbitals
+    pub fn e_casscf(&self) -> f64 {
+        self.e_casscf
+    }
+
+    /// <S^2> of the CASSCF state
+    pub fn s2(&self) -> f64 {
+        self.s2
+    }
+
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    /// natural occupation numbers, 2 for core and 0 for virtual orbitals
+    pub fn occupations(&self) -> &FVector {
+        &self.occupations
+    }
+}
+
+impl CASSCF {
+    /// CAS(n_alpha + n_beta, n_active) after `n_core` doubly occupied orbitals, starting
+    /// from the MO coefficients c (AO x MO)
+    pub fn new(c: &FMatrix, n_core: usize, n_active: usize, n_alpha: usize, n_beta: usize) -> Self {
+        assert!(
+            n_alpha <= n_active && n_beta <= n_active,
+            "CAS({}, {}): too many active electrons",
+            n_alpha + n_beta,
+            n_active
+        );
+        assert!(
+            n_core + n_active <= c.cols,
+            "Active space exceeds the {} molecular orbitals",
+            c.cols
+        );
+
+        Self {
+            n_core,
+            n_active,
+            n_alpha,
+            n_beta,
+            c: c.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Natural orbitals in the active space, canonical core and virtual orbitals
+    pub fn orbitals(&self) -> Orbitals {
+        Orbitals::new_natural(
+            self.c.clone(),
+            self.orbital_energies.clone(),
+            self.occupations.clone(),
+        )
+    }
+
+    /// Optimize orbitals and CI vector, returns if the macro iterations converged. Without
+    /// iterations (max_iter = 0) the CASCI state of the starting orbitals is kept.
+    pub fn solve(&mut self, h: &FMatrix, eri: &FMatrixContainer, options: &CASSCFOptions) -> bool {
+        let pairs = self.rotation_pairs();
+        println!("Orbital rotations:     {}", pairs.len());
+        println!(
+            "\nIter {:^20} {:^12} {:^10} {:^10} {:^8}",
+            "E(el)", "ΔE", "|g|max", "Step", "Time"
+        );
+
+        let mut c_start = None;
+        let mut kappa = vec![0.0; pairs.len()];
+        let mut diis = DIIS::new(options.diis_dim_max);
+
+        let mut e_old = 0.0;
+        let mut current = None;
+        for iter in 0..options.max_iter {
+            let t = Instant::now();
+            let it = self.macro_iteration(h, eri, options);
+            if iter == 0 {
+                self.e_casci = it.energy;
+            }
+
+            let gradient = self.gradient(&it.f_gen, &pairs);
+            let g_max = gradient.iter().fold(0.0_f64, |m, g| m.max(g.abs()));
+            let delta_e = it.energy - e_old;
+            e_old = it.energy;
+
+            self.converged = delta_e.abs() < options.e_threshold && g_max < options.g_threshold;
+            if self.converged || iter + 1 == options.max_iter {
+                println!(
+                    "{:>4} {:>20.12} {:>12.4e} {:>10.2e} {:>10} {:>8.2?}",
+                    iter,
+                    it.energy,
+                    delta_e,
+                    g_max,
+                    "",
+                    t.elapsed()
+                );
+                current = Some(it);
+                break;
+            }
+
+            // augmented-Hessian step and rotation of the orbitals
+            let hessian = self.diagonal_hessian(&it, &pairs);
+            let step = augmented_hessian_step(&gradient, &hessian, options.max_step);
+            let norm = step.iter().map(|x| x * x).sum::<f64>().sqrt();
+
+            // close to convergence: DIIS on the total rotation from the orbitals of that
+            // point, with the step as error vector
+            if c_start.is_none() && g_max > options.diis_threshold {
+                self.rotate(&pairs, &step);
+            } else {
+                let c_start = c_start.get_or_insert_with(|| self.c.clone());
+                kappa.iter_mut().zip(&step).for_each(|(k, x)| *k += x);
+                diis.extrapolate(&mut kappa, &step);
+                self.c = c_start.clone();
+                self.rotate(&pairs, &kappa);
+            }
+            println!(
+                "{:>4} {:>20.12} {:>12.4e} {:>10.2e} {:>10.2e} {:>8.2?}",
+                iter,
+                it.energy,
+                delta_e,
+                g_max,
+                norm,
+                t.elapsed()
+            );
+        }
+
+        let it = current.unwrap_or_else(|| {
+            let it = self.macro_iteration(h, eri, options);
+            self.e_casci = it.energy;
+            it
+        });
+        self.e_casscf = it.energy;
+        self.s2 = it.s2;
+        self.natural_orbitals(&it);
+
+        if !self.converged {
+            println!(
+                "Warning: CASSCF not converged within {} iterations",
+                options.max_iter
+            );
+        }
+        self.converged
+    }
+
+    /// Non-redundant rotations (p, q), p > q: active-core, virtual-core and
+    /// virtual-active
+    fn rotation_pairs(&self) -> Vec<(usize, usize)> {
+        let n_mo = self.c.cols;
+        let n_occ = self.n_core + self.n_active;
+        let mut pairs = Vec::new();
+        for q in 0..self.n_core {
+            for p in self.n_core..n_mo {
+                pairs.push((p, q));
+            }
+        }
+        for q in self.n_core..n_occ {
+            for p in n_occ..n_mo {
+                pairs.push((p, q));
+            }
+        }
+        pairs
+    }
+
+    /// CASCI in the current orbitals, density matrices and the Fock matrices
+    fn macro_iteration(
+        &self,
+        h: &FMatrix,
+        eri: &FMatrixContainer,
+        options: &CASSCFOptions,
+    ) -> MacroIteration {
+        let (n_core, n_active) = (self.n_core, self.n_active);
+        let c = &self.c;
+
+        // lowest root with the spin of the high-spin determinant, S = M_S
+        let active = ActiveSpace::new(h, eri, c, n_core, n_active, &TransformBuffer::Memory);
+        let space = DeterminantSpace::new(n_active, self.n_alpha, self.n_beta, None);
+        let ci_options = CIOptions {
+            n_roots: space.dim().min(3),
+            threshold: options.ci_threshold,
+            ..Default::default()
+        };
+        let sz = 0.5 * (self.n_alpha as f64 - self.n_beta as f64);
+        let states = ci(&active, &space, &ci_options);
+        let state = states
+            .iter()
+            .find(|state| (state.s2 - sz * (sz + 1.0)).abs() < 1e-2)
+            .unwrap_or(&states[0]);
+        let (gamma, big_gamma) = density_matrices(&space, &state.coefficients);
+
+        // inactive and active Fock matrices from the core and active densities
+        let c_active = orbital_space(c, n_core..n_core + n_active);
+        let d_core = match n_core {
+            0 => FMatrix::zero(c.rows, c.rows),
+            _ => {
+                let c_core = orbital_space(c, 0..n_core);
+                &c_core * &c_core.transposed() * 2.0
+            }
+        };
+        let d_active = &c_active * &(&gamma * &c_active.transposed());
+        let zero = FMatrix::zero(c.rows, c.rows);
+        let f_inactive = &c.transposed() * &(fock(&d_core, h, eri, 1.0, 0.5) * c);
+        let f_active = &c.transposed() * &(fock(&d_active, &zero, eri, 1.0, 0.5) * c);
+
+        // generalized Fock matrix: F_pi = 2 (F^I + F^A)_pi,
+        // F_pt = \sum_u F^I_pu γ_tu + \sum_uvw Γ_tuvw (pu|vw)
+        let puvw = mo_transform(
+            eri,
+            [c, &c_active, &c_active, &c_active],
+            &TransformBuffer::Memory,
+        );
+        let f = &f_inactive + &f_active;
+        let n_mo = c.cols;
+        let mut f_gen = FMatrix::zero(n_mo, n_mo);
+        for p in 0..n_mo {
+            for i in 0..n_core {
+                f_gen[(p, i)] = 2.0 * f[(p, i)];
+            }
+            for t in 0..n_active {
+                f_gen[(p, n_core + t)] =
+                    generalized_active(&f_inactive, &gamma, &big_gamma, &puvw, p, t, n_core);
+            }
+        }
+
+        MacroIteration {
+            energy: state.energy,
+            s2: state.s2,
+            gamma,
+            f,
+            f_gen,
+        }
+    }
+
+    /// Orbital gradient g_pq = ∂E/∂κ_pq = 2 (F_pq - F_qp) of the rotation C -> C exp(κ)
+    fn gradient(&self, f_gen: &FMatrix, pairs: &[(usize, usize)]) -> Vec<f64> {
+        pairs
+            .iter()
+            .map(|&(p, q)| 2.0 * (f_gen[(p, q)] - f_gen[(q, p)]))
+            .collect()
+    }
+
+    /// Approximate diagonal of the orbital Hessian, G. Chaban, M. W. Schmidt and M. S.
+    /// Gordon, Theor. Chem. Acc. 97, 88 (1997)
+    fn diagonal_hessian(&self, it: &MacroIteration, pairs: &[(usize, usize)]) -> Vec<f64> {
+        let (n_core, n_occ) = (self.n_core, self.n_core + self.n_active);
+        let (f, f_gen, gamma) = (&it.f, &it.f_gen, &it.gamma);
+        pairs
+            .iter()
+            .map(|&(p, q)| {
+                let x = match (p < n_occ, q < n_core) {
+                    // active-core
+                    (true, true) => {
+                        let t = p - n_core;
+                        4.0 * f[(p, p)] - 4.0 * f[(q, q)] + 2.0 * gamma[(t, t)] * f[(q, q)]
+                            - 2.0 * f_gen[(p, p)]
+                    }
+                    // virtual-core
+                    (false, true) => 4.0 * f[(p, p)] - 4.0 * f[(q, q)],
+                    // virtual-active
+                    _ => {
+                        let t = q - n_core;
+                        2.0 * gamma[(t, t)] * f[(p, p)] - 2.0 * f_gen[(q, q)]
+                    }
+                };
+                x.max(0.05)
+            })
+            .collect()
+    }
+
+    /// C <- C exp(κ) with the antisymmetric κ_pq = -κ_qp of the step
+    fn rotate(&mut self, pairs: &[(usize, usize)], step: &[f64]) {
+        let n_mo = self.c.cols;
+        let mut kappa = FMatrix::zero(n_mo, n_mo);
+        for (&(p, q), x) in pairs.iter().zip(step) {
+            kappa[(p, q)] = *x;
+            kappa[(q, p)] = -x;
+        }
+        self.c = &self.c * &exp_antisymmetric(&kappa);
+    }
+
+    /// Natural orbitals of the active space and canonical core and virtual orbitals,
+    /// which leave the energy unchanged
+    fn natural_orbitals(&mut self, it: &MacroIteration) {
+        let n_mo = self.c.cols;
+        let (n_core, n_occ) = (self.n_core, self.n_core + self.n_active);
+        let mut u = FMatrix::zero(n_mo, n_mo);
+        let mut occupations = FVector::zero(n_mo);
+
+        // core and virtual blocks diagonalize F^I + F^A
+        for range in [0..n_core, n_occ..n_mo] {
+            if range.is_empty() {
+                continue;
+            }
+            let block =
+                it.f.slice(range.start, range.end - 1, range.start, range.end - 1);
+            let (_, v) = block.diagonalize_sym();
+            for (k, p) in range.clone().enumerate() {
+                for (l, q) in range.clone().enumerate() {
+                    u[(p, q)] = v[(k, l)];
+                }
+            }
+        }
+        for i in 0..n_core {
+            occupations[i] = 2.0;
+        }
+
+        // active block diagonalizes γ, largest occupation first
+        let (n, v) = (&it.gamma * -1.0).diagonalize_sym();
+        for t in 0..self.n_active {
+            occupations[n_core + t] = -n[t];
+            for s in 0..self.n_active {
+                u[(n_core + s, n_core + t)] = v[(s, t)];
+            }
+        }
+
+        let f = &u.transposed() * &(&it.f * &u);
+        self.c = &self.c * &u;
+        self.orbital_energies =
+            FVector::new_from_vec(&(0..n_mo).map(|p| f[(p, p)]).collect::<Vec<f64>>());
+        self.occupations = occupations;
+    }
+}
+
+/// F_pt = \sum_u F^I_pu γ_tu + \sum_uvw Γ_tuvw (pu|vw) of an active orbital t
+fn generalized_active(
+    f_inactive: &FMatrix,
+    gamma: &FMatrix,
+    big_gamma: &MOIntegrals,
+    puvw: &MOIntegrals,
+    p: usize,
+    t: usize,
+    n_core: usize,
+) -> f64 {
+    let n_active = gamma.rows;
+    let mut x = 0.0;
+    for u in 0..n_active {
+        x += f_inactive[(p, n_core + u)] * gamma[(t, u)];
+        for v in 0..n_active {
+            for w in 0..n_active {
+                x += big_gamma[(t, u, v, w)] * puvw[(p, u, v, w)];
+            }
+        }
+    }
+    x
+}
+
+/// Step x = -(H - ε)^-1 g of the augmented Hessian [[0, g^T], [g, H]] with a diagonal
+/// Hessian, ε is the lowest root of ε = -\sum_k g_k^2 / (H_k - ε). Steps longer than
+/// `max_step` are scaled down.
+fn augmented_hessian_step(gradient: &[f64], hessian: &[f64], max_step: f64) -> Vec<f64> {
+    let mut epsilon = 0.0;
+    for _ in 0..100 {
+        let next: f64 = -gradient
+            .iter()
+            .zip(hessian)
+            .map(|(g, h)| g * g / (h - epsilon))
+            .sum::<f64>();
+        let converged = (next - epsilon).abs() < 1e-14;
+        epsilon = next;
+        if converged {
+            break;
+        }
+    }
+
+    let mut step: Vec<f64> = gradient
+        .iter()
+        .zip(hessian)
+        .map(|(g, h)| -g / (h - epsilon))
+        .collect();
+    let norm = step.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > max_step {
+        step.iter_mut().for_each(|x| *x *= max_step / norm);
+    }
+    step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CASSCFOptions, CASSCF};
+    use crate::{
+        correlation::tests::rhf, geometry::atom::Atom, gto_basis_sets::BasisSet,
+        gto_integrals::nuclear_repulsion::nuclear_repulsion, misc::elements::Element::H,
+        tests::water,
+    };
+
+    #[test]
+    fn gradient() {
+        // analytic orbital gradient against central differences of the CASCI energy
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let casscf = CASSCF::new(rhf.orbitals.c(0), 2, 4, 2, 2);
+        let options = CASSCFOptions {
+            ci_threshold: 1e-10,
+            ..Default::default()
+        };
+        let pairs = casscf.rotation_pairs();
+        let it = casscf.macro_iteration(&rhf.h, &rhf.eri, &options);
+        let gradient = casscf.gradient(&it.f_gen, &pairs);
+
+        let delta = 1e-4;
+        let energy = |k: usize, x: f64| {
+            let mut displaced = casscf.clone();
+            let mut step = vec![0.0; pairs.len()];
+            step[k] = x;
+            displaced.rotate(&pairs, &step);
+            displaced.macro_iteration(&rhf.h, &rhf.eri, &options).energy
+        };
+        for k in [0, 3, 7, pairs.len() - 1] {
+            let numeric = (energy(k, delta) - energy(k, -delta)) / (2.0 * delta);
+            assert!(
+                (numeric - gradient[k]).abs() < 1e-6,
+                "{:?}: {} != {}",
+                pairs[k],
+                numeric,
+                gradient[k]
+            );
+        }
+    }
+
+    #[test]
+    fn no_iterations() {
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let mut casscf = CASSCF::new(rhf.orbitals.c(0), 3, 4, 2, 2);
+        let options = CASSCFOptions {
+            max_iter: 0,
+            ..Default::default()
+        };
+        assert!(!casscf.solve(&rhf.h, &rhf.eri, &options));
+        assert_eq!(casscf.e_casscf(), casscf.e_casci());
+        assert!(casscf.e_casci() < rhf.e_scf - nuclear_repulsion(&water()));
+    }
+
+    #[test]
+    fn water_6_31g() {
+        let rhf = rhf(&water(), &BasisSet::_6_31g);
+        let mut casscf = CASSCF::new(rhf.orbitals.c(0), 3, 4, 2, 2);
+        assert!(casscf.solve(&rhf.h, &rhf.eri, &CASSCFOptions::default()));
+
+        // orbital optimization lowers the CASCI energy
+        assert!(casscf.e_casscf() < casscf.e_casci() - 1e-4);
+        assert!(casscf.s2().abs() < 1e-6);
+
+        // orthonormal orbitals, occupations sum to the number of electrons
+        let orbitals = casscf.orbitals();
+        let c = orbitals.c(0);
+        let one = &c.transposed() * &(&rhf.s * c);
+        for p in 0..c.cols {
+            for q in 0..c.cols {
+                let expected = if p == q { 1.0 } else { 0.0 };
+                assert!((one[(p, q)] - expected).abs() < 1e-8);
+            }
+        }
+        assert!((orbitals.on(0).iter().sum::<f64>() - 10.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn dissociation() {
+        // CAS(2, 2) separates stretched H2 into two hydrogen atoms, unlike RHF
+        let atoms = vec![
+            Atom::new(H, [0.0, 0.0, 0.0]),
+            Atom::new(H, [0.0, 0.0, 10.0]),
+        ];
+        let rhf = rhf(&atoms, &BasisSet::_6_31g);
+        let mut casscf = CASSCF::new(rhf.orbitals.c(0), 0, 2, 1, 1);
+        assert!(casscf.solve(&rhf.h, &rhf.eri, &CASSCFOptions::default()));
+
+        let e_atom = -0.498232909;
+        let e_casscf = casscf.e_casscf() + nuclear_repulsion(&atoms);
+        assert!((e_casscf - 2.0 * e_atom).abs() < 1e-5, "{}", e_casscf);
+        assert!(rhf.e_scf > 2.0 * e_atom + 0.1);
+        assert!((casscf.occupations()[0] - 1.0).abs() < 1e-2);
+    }
+}
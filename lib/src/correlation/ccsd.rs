@@ -0,0 +1,637 @@
+use crate::{
+    data::orbitals::Orbitals,
+    gto_integrals::mo_transform::{mo_transform, orbital_space, MOIntegrals, TransformBuffer},
+    linear_algebra::{diis::DIIS, matrix::FMatrix, matrix_container::FMatrixContainer},
+};
+
+use std::time::Instant;
+
+/// Convergence settings of the amplitude iterations
+#[derive(Clone, Debug, PartialEq)]
+pub struct CCSDOptions {
+    pub max_iter: usize,
+    pub e_threshold: f64,
+    pub rms_threshold: f64,
+    pub diis_dim_max: usize,
+}
+
+impl Default for CCSDOptions {
+    fn default() -> Self {
+        Self {
+            max_iter: 100,
+            e_threshold: 1e-9,
+            rms_threshold: 1e-7,
+            diis_dim_max: 8,
+        }
+    }
+}
+
+/// Spin-orbital coupled-cluster singles and doubles on canonical restricted or
+/// unrestricted orbitals, J. F. Stanton, J. Gauss, J. D. Watts and R. J. Bartlett,
+/// J. Chem. Phys. 94, 4334 (1991). Spin orbitals are ordered as occupied alpha, occupied
+/// beta, virtual alpha and virtual beta; virtual orbitals are offset by the number of
+/// occupied ones in the antisymmetrized integrals <pq||rs>.
+pub struct CCSD {
+    n_o: usize,
+    n_v: usize,
+
+    e_occ: Vec<f64>,
+    e_virt: Vec<f64>,
+
+    // <pq||rs> over all correlated spin orbitals
+    integrals: MOIntegrals,
+
+    t1: FMatrix,
+    t2: MOIntegrals,
+
+    e_mp2: f64,
+    e_ccsd: f64,
+    converged: bool,
+}
+
+/// Getters
+impl CCSD {
+    /// MP2 correlation energy of the initial amplitudes
+    pub fn e_mp2(&self) -> f64 {
+        self.e_mp2
+    }
+
+    /// CCSD correlation energy of the current amplitudes
+    pub fn e_ccsd(&self) -> f64 {
+        self.e_ccsd
+    }
+
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+impl CCSD {
+    /// Antisymmetrized integrals and MP2 amplitudes as initial guess, the lowest
+    /// `n_frozen` orbitals of every spin are kept frozen and the half-transformed
+    /// integrals are kept in `buffer`
+    pub fn new(
+        orbitals: &Orbitals,
+        eri: &FMatrixContainer,
+        n_frozen: usize,
+        buffer: &TransformBuffer,
+    ) -> Self {
+        print!("Transforming integrals to spin orbitals ... ");
+        let t = Instant::now();
+
+        // (spin, active orbital) of the occupied and virtual spin orbitals
+        let op = |spin: usize| if orbitals.restricted() { 0 } else { spin };
+        let mut occ = Vec::new();
+        let mut virt = Vec::new();
+        for spin in 0..2 {
+            let n_mo = orbitals.c(op(spin)).cols;
+            let homo = orbitals.homo(op(spin));
+            occ.extend((n_frozen..homo).map(|i| (spin, i - n_frozen)));
+            virt.extend((homo..n_mo).map(|a| (spin, a - n_frozen)));
+        }
+        let energy = |&(spin, p): &(usize, usize)| orbitals.e(op(spin))[p + n_frozen];
+        let e_occ: Vec<f64> = occ.iter().map(energy).collect();
+        let e_virt: Vec<f64> = virt.iter().map(energy).collect();
+
+        // (pq|rs) of the active spatial orbitals for the spin pairs αα, αβ and ββ
+        let active: Vec<FMatrix> = (0..orbitals.num_op())
+            .map(|op| orbital_space(orbitals.c(op), n_frozen..orbitals.c(op).cols))
+            .collect();
+        let pairs = match orbitals.restricted() {
+            true => vec![(0, 0)],
+            false => vec![(0, 0), (0, 1), (1, 1)],
+        };
+        let mo: Vec<MOIntegrals> = pairs
+            .iter()
+            .map(|&(s1, s2)| {
+                let (c1, c2) = (&active[s1], &active[s2]);
+                mo_transform(eri, [c1, c1, c2, c2], buffer)
+            })
+            .collect();
+
+        let spin_orbitals: Vec<(usize, usize)> = occ.iter().chain(virt.iter()).copied().collect();
+        let chemist = |p: usize, q: usize, r: usize, s: usize| -> f64 {
+            let ((sp, p), (sq, q)) = (spin_orbitals[p], spin_orbitals[q]);
+            let ((sr, r), (ss, s)) = (spin_orbitals[r], spin_orbitals[s]);
+            if sp != sq || sr != ss {
+                return 0.0;
+            }
+            match (orbitals.restricted(), sp, sr) {
+                (true, _, _) => mo[0][(p, q, r, s)],
+                (false, 0, 0) => mo[0][(p, q, r, s)],
+                (false, 0, _) => mo[1][(p, q, r, s)],
+                (false, _, 0) => mo[1][(r, s, p, q)],
+                (false, _, _) => mo[2][(p, q, r, s)],
+            }
+        };
+
+        // <pq||rs> = (pr|qs) - (ps|qr)
+        let n = spin_orbitals.len();
+        let mut integrals = MOIntegrals::zero([n; 4]);
+        for p in 0..n {
+            for q in 0..n {
+                for r in 0..n {
+                    for s in 0..n {
+                        integrals[(p, q, r, s)] = chemist(p, r, q, s) - chemist(p, s, q, r);
+                    }
+                }
+            }
+        }
+        println!("done ({:?})", t.elapsed());
+
+        let (n_o, n_v) = (occ.len(), virt.len());
+        let mut ccsd = Self {
+            n_o,
+            n_v,
+            e_occ,
+            e_virt,
+            integrals,
+            t1: FMatrix::zero(n_o, n_v),
+            t2: MOIntegrals::zero([n_o, n_o, n_v, n_v]),
+            e_mp2: 0.0,
+            e_ccsd: 0.0,
+            converged: false,
+        };
+
+        // MP2 guess: t_ij^ab = <ij||ab> / D_ij^ab
+        for i in 0..n_o {
+            for j in 0..n_o {
+                for a in 0..n_v {
+                    for b in 0..n_v {
+                        ccsd.t2[(i, j, a, b)] =
+                            ccsd.v(i, j, n_o + a, n_o + b) / ccsd.denominator(&[i, j], &[a, b]);
+                    }
+                }
+            }
+        }
+        ccsd.e_mp2 = ccsd.energy();
+        ccsd.e_ccsd = ccsd.e_mp2;
+
+        ccsd
+    }
+
+    /// <pq||rs>, virtual orbitals offset by the number of occupied ones
+    fn v(&self, p: usize, q: usize, r: usize, s: usize) -> f64 {
+        self.integrals[(p, q, r, s)]
+    }
+
+    /// Orbital energy difference of occupied and virtual spin orbitals
+    fn denominator(&self, occ: &[usize], virt: &[usize]) -> f64 {
+        occ.iter().map(|i| self.e_occ[*i]).sum::<f64>()
+            - virt.iter().map(|a| self.e_virt[*a]).sum::<f64>()
+    }
+
+    /// Correlation energy of the current amplitudes
+    fn energy(&self) -> f64 {
+        let o = self.n_o;
+        let mut e = 0.0;
+        for i in 0..o {
+            for j in 0..o {
+                for a in 0..self.n_v {
+                    for b in 0..self.n_v {
+                        let v = self.v(i, j, o + a, o + b);
+                        e += 0.25 * v * self.t2[(i, j, a, b)]
+                            + 0.5 * v * self.t1[(i, a)] * self.t1[(j, b)];
+                    }
+                }
+            }
+        }
+        e
+    }
+
+    /// τ_ij^ab = t_ij^ab + s (t_i^a t_j^b - t_i^b t_j^a), with s = 1 for τ and s = 1/2
+    /// for τ~
+    fn tau(&self, scale: f64) -> MOIntegrals {
+        let mut tau = self.t2.clone();
+        for i in 0..self.n_o {
+            for j in 0..self.n_o {
+                for a in 0..self.n_v {
+                    for b in 0..self.n_v {
+                        tau[(i, j, a, b)] += scale
+                            * (self.t1[(i, a)] * self.t1[(j, b)]
+                                - self.t1[(i, b)] * self.t1[(j, a)]);
+                    }
+                }
+            }
+        }
+        tau
+    }
+
+    /// One iteration of the amplitude equations with canonical orbitals (diagonal Fock
+    /// matrix), returning the new amplitudes
+    fn update(&self) -> (FMatrix, MOIntegrals) {
+        let (o, nv) = (self.n_o, self.n_v);
+        let (t1, t2) = (&self.t1, &self.t2);
+        let tau = self.tau(1.0);
+        let tau_tilde = self.tau(0.5);
+
+        // --------------------------------
+        // one-particle intermediates
+        // --------------------------------
+        let mut f_ae = FMatrix::zero(nv, nv);
+        for a in 0..nv {
+            for e in 0..nv {
+                let mut x = 0.0;
+                for m in 0..o {
+                    for f in 0..nv {
+                        x += t1[(m, f)] * self.v(m, o + a, o + f, o + e);
+                        for n in 0..o {
+                            x -= 0.5 * tau_tilde[(m, n, a, f)] * self.v(m, n, o + e, o + f);
+                        }
+                    }
+                }
+                f_ae[(a, e)] = x;
+            }
+        }
+
+        let mut f_mi = FMatrix::zero(o, o);
+        for m in 0..o {
+            for i in 0..o {
+                let mut x = 0.0;
+                for n in 0..o {
+                    for e in 0..nv {
+                        x += t1[(n, e)] * self.v(m, n, i, o + e);
+                        for f in 0..nv {
+                            x += 0.5 * tau_tilde[(i, n, e, f)] * self.v(m, n, o + e, o + f);
+                        }
+                    }
+                }
+                f_mi[(m, i)] = x;
+            }
+        }
+
+        let mut f_me = FMatrix::zero(o, nv);
+        for m in 0..o {
+            for e in 0..nv {
+                let mut x = 0.0;
+                for n in 0..o {
+                    for f in 0..nv {
+                        x += t1[(n, f)] * self.v(m, n, o + e, o + f);
+                    }
+                }
+                f_me[(m, e)] = x;
+            }
+        }
+
+        // --------------------------------
+        // two-particle intermediates
+        // --------------------------------
+        let mut w_mnij = MOIntegrals::zero([o, o, o, o]);
+        for m in 0..o {
+            for n in 0..o {
+                for i in 0..o {
+                    for j in 0..o {
+                        let mut x = self.v(m, n, i, j);
+                        for e in 0..nv {
+                            x += t1[(j, e)] * self.v(m, n, i, o + e)
+                                - t1[(i, e)] * self.v(m, n, j, o + e);
+                            for f in 0..nv {
+                                x += 0.25 * tau[(i, j, e, f)] * self.v(m, n, o + e, o + f);
+                            }
+                        }
+                        w_mnij[(m, n, i, j)] = x;
+                    }
+                }
+            }
+        }
+
+        let mut w_mbej = MOIntegrals::zero([o, nv, nv, o]);
+        for m in 0..o {
+            for b in 0..nv {
+                for e in 0..nv {
+                    for j in 0..o {
+                        let mut x = self.v(m, o + b, o + e, j);
+                        for f in 0..nv {
+                            x += t1[(j, f)] * self.v(m, o + b, o + e, o + f);
+                        }
+                        for n in 0..o {
+                            x -= t1[(n, b)] * self.v(m, n, o + e, j);
+                            for f in 0..nv {
+                                x -= (0.5 * t2[(j, n, f, b)] + t1[(j, f)] * t1[(n, b)])
+                                    * self.v(m, n, o + e, o + f);
+                            }
+                        }
+                        w_mbej[(m, b, e, j)] = x;
+                    }
+                }
+            }
+        }
+
+        // --------------------------------
+        // singles
+        // --------------------------------
+        let mut t1_new = FMatrix::zero(o, nv);
+        for i in 0..o {
+            for a in 0..nv {
+                let mut x = 0.0;
+                for e in 0..nv {
+                    x += t1[(i, e)] * f_ae[(a, e)];
+                }
+                for m in 0..o {
+                    x -= t1[(m, a)] * f_mi[(m, i)];
+                    for e in 0..nv {
+                        x += t2[(i, m, a, e)] * f_me[(m, e)];
+                        x -= t1[(m, e)] * self.v(m, o + a, i, o + e);
+                        for f in 0..nv {
+                            x -= 0.5 * t2[(i, m, e, f)] * self.v(m, o + a, o + e, o + f);
+                        }
+                        for n in 0..o {
+                            x -= 0.5 * t2[(m, n, a, e)] * self.v(n, m, o + e, i);
+                        }
+                    }
+                }
+                t1_new[(i, a)] = x / self.denominator(&[i], &[a]);
+            }
+        }
+
+        // --------------------------------
+        // doubles
+        // --------------------------------
+        // F_be - 1/2 \sum_m t_m^b F_me and F_mj + 1/2 \sum_e t_j^e F_me
+        let mut f_be = f_ae.clone();
+        for b in 0..nv {
+            for e in 0..nv {
+                for m in 0..o {
+                    f_be[(b, e)] -= 0.5 * t1[(m, b)] * f_me[(m, e)];
+                }
+            }
+        }
+        let mut f_mj = f_mi.clone();
+        for m in 0..o {
+            for j in 0..o {
+                for e in 0..nv {
+                    f_mj[(m, j)] += 0.5 * t1[(j, e)] * f_me[(m, e)];
+                }
+            }
+        }
+
+        // terms before the permutations P(ij) and P(ab)
+        let mut x_ab = MOIntegrals::zero([o, o, nv, nv]);
+        let mut x_ij = MOIntegrals::zero([o, o, nv, nv]);
+        let mut x_ijab = MOIntegrals::zero([o, o, nv, nv]);
+        for i in 0..o {
+            for j in 0..o {
+                for a in 0..nv {
+                    for b in 0..nv {
+                        let mut ab = 0.0;
+                        let mut ij = 0.0;
+                        let mut ijab = 0.0;
+                        for e in 0..nv {
+                            ab += t2[(i, j, a, e)] * f_be[(b, e)];
+                            ij += t1[(i, e)] * self.v(o + a, o + b, o + e, j);
+                        }
+                        for m in 0..o {
+                            ij -= t2[(i, m, a, b)] * f_mj[(m, j)];
+                            ab -= t1[(m, a)] * self.v(m, o + b, i, j);
+                            for e in 0..nv {
+                                ijab += t2[(i, m, a, e)] * w_mbej[(m, b, e, j)]
+                                    - t1[(i, e)] * t1[(m, a)] * self.v(m, o + b, o + e, j);
+                            }
+                        }
+                        x_ab[(i, j, a, b)] = ab;
+                        x_ij[(i, j, a, b)] = ij;
+                        x_ijab[(i, j, a, b)] = ijab;
+                    }
+                }
+            }
+        }
+
+        let mut t2_new = MOIntegrals::zero([o, o, nv, nv]);
+        for i in 0..o {
+            for j in 0..o {
+                for a in 0..nv {
+                    for b in 0..nv {
+                        let mut x = self.v(i, j, o + a, o + b);
+                        x += x_ab[(i, j, a, b)] - x_ab[(i, j, b, a)];
+                        x += x_ij[(i, j, a, b)] - x_ij[(j, i, a, b)];
+                        x += x_ijab[(i, j, a, b)] - x_ijab[(j, i, a, b)] - x_ijab[(i, j, b, a)]
+                            + x_ijab[(j, i, b, a)];
+                        for m in 0..o {
+                            for n in 0..o {
+                                x += 0.5 * tau[(m, n, a, b)] * w_mnij[(m, n, i, j)];
+                            }
+                        }
+                        t2_new[(i, j, a, b)] = x;
+                    }
+                }
+            }
+        }
+
+        // 1/2 \sum_ef τ_ij^ef W_abef with W_abef built for one pair (a, b) at a time
+        let mut w_ef = FMatrix::zero(nv, nv);
+        for a in 0..nv {
+            for b in 0..nv {
+                for e in 0..nv {
+                    for f in 0..nv {
+                        let mut x = self.v(o + a, o + b, o + e, o + f);
+                        for m in 0..o {
+                            x -= t1[(m, b)] * self.v(o + a, m, o + e, o + f)
+                                - t1[(m, a)] * self.v(o + b, m, o + e, o + f);
+                            for n in 0..o {
+                                x += 0.25 * tau[(m, n, a, b)] * self.v(m, n, o + e, o + f);
+                            }
+                        }
+                        w_ef[(e, f)] = x;
+                    }
+                }
+
+                for i in 0..o {
+                    for j in 0..o {
+                        let mut x = 0.0;
+                        for e in 0..nv {
+                            for f in 0..nv {
+                                x += tau[(i, j, e, f)] * w_ef[(e, f)];
+                            }
+                        }
+                        t2_new[(i, j, a, b)] += 0.5 * x;
+                    }
+                }
+            }
+        }
+
+        for i in 0..o {
+            for j in 0..o {
+                for a in 0..nv {
+                    for b in 0..nv {
+                        t2_new[(i, j, a, b)] /= self.denominator(&[i, j], &[a, b]);
+                    }
+                }
+            }
+        }
+
+        (t1_new, t2_new)
+    }
+
+    /// Iterate the amplitude equations with DIIS extrapolation until the correlation
+    /// energy and the amplitudes are converged
+    pub fn solve(&mut self, options: &CCSDOptions) -> bool {
+        let mut diis = DIIS::new(options.diis_dim_max);
+        let n_t1 = self.t1.len();
+
+        println!(
+            "\nIter {:^16} {:^16} {:^16} {:^8}",
+            "E(corr)", "ΔE", "T(rms)", "Time"
+        );
+        for iter in 0..options.max_iter {
+            let t = Instant::now();
+            let (t1, t2) = self.update();
+
+            // DIIS on the amplitudes with their change as error vector
+            let mut amplitudes: Vec<f64> = t1.iter().chain(t2.iter()).copied().collect();
+            let error: Vec<f64> = amplitudes
+                .iter()
+                .zip(self.t1.iter().chain(self.t2.iter()))
+                .map(|(new, old)| new - old)
+                .collect();
+            let rms = (error.iter().map(|x| x * x).sum::<f64>() / error.len() as f64).sqrt();
+            diis.extrapolate(&mut amplitudes, &error);
+
+            self.t1 = FMatrix::new_from_vec(self.n_o, self.n_v, &amplitudes[..n_t1]);
+            self.t2.copy_from_slice(&amplitudes[n_t1..]);
+
+            let e_old = self.e_ccsd;
+            self.e_ccsd = self.energy();
+            let delta = self.e_ccsd - e_old;
+
+            println!(
+                "{:3} {:16.10} {:16.5e} {:16.5e} {:8.2?}",
+                iter,
+                self.e_ccsd,
+                delta,
+                rms,
+                t.elapsed()
+            );
+            if delta.abs() < options.e_threshold && rms < options.rms_threshold {
+                self.converged = true;
+                break;
+            }
+        }
+
+        if self.converged {
+            println!("Converged!\n");
+        } else {
+            println!(
+                "Warning: CCSD amplitudes not converged within {} iterations\n",
+                options.max_iter
+            );
+        }
+
+        self.converged
+    }
+
+    /// Perturbative triples correction (T) of the current amplitudes,
+    /// K. Raghavachari, G. W. Trucks, J. A. Pople and M. Head-Gordon,
+    /// Chem. Phys. Lett. 157, 479 (1989), summed over i < j < k and a < b < c
+    pub fn triples(&self) -> f64 {
+        let (o, nv) = (self.n_o, self.n_v);
+
+        // P(i/jk) P(a/bc) as permutations with their signs
+        let permutations =
+            |i: usize, j: usize, k: usize| [(1.0, i, j, k), (-1.0, j, i, k), (-1.0, k, j, i)];
+
+        let mut e_t = 0.0;
+        for i in 0..o {
+            for j in 0..i {
+                for k in 0..j {
+                    for a in 0..nv {
+                        for b in 0..a {
+                            for c in 0..b {
+                                let mut connected = 0.0;
+                                let mut disconnected = 0.0;
+                                for (s1, i, j, k) in permutations(i, j, k) {
+                                    for (s2, a, b, c) in permutations(a, b, c) {
+                                        let sign = s1 * s2;
+                                        let mut x = 0.0;
+                                        for e in 0..nv {
+                                            x += self.t2[(j, k, a, e)]
+                                                * self.v(o + e, i, o + b, o + c);
+                                        }
+                                        for m in 0..o {
+                                            x -= self.t2[(i, m, b, c)] * self.v(m, o + a, j, k);
+                                        }
+                                        connected += sign * x;
+                                        disconnected +=
+                                            sign * self.t1[(i, a)] * self.v(j, k, o + b, o + c);
+                                    }
+                                }
+
+                                e_t += connected * (connected + disconnected)
+                                    / self.denominator(&[i, j, k], &[a, b, c]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        e_t
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CCSDOptions, CCSD};
+    use crate::{
+        correlation::{mp2::mp2, tests::rhf},
+        data::orbitals::Orbitals,
+        gto_basis_sets::BasisSet,
+        gto_integrals::mo_transform::TransformBuffer,
+        tests::water,
+    };
+
+    #[test]
+    fn water_sto_3g() {
+        // reference energies of T. D. Crawford's programming projects #4-#6
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        assert!(
+            (rhf.e_scf - (-74.942079928192)).abs() < 1e-8,
+            "{}",
+            rhf.e_scf
+        );
+
+        let mut ccsd = CCSD::new(&rhf.orbitals, &rhf.eri, 0, &TransformBuffer::Memory);
+        assert!(
+            (ccsd.e_mp2() - (-0.049149636120)).abs() < 1e-9,
+            "{}",
+            ccsd.e_mp2()
+        );
+        assert!(
+            (ccsd.e_mp2() - mp2(&rhf.orbitals, &rhf.eri, 0, &TransformBuffer::Memory).total())
+                .abs()
+                < 1e-10
+        );
+
+        let options = CCSDOptions {
+            e_threshold: 1e-12,
+            rms_threshold: 1e-10,
+            ..Default::default()
+        };
+        assert!(ccsd.solve(&options));
+        assert!(
+            (ccsd.e_ccsd() - (-0.070680088376)).abs() < 1e-9,
+            "{}",
+            ccsd.e_ccsd()
+        );
+
+        let e_t = ccsd.triples();
+        assert!((e_t - (-0.000099877272)).abs() < 1e-10, "{}", e_t);
+    }
+
+    #[test]
+    fn unrestricted() {
+        // identical alpha and beta orbitals reproduce the restricted energies
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let (c, e) = (rhf.orbitals.c(0).clone(), rhf.orbitals.e(0).clone());
+        let n = rhf.orbitals.homo(0);
+        let uhf = Orbitals::new_unrestricted([c.clone(), c], [e.clone(), e], [n, n]);
+
+        let options = CCSDOptions::default();
+        let mut restricted = CCSD::new(&rhf.orbitals, &rhf.eri, 1, &TransformBuffer::Memory);
+        let mut unrestricted = CCSD::new(&uhf, &rhf.eri, 1, &TransformBuffer::Memory);
+        restricted.solve(&options);
+        unrestricted.solve(&options);
+
+        assert!((restricted.e_ccsd() - unrestricted.e_ccsd()).abs() < 1e-8);
+        assert!((restricted.triples() - unrestricted.triples()).abs() < 1e-10);
+    }
+}
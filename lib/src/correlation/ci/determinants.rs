@@ -0,0 +1,236 @@
+use std::fmt::{Display, Formatter};
+
+/// Occupation string of one spin, orbital p is occupied if bit p is set
+pub type OccupationString = u64;
+
+/// largest number of orbitals representable by an occupation string
+pub const MAX_ORBITALS: usize = 63;
+
+/// All strings of `n_electrons` electrons in `n_orbitals` orbitals in ascending order
+pub fn strings(n_orbitals: usize, n_electrons: usize) -> Vec<OccupationString> {
+    assert!(
+        n_orbitals <= MAX_ORBITALS,
+        "At most {} orbitals are supported",
+        MAX_ORBITALS
+    );
+    if n_electrons > n_orbitals {
+        return Vec::new();
+    }
+
+    // Gosper's hack: next larger integer with the same number of set bits
+    let mut strings = Vec::new();
+    let mut s: OccupationString = (1 << n_electrons) - 1;
+    while s < 1 << n_orbitals {
+        strings.push(s);
+        if s == 0 {
+            break;
+        }
+        let c = s & s.wrapping_neg();
+        let r = s + c;
+        s = (((r ^ s) >> 2) / c) | r;
+    }
+    strings
+}
+
+/// Binomial coefficient n over k, None if it overflows
+fn binomial(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    // C(n, i + 1) = C(n, i) (n - i) / (i + 1) is exact in every step
+    (0..k).try_fold(1usize, |x, i| Some(x.checked_mul(n - i)? / (i + 1)))
+}
+
+/// Occupied orbitals of a string in ascending order
+pub fn occupied(s: OccupationString) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |p| s & (1 << p) != 0)
+}
+
+/// Sign (-1)^n of moving an electron from orbital i to orbital a, with n the number of
+/// occupied orbitals in between
+pub fn excitation_sign(s: OccupationString, i: usize, a: usize) -> f64 {
+    let (low, high) = (i.min(a), i.max(a));
+    let between = s & ((1 << high) - 1) & !((1 << (low + 1)) - 1);
+    match between.count_ones() % 2 {
+        0 => 1.0,
+        _ => -1.0,
+    }
+}
+
+/// Slater determinant |alpha beta> with all alpha spin orbitals ordered before the beta
+/// ones
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Determinant {
+    pub alpha: OccupationString,
+    pub beta: OccupationString,
+}
+
+impl Determinant {
+    /// Number of electrons in different orbitals than in the other determinant
+    pub fn excitation_level(&self, other: &Determinant) -> usize {
+        ((self.alpha ^ other.alpha).count_ones() + (self.beta ^ other.beta).count_ones()) as usize
+            / 2
+    }
+}
+
+/// Determinant space of `n_alpha` and `n_beta` electrons in `n_orbitals` orbitals
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeterminantSpace {
+    n_orbitals: usize,
+    reference: Determinant,
+    determinants: Vec<Determinant>,
+}
+
+/// Getters
+impl DeterminantSpace {
+    pub fn n_orbitals(&self) -> usize {
+        self.n_orbitals
+    }
+
+    /// aufbau determinant, the first one of the space
+    pub fn reference(&self) -> &Determinant {
+        &self.reference
+    }
+
+    pub fn determinants(&self) -> &[Determinant] {
+        &self.determinants
+    }
+
+    pub fn dim(&self) -> usize {
+        self.determinants.len()
+    }
+}
+
+impl DeterminantSpace {
+    /// Number of determinants of a space without building it, None if it overflows
+    pub fn count(
+        n_orbitals: usize,
+        n_alpha: usize,
+        n_beta: usize,
+        max_excitation: Option<usize>,
+    ) -> Option<usize> {
+        // strings of one spin with k electrons excited from the aufbau string
+        let excited = |n_electrons: usize, k: usize| -> Option<usize> {
+            binomial(n_electrons, k)?.checked_mul(binomial(n_orbitals - n_electrons, k)?)
+        };
+        if n_alpha > n_orbitals || n_beta > n_orbitals {
+            return Some(0);
+        }
+
+        let max = max_excitation.unwrap_or(n_alpha + n_beta);
+        let mut count: usize = 0;
+        for k_alpha in 0..=max.min(n_alpha) {
+            for k_beta in 0..=(max - k_alpha).min(n_beta) {
+                let n = excited(n_alpha, k_alpha)?.checked_mul(excited(n_beta, k_beta)?)?;
+                count = count.checked_add(n)?;
+            }
+        }
+        Some(count)
+    }
+
+    /// Determinants up to `max_excitation` electrons excited from the aufbau determinant,
+    /// or all of them (full CI)
+    pub fn new(
+        n_orbitals: usize,
+        n_alpha: usize,
+        n_beta: usize,
+        max_excitation: Option<usize>,
+    ) -> Self {
+        let alpha = strings(n_orbitals, n_alpha);
+        let beta = strings(n_orbitals, n_beta);
+        let reference = Determinant {
+            alpha: alpha[0],
+            beta: beta[0],
+        };
+
+        let mut determinants: Vec<Determinant> = alpha
+            .iter()
+            .flat_map(|a| {
+                beta.iter().map(move |b| Determinant {
+                    alpha: *a,
+                    beta: *b,
+                })
+            })
+            .filter(|det| {
+                max_excitation.map_or(true, |max| det.excitation_level(&reference) <= max)
+            })
+            .collect();
+        determinants.sort_by_key(|det| det.excitation_level(&reference));
+
+        Self {
+            n_orbitals,
+            reference,
+            determinants,
+        }
+    }
+}
+
+/// Occupation of every orbital as 2, a (alpha), b (beta) or 0
+pub struct Occupation<'a>(pub &'a Determinant, pub usize);
+
+impl Display for Occupation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Occupation(det, n_orbitals) = self;
+        for p in 0..*n_orbitals {
+            let c = match (det.alpha & (1 << p) != 0, det.beta & (1 << p) != 0) {
+                (true, true) => '2',
+                (true, false) => 'a',
+                (false, true) => 'b',
+                (false, false) => '0',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{excitation_sign, strings, DeterminantSpace, Occupation, MAX_ORBITALS};
+
+    #[test]
+    fn enumeration() {
+        assert_eq!(
+            strings(4, 2),
+            vec![0b0011, 0b0101, 0b0110, 0b1001, 0b1010, 0b1100]
+        );
+        assert_eq!(strings(3, 0), vec![0]);
+        assert_eq!(strings(7, 5).len(), 21);
+
+        // water/STO-3G: reference, 2 x 10 singles, 2 x 10 same-spin and 100 opposite-spin
+        // doubles out of 441 determinants
+        assert_eq!(DeterminantSpace::new(7, 5, 5, None).dim(), 441);
+        assert_eq!(DeterminantSpace::new(7, 5, 5, Some(1)).dim(), 21);
+        let cisd = DeterminantSpace::new(7, 5, 5, Some(2));
+        assert_eq!(cisd.dim(), 1 + 20 + 2 * 10 + 100);
+        assert_eq!(Occupation(cisd.reference(), 7).to_string(), "2222200");
+    }
+
+    #[test]
+    fn count() {
+        for max_excitation in [Some(0), Some(1), Some(2), Some(3), None] {
+            for (n_orbitals, n_alpha, n_beta) in [(7, 5, 5), (6, 3, 2), (4, 0, 1)] {
+                let space = DeterminantSpace::new(n_orbitals, n_alpha, n_beta, max_excitation);
+                assert_eq!(
+                    DeterminantSpace::count(n_orbitals, n_alpha, n_beta, max_excitation),
+                    Some(space.dim())
+                );
+            }
+        }
+
+        // water/aug-cc-pVDZ: C(41, 5)^2
+        assert_eq!(
+            DeterminantSpace::count(41, 5, 5, None),
+            Some(749398 * 749398)
+        );
+        assert_eq!(DeterminantSpace::count(MAX_ORBITALS, 31, 31, None), None);
+    }
+
+    #[test]
+    fn sign() {
+        // a^+_3 a_0 |0 1 2> passes the electrons in orbitals 1 and 2
+        assert_eq!(excitation_sign(0b0111, 0, 3), 1.0);
+        assert_eq!(excitation_sign(0b0111, 1, 3), -1.0);
+        assert_eq!(excitation_sign(0b1011, 3, 2), 1.0);
+    }
+}
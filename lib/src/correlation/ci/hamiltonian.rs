@@ -0,0 +1,250 @@
+use crate::{
+    correlation::ci::determinants::{
+        excitation_sign, occupied, Determinant, DeterminantSpace, OccupationString,
+    },
+    gto_integrals::mo_transform::{mo_transform, orbital_space, MOIntegrals, TransformBuffer},
+    linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer},
+};
+
+/// Hamiltonian of an active space: the doubly occupied core orbitals enter through their
+/// energy and the effective one-electron operator h_pq + \sum_c [2 (pq|cc) - (pc|cq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActiveSpace {
+    e_core: f64,
+    h: FMatrix,
+    eri: MOIntegrals,
+}
+
+/// Getters
+impl ActiveSpace {
+    /// electronic energy of the core orbitals
+    pub fn e_core(&self) -> f64 {
+        self.e_core
+    }
+
+    /// effective one-electron integrals of the active orbitals
+    pub fn h(&self) -> &FMatrix {
+        &self.h
+    }
+
+    /// (pq|rs) of the active orbitals
+    pub fn eri(&self) -> &MOIntegrals {
+        &self.eri
+    }
+}
+
+impl ActiveSpace {
+    /// Active space of `n_active` orbitals after `n_core` core orbitals of the MO
+    /// coefficients c (AO x MO), with the core Hamiltonian h and the AO integrals eri.
+    /// The half-transformed integrals are kept in `buffer`.
+    pub fn new(
+        h: &FMatrix,
+        eri: &FMatrixContainer,
+        c: &FMatrix,
+        n_core: usize,
+        n_active: usize,
+        buffer: &TransformBuffer,
+    ) -> Self {
+        let n = n_core + n_active;
+        let c = orbital_space(c, 0..n);
+        let mo = mo_transform(eri, [&c; 4], buffer);
+        let h_mo = &c.transposed() * &(h * &c);
+
+        // core energy: \sum_c 2 h_cc + \sum_cd [2 (cc|dd) - (cd|dc)]
+        let mut e_core = 0.0;
+        for c in 0..n_core {
+            e_core += 2.0 * h_mo[(c, c)];
+            for d in 0..n_core {
+                e_core += 2.0 * mo[(c, c, d, d)] - mo[(c, d, d, c)];
+            }
+        }
+
+        let mut h_active = FMatrix::zero(n_active, n_active);
+        let mut eri_active = MOIntegrals::zero([n_active; 4]);
+        for p in 0..n_active {
+            for q in 0..n_active {
+                let (p_mo, q_mo) = (n_core + p, n_core + q);
+                h_active[(p, q)] = h_mo[(p_mo, q_mo)];
+                for c in 0..n_core {
+                    h_active[(p, q)] += 2.0 * mo[(p_mo, q_mo, c, c)] - mo[(p_mo, c, c, q_mo)];
+                }
+
+                for r in 0..n_active {
+                    for s in 0..n_active {
+                        eri_active[(p, q, r, s)] = mo[(p_mo, q_mo, n_core + r, n_core + s)];
+                    }
+                }
+            }
+        }
+
+        Self {
+            e_core,
+            h: h_active,
+            eri: eri_active,
+        }
+    }
+
+    /// Diagonal element of a determinant, Slater-Condon rule for identical determinants
+    pub fn diagonal(&self, det: &Determinant) -> f64 {
+        let (alpha, beta): (Vec<usize>, Vec<usize>) =
+            (occupied(det.alpha).collect(), occupied(det.beta).collect());
+        let j = |i: usize, k: usize| self.eri[(i, i, k, k)];
+        let k = |i: usize, k: usize| self.eri[(i, k, k, i)];
+
+        let mut e = 0.0;
+        for occ in [&alpha, &beta] {
+            for (n, i) in occ.iter().enumerate() {
+                e += self.h[(*i, *i)];
+                for l in occ.iter().take(n) {
+                    e += j(*i, *l) - k(*i, *l);
+                }
+            }
+        }
+        for i in &alpha {
+            for l in &beta {
+                e += j(*i, *l);
+            }
+        }
+        e
+    }
+
+    /// Matrix element <i -> a|H|0> of a single excitation i -> a in one spin, with the
+    /// occupations of both spins of the determinant |0>
+    fn single(&self, same: OccupationString, other: OccupationString, i: usize, a: usize) -> f64 {
+        let mut x = self.h[(a, i)];
+        for k in occupied(same) {
+            x += self.eri[(a, i, k, k)] - self.eri[(a, k, k, i)];
+        }
+        for k in occupied(other) {
+            x += self.eri[(a, i, k, k)];
+        }
+        excitation_sign(same, i, a) * x
+    }
+
+    /// Matrix element <bra|H|ket> between two determinants by the Slater-Condon rules
+    pub fn matrix_element(&self, bra: &Determinant, ket: &Determinant) -> f64 {
+        // holes: occupied in ket only, particles: occupied in bra only
+        let holes =
+            |b: OccupationString, k: OccupationString| -> Vec<usize> { occupied(k & !b).collect() };
+        let (holes_a, holes_b) = (holes(bra.alpha, ket.alpha), holes(bra.beta, ket.beta));
+        let (particles_a, particles_b) = (holes(ket.alpha, bra.alpha), holes(ket.beta, bra.beta));
+
+        match (holes_a.len(), holes_b.len()) {
+            (0, 0) => self.diagonal(ket),
+            (1, 0) => self.single(ket.alpha, ket.beta, holes_a[0], particles_a[0]),
+            (0, 1) => self.single(ket.beta, ket.alpha, holes_b[0], particles_b[0]),
+            (1, 1) => {
+                let (i, a) = (holes_a[0], particles_a[0]);
+                let (j, b) = (holes_b[0], particles_b[0]);
+                excitation_sign(ket.alpha, i, a)
+                    * excitation_sign(ket.beta, j, b)
+                    * self.eri[(a, i, b, j)]
+            }
+            (2, 0) => self.same_spin_double(ket.alpha, &holes_a, &particles_a),
+            (0, 2) => self.same_spin_double(ket.beta, &holes_b, &particles_b),
+            _ => 0.0,
+        }
+    }
+
+    /// <ij -> ab|H|0> = <ab||ij> for two excitations in one spin
+    fn same_spin_double(&self, s: OccupationString, holes: &[usize], particles: &[usize]) -> f64 {
+        let ([i, j], [a, b]) = ([holes[0], holes[1]], [particles[0], particles[1]]);
+        let sign = excitation_sign(s, i, a) * excitation_sign(s & !(1 << i) | (1 << a), j, b);
+        sign * (self.eri[(a, i, b, j)] - self.eri[(a, j, b, i)])
+    }
+
+    /// Sparse Hamiltonian matrix of a determinant space, one list of (column, value) per
+    /// row without the core energy
+    pub fn matrix(&self, space: &DeterminantSpace) -> Vec<Vec<(usize, f64)>> {
+        let determinants = space.determinants();
+        let mut rows = vec![Vec::new(); determinants.len()];
+        for (m, bra) in determinants.iter().enumerate() {
+            for (n, ket) in determinants.iter().enumerate().take(m + 1) {
+                if bra.excitation_level(ket) > 2 {
+                    continue;
+                }
+                let x = self.matrix_element(bra, ket);
+                if x.abs() < 1e-14 {
+                    continue;
+                }
+                rows[m].push((n, x));
+                if m != n {
+                    rows[n].push((m, x));
+                }
+            }
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActiveSpace;
+    use crate::{
+        correlation::{
+            ci::determinants::{Determinant, DeterminantSpace},
+            tests::rhf,
+        },
+        gto_basis_sets::BasisSet,
+        gto_integrals::{mo_transform::TransformBuffer, nuclear_repulsion::nuclear_repulsion},
+        linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix},
+        tests::water,
+    };
+
+    #[test]
+    fn reference_energy() {
+        // the aufbau determinant reproduces the SCF energy with and without a core
+        let atoms = water();
+        let rhf = rhf(&atoms, &BasisSet::sto_3g);
+        let c = rhf.orbitals.c(0);
+        let e_nuc = nuclear_repulsion(&atoms);
+
+        let full = ActiveSpace::new(&rhf.h, &rhf.eri, c, 0, 7, &TransformBuffer::Memory);
+        let reference = Determinant {
+            alpha: 0b11111,
+            beta: 0b11111,
+        };
+        assert!((full.diagonal(&reference) + e_nuc - rhf.e_scf).abs() < 1e-10);
+
+        let core = ActiveSpace::new(&rhf.h, &rhf.eri, c, 2, 5, &TransformBuffer::Memory);
+        let reference = Determinant {
+            alpha: 0b111,
+            beta: 0b111,
+        };
+        assert!((core.e_core() + core.diagonal(&reference) + e_nuc - rhf.e_scf).abs() < 1e-10);
+    }
+
+    #[test]
+    fn hermitian() {
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let active = ActiveSpace::new(
+            &rhf.h,
+            &rhf.eri,
+            rhf.orbitals.c(0),
+            1,
+            6,
+            &TransformBuffer::Memory,
+        );
+        let space = DeterminantSpace::new(6, 4, 4, None);
+
+        // the sparse matrix equals the element-wise one and is symmetric
+        let rows = active.matrix(&space);
+        let mut h = FMatrix::zero(space.dim(), space.dim());
+        for (m, row) in rows.iter().enumerate() {
+            for (n, x) in row {
+                h[(m, *n)] = *x;
+            }
+        }
+        let dets = space.determinants();
+        for m in 0..space.dim() {
+            for n in 0..space.dim() {
+                assert!((h[(m, n)] - active.matrix_element(&dets[m], &dets[n])).abs() < 1e-12);
+                assert!((h[(m, n)] - h[(n, m)]).abs() < 1e-12);
+            }
+        }
+
+        // the lowest eigenvalue of the full CI matrix lies below the reference energy
+        let (e, _) = h.diagonalize_sym();
+        assert!(e[0] < active.diagonal(space.reference()) - 0.01);
+    }
+}
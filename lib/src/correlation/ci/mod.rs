@@ -0,0 +1,364 @@
+//! Configuration interaction in a space of Slater determinants built from occupation
+//! strings, with Slater-Condon matrix elements and a Davidson eigensolver
+
+pub mod determinants;
+pub mod hamiltonian;
+pub mod rdm;
+
+use crate::{
+    correlation::ci::{
+        determinants::{occupied, Determinant, DeterminantSpace},
+        hamiltonian::ActiveSpace,
+    },
+    linear_algebra::{
+        davidson::{davidson, DavidsonOptions},
+        lanczos::{lanczos, LanczosOptions},
+        vector::FVector,
+    },
+};
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    str::FromStr,
+    time::Instant,
+};
+
+/// Truncation of the determinant space
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CIType {
+    CIS,
+    CISD,
+    #[default]
+    FCI,
+}
+
+impl CIType {
+    /// highest excitation level from the reference determinant
+    pub fn max_excitation(&self) -> Option<usize> {
+        match self {
+            CIType::CIS => Some(1),
+            CIType::CISD => Some(2),
+            CIType::FCI => None,
+        }
+    }
+}
+
+impl FromStr for CIType {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ci = match s.to_uppercase().as_str() {
+            "CIS" => CIType::CIS,
+            "CISD" => CIType::CISD,
+            "FCI" => CIType::FCI,
+            _ => return Err("Unknown CI type"),
+        };
+
+        Ok(ci)
+    }
+}
+
+impl Display for CIType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CIType::CIS => write!(f, "CIS"),
+            CIType::CISD => write!(f, "CISD"),
+            CIType::FCI => write!(f, "FCI"),
+        }
+    }
+}
+
+/// Eigensolver of the CI Hamiltonian
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CISolver {
+    /// lowest roots of any symmetry
+    #[default]
+    Davidson,
+    /// lowest roots in the Krylov space of the reference determinant, i.e. of its spin
+    /// and spatial symmetry only
+    Lanczos,
+}
+
+impl FromStr for CISolver {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let solver = match s.to_uppercase().as_str() {
+            "DAVIDSON" => CISolver::Davidson,
+            "LANCZOS" => CISolver::Lanczos,
+            _ => return Err("Unknown CI solver"),
+        };
+
+        Ok(solver)
+    }
+}
+
+impl Display for CISolver {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CISolver::Davidson => write!(f, "Davidson"),
+            CISolver::Lanczos => write!(f, "Lanczos"),
+        }
+    }
+}
+
+/// Number of roots and convergence settings of the eigensolver
+#[derive(Clone, Debug, PartialEq)]
+pub struct CIOptions {
+    pub n_roots: usize,
+    pub max_iter: usize,
+    pub threshold: f64,
+    pub solver: CISolver,
+}
+
+impl Default for CIOptions {
+    fn default() -> Self {
+        Self {
+            n_roots: 1,
+            max_iter: 100,
+            threshold: 1e-6,
+            solver: CISolver::Davidson,
+        }
+    }
+}
+
+/// Eigenstate of the CI Hamiltonian
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CIState {
+    /// electronic energy including the core energy
+    pub energy: f64,
+    /// expectation value <S^2>
+    pub s2: f64,
+    /// coefficients of the determinants of the space
+    pub coefficients: Vec<f64>,
+}
+
+/// Lowest eigenstates of the Hamiltonian of an active space in a determinant space
+pub fn ci(active: &ActiveSpace, space: &DeterminantSpace, options: &CIOptions) -> Vec<CIState> {
+    print!(
+        "Building CI Hamiltonian ({} determinants) ... ",
+        space.dim()
+    );
+    let t = Instant::now();
+    let rows = active.matrix(space);
+    let n_elements: usize = rows.iter().map(|row| row.len()).sum();
+    println!("done ({:?}), {} non-zero elements", t.elapsed(), n_elements);
+
+    let diagonal: Vec<f64> = space
+        .determinants()
+        .iter()
+        .map(|det| active.diagonal(det))
+        .collect();
+    let sigma = |c: &FVector| -> FVector {
+        let sigma: Vec<f64> = rows
+            .iter()
+            .map(|row| row.iter().map(|(n, x)| x * c[*n]).sum())
+            .collect();
+        FVector::new_from_vec(&sigma)
+    };
+
+    let (energies, vectors) = match options.solver {
+        CISolver::Davidson => {
+            let davidson_options = DavidsonOptions {
+                n_roots: options.n_roots,
+                max_iter: options.max_iter,
+                threshold: options.threshold,
+                ..Default::default()
+            };
+            davidson(&FVector::new_from_vec(&diagonal), sigma, &davidson_options)
+        }
+        CISolver::Lanczos => {
+            let lanczos_options = LanczosOptions {
+                n_roots: options.n_roots,
+                max_iter: options.max_iter,
+                threshold: options.threshold,
+            };
+            // the reference is the first determinant of the space
+            let mut start = FVector::zero(space.dim());
+            start[0] = 1.0;
+            lanczos(&start, sigma, &lanczos_options)
+        }
+    };
+
+    energies
+        .iter()
+        .enumerate()
+        .map(|(root, e)| {
+            let coefficients: Vec<f64> = vectors.col(root).copied().collect();
+            CIState {
+                energy: e + active.e_core(),
+                s2: spin_square(space, &coefficients),
+                coefficients,
+            }
+        })
+        .collect()
+}
+
+/// <S^2> = S_z (S_z + 1) + |S_+ Ψ|^2 of a CI vector
+pub fn spin_square(space: &DeterminantSpace, coefficients: &[f64]) -> f64 {
+    let reference = space.reference();
+    let n_alpha = reference.alpha.count_ones() as usize;
+    let sz = 0.5 * (n_alpha as f64 - reference.beta.count_ones() as f64);
+
+    // S_+ = \sum_p a^+_pα a_pβ on every determinant
+    let mut raised: HashMap<Determinant, f64> = HashMap::new();
+    for (det, c) in space.determinants().iter().zip(coefficients) {
+        for p in occupied(det.beta & !det.alpha) {
+            // a_pβ passes all alpha and the lower beta electrons, a^+_pα the lower alpha ones
+            let below = (1 << p) - 1;
+            let n_passed = n_alpha + (det.beta & below).count_ones() as usize;
+            let mut sign = if n_passed % 2 == 0 { 1.0 } else { -1.0 };
+            if (det.alpha & below).count_ones() % 2 == 1 {
+                sign = -sign;
+            }
+
+            let target = Determinant {
+                alpha: det.alpha | (1 << p),
+                beta: det.beta & !(1 << p),
+            };
+            *raised.entry(target).or_insert(0.0) += sign * c;
+        }
+    }
+
+    sz * (sz + 1.0) + raised.values().map(|x| x * x).sum::<f64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ci, CIOptions, CISolver, CIType};
+    use crate::{
+        correlation::{
+            ccsd::{CCSDOptions, CCSD},
+            ci::{determinants::DeterminantSpace, hamiltonian::ActiveSpace},
+            tests::rhf,
+        },
+        geometry::atom::Atom,
+        gto_basis_sets::BasisSet,
+        gto_integrals::{mo_transform::TransformBuffer, nuclear_repulsion::nuclear_repulsion},
+        misc::elements::Element::H,
+        tests::water,
+    };
+
+    #[test]
+    fn water_sto_3g() {
+        let atoms = water();
+        let rhf = rhf(&atoms, &BasisSet::sto_3g);
+        let active = ActiveSpace::new(
+            &rhf.h,
+            &rhf.eri,
+            rhf.orbitals.c(0),
+            0,
+            7,
+            &TransformBuffer::Memory,
+        );
+        let e_nuc = nuclear_repulsion(&atoms);
+        let energy = |ci_type: CIType, n_roots: usize| {
+            let space = DeterminantSpace::new(7, 5, 5, ci_type.max_excitation());
+            let options = CIOptions {
+                n_roots,
+                threshold: 1e-8,
+                ..Default::default()
+            };
+            ci(&active, &space, &options)
+        };
+
+        // Brillouin: the CIS ground state is the Hartree-Fock determinant, followed by
+        // triplet and singlet excitations
+        let cis = energy(CIType::CIS, 4);
+        assert!((cis[0].energy + e_nuc - rhf.e_scf).abs() < 1e-9);
+        for state in &cis {
+            assert!(state.s2.abs() < 1e-6 || (state.s2 - 2.0).abs() < 1e-6);
+        }
+        assert!(cis.iter().any(|state| (state.s2 - 2.0).abs() < 1e-6));
+
+        // CISD and FCI from an independent determinant code, which applies the
+        // second-quantized Hamiltonian to bit strings, with the integrals of this geometry
+        let e_cisd = energy(CIType::CISD, 1)[0].energy + e_nuc;
+        let fci = energy(CIType::FCI, 1);
+        let e_fci = fci[0].energy + e_nuc;
+        assert!((e_cisd - -75.011222999811).abs() < 1e-8, "{}", e_cisd);
+        assert!((e_fci - -75.012980198443).abs() < 1e-8, "{}", e_fci);
+        assert!(fci[0].s2.abs() < 1e-6);
+
+        // FCI close to CCSD(T)
+        let e_ccsd_t = -74.942079928192 - 0.070680088376 - 0.000099877272;
+        assert!((e_fci - e_ccsd_t).abs() < 2e-4, "{}", e_fci);
+
+        // Lanczos from the closed-shell reference finds the same ground state but singlets
+        // only, the Davidson roots include triplets
+        let space = DeterminantSpace::new(7, 5, 5, None);
+        let options = CIOptions {
+            n_roots: 3,
+            threshold: 1e-8,
+            solver: CISolver::Lanczos,
+            ..Default::default()
+        };
+        let singlets = ci(&active, &space, &options);
+        assert!((singlets[0].energy + e_nuc - e_fci).abs() < 1e-9);
+        assert!(singlets.iter().all(|state| state.s2.abs() < 1e-6));
+    }
+
+    #[test]
+    fn h2_sto_3g() {
+        // minimal basis H2 at 1.4 Bohr, A. Szabo and N. S. Ostlund, Modern Quantum
+        // Chemistry, Sec. 4.1: E(HF) = -1.1167 and E(corr) = -0.0206
+        let atoms = vec![Atom::new(H, [0.0, 0.0, 0.0]), Atom::new(H, [0.0, 0.0, 1.4])];
+        let rhf = rhf(&atoms, &BasisSet::sto_3g);
+        let active = ActiveSpace::new(
+            &rhf.h,
+            &rhf.eri,
+            rhf.orbitals.c(0),
+            0,
+            2,
+            &TransformBuffer::Memory,
+        );
+        let space = DeterminantSpace::new(2, 1, 1, None);
+        let options = CIOptions {
+            threshold: 1e-10,
+            ..Default::default()
+        };
+        let e_fci = ci(&active, &space, &options)[0].energy + nuclear_repulsion(&atoms);
+        assert!((rhf.e_scf - -1.1167).abs() < 1e-4, "{}", rhf.e_scf);
+        assert!((e_fci - rhf.e_scf - -0.0206).abs() < 1e-4, "{}", e_fci);
+
+        // closed form of the two-determinant problem |1α1β>, |2α2β>
+        let (h, eri) = (active.h(), active.eri());
+        let e_1 = 2.0 * h[(0, 0)] + eri[(0, 0, 0, 0)];
+        let e_2 = 2.0 * h[(1, 1)] + eri[(1, 1, 1, 1)];
+        let k = eri[(0, 1, 0, 1)];
+        let e_exact = 0.5 * (e_1 + e_2) - (0.25 * (e_2 - e_1).powi(2) + k * k).sqrt();
+        assert!((e_fci - e_exact - nuclear_repulsion(&atoms)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn two_electrons() {
+        // CCSD is exact for two electrons
+        let atoms = vec![Atom::new(H, [0.0, 0.0, 0.0]), Atom::new(H, [0.0, 0.0, 1.4])];
+        let rhf = rhf(&atoms, &BasisSet::cc_pvdz);
+        let n = rhf.orbitals.c(0).cols;
+        let active = ActiveSpace::new(
+            &rhf.h,
+            &rhf.eri,
+            rhf.orbitals.c(0),
+            0,
+            n,
+            &TransformBuffer::Memory,
+        );
+        let space = DeterminantSpace::new(n, 1, 1, None);
+        let options = CIOptions {
+            threshold: 1e-8,
+            ..Default::default()
+        };
+        let e_fci = ci(&active, &space, &options)[0].energy + nuclear_repulsion(&atoms);
+
+        let mut ccsd = CCSD::new(&rhf.orbitals, &rhf.eri, 0, &TransformBuffer::Memory);
+        let options = CCSDOptions {
+            e_threshold: 1e-12,
+            rms_threshold: 1e-10,
+            ..Default::default()
+        };
+        ccsd.solve(&options);
+        assert!((e_fci - rhf.e_scf - ccsd.e_ccsd()).abs() < 1e-9);
+    }
+}
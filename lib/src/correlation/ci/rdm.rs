@@ -0,0 +1,141 @@
+use crate::{
+    correlation::ci::determinants::{excitation_sign, Determinant, DeterminantSpace},
+    gto_integrals::mo_transform::MOIntegrals,
+    linear_algebra::matrix::FMatrix,
+};
+
+use std::collections::HashMap;
+
+/// E_pq |Ψ> = \sum_σ a^+_pσ a_qσ |Ψ> of a CI vector in a space closed under the
+/// excitation (e.g. full CI)
+fn excite(
+    space: &DeterminantSpace,
+    index: &HashMap<Determinant, usize>,
+    coefficients: &[f64],
+    p: usize,
+    q: usize,
+) -> Vec<f64> {
+    let mut x = vec![0.0; coefficients.len()];
+    for (det, c) in space.determinants().iter().zip(coefficients) {
+        for beta in [false, true] {
+            let s = if beta { det.beta } else { det.alpha };
+            if s & (1 << q) == 0 || (p != q && s & (1 << p) != 0) {
+                continue;
+            }
+
+            let t = s & !(1 << q) | (1 << p);
+            let target = match beta {
+                false => Determinant {
+                    alpha: t,
+                    beta: det.beta,
+                },
+                true => Determinant {
+                    alpha: det.alpha,
+                    beta: t,
+                },
+            };
+            let sign = if p == q {
+                1.0
+            } else {
+                excitation_sign(s, q, p)
+            };
+            x[index[&target]] += sign * c;
+        }
+    }
+    x
+}
+
+/// Spin-summed one- and two-particle density matrices of a CI vector,
+/// γ_pq = <Ψ|E_pq|Ψ> and Γ_pqrs = <Ψ|E_pq E_rs|Ψ> - δ_qr γ_ps, such that
+/// E = \sum_pq h_pq γ_pq + 1/2 \sum_pqrs (pq|rs) Γ_pqrs
+pub fn density_matrices(space: &DeterminantSpace, coefficients: &[f64]) -> (FMatrix, MOIntegrals) {
+    let n = space.n_orbitals();
+    let index: HashMap<Determinant, usize> = space
+        .determinants()
+        .iter()
+        .enumerate()
+        .map(|(k, det)| (*det, k))
+        .collect();
+
+    // E_pq |Ψ> for all pairs
+    let excited: Vec<Vec<f64>> = (0..n * n)
+        .map(|pq| excite(space, &index, coefficients, pq / n, pq % n))
+        .collect();
+    let dot = |x: &[f64], y: &[f64]| -> f64 { x.iter().zip(y).map(|(a, b)| a * b).sum() };
+
+    let mut gamma = FMatrix::zero(n, n);
+    for p in 0..n {
+        for q in 0..n {
+            gamma[(p, q)] = dot(coefficients, &excited[p * n + q]);
+        }
+    }
+
+    // <Ψ|E_pq E_rs|Ψ> = <E_qp Ψ|E_rs Ψ>
+    let mut big_gamma = MOIntegrals::zero([n; 4]);
+    for p in 0..n {
+        for q in 0..n {
+            for r in 0..n {
+                for s in 0..n {
+                    let mut x = dot(&excited[q * n + p], &excited[r * n + s]);
+                    if q == r {
+                        x -= gamma[(p, s)];
+                    }
+                    big_gamma[(p, q, r, s)] = x;
+                }
+            }
+        }
+    }
+
+    (gamma, big_gamma)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::density_matrices;
+    use crate::{
+        correlation::{
+            ci::{ci, determinants::DeterminantSpace, hamiltonian::ActiveSpace, CIOptions},
+            tests::rhf,
+        },
+        gto_basis_sets::BasisSet,
+        gto_integrals::mo_transform::TransformBuffer,
+        tests::water,
+    };
+
+    #[test]
+    fn energy() {
+        // the density matrices reproduce the CASCI energy and the electron count
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let active = ActiveSpace::new(
+            &rhf.h,
+            &rhf.eri,
+            rhf.orbitals.c(0),
+            2,
+            5,
+            &TransformBuffer::Memory,
+        );
+        let space = DeterminantSpace::new(5, 3, 3, None);
+        let options = CIOptions {
+            threshold: 1e-9,
+            ..Default::default()
+        };
+        let state = &ci(&active, &space, &options)[0];
+        let (gamma, big_gamma) = density_matrices(&space, &state.coefficients);
+
+        let trace: f64 = (0..5).map(|p| gamma[(p, p)]).sum();
+        assert!((trace - 6.0).abs() < 1e-10);
+
+        let mut e = active.e_core();
+        for p in 0..5 {
+            for q in 0..5 {
+                e += active.h()[(p, q)] * gamma[(p, q)];
+                for r in 0..5 {
+                    for s in 0..5 {
+                        e += 0.5 * active.eri()[(p, q, r, s)] * big_gamma[(p, q, r, s)];
+                    }
+                }
+            }
+        }
+        assert!((e - state.energy).abs() < 1e-9, "{} != {}", e, state.energy);
+    }
+}
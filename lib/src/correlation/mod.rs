@@ -0,0 +1,76 @@
+//! Electron correlation methods on top of converged SCF orbitals
+
+pub mod casscf;
+pub mod ccsd;
+pub mod ci;
+pub mod mp2;
+pub mod ri_mp2;
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{
+        data::orbitals::Orbitals,
+        geometry::atom::Atom,
+        gto_basis_sets::{basis::Basis, load_basis_set, BasisSet},
+        gto_integrals::{
+            integral_interface::IntegralInterface, nuclear_repulsion::nuclear_repulsion,
+            one_electron::OneElectronKernel, two_electron::TwoElectronKernel,
+        },
+        linear_algebra::{
+            diagonalize::DiagonalizeSym, matrix::FMatrix, matrix_container::FMatrixContainer,
+            power::PowerSym, traits::Dot,
+        },
+        scf::fock::fock,
+    };
+
+    /// Converged closed-shell RHF reference with its basis and AO integrals
+    pub(crate) struct Reference {
+        pub(crate) basis: Basis,
+        pub(crate) orbitals: Orbitals,
+        pub(crate) s: FMatrix,
+        pub(crate) h: FMatrix,
+        pub(crate) eri: FMatrixContainer,
+        pub(crate) e_scf: f64,
+    }
+
+    /// RHF from plain Roothaan iterations
+    pub(crate) fn rhf(atoms: &[Atom], basis_set: &BasisSet) -> Reference {
+        let basis = load_basis_set(basis_set, atoms);
+        let integrals = IntegralInterface::new(&basis, atoms);
+        let s = integrals.compute_one_electron_integral(OneElectronKernel::Overlap);
+        let h = integrals.compute_one_electron_integral(OneElectronKernel::HCore);
+        let eri = integrals.compute_two_electron_integral(TwoElectronKernel::ERI);
+
+        let n_electrons = atoms.iter().map(|atom| usize::from(atom.z())).sum();
+        let x = s.powf_sym(-0.5);
+        let n = basis.dim();
+        let fock = |d: &FMatrix| -> FMatrix { fock(d, &h, &eri, 1.0, 0.5) };
+
+        let mut d = FMatrix::zero(n, n);
+        let mut orbitals = None;
+        let mut energy = 0.0;
+        for _ in 0..200 {
+            let f = fock(&d);
+            let (e, c) = (&x * &(&f * &x)).diagonalize_sym();
+            let next = Orbitals::new_restricted(&x * &c, e, n_electrons);
+            let d_old = std::mem::replace(&mut d, next.density(0));
+            orbitals = Some(next);
+
+            let e_old = energy;
+            energy = 0.5 * d.dot(&(&h + fock(&d))) + nuclear_repulsion(atoms);
+            let change = d.iter().zip(d_old.iter()).map(|(x, y)| (x - y).abs());
+            if (energy - e_old).abs() < 1e-12 && change.fold(0.0, f64::max) < 1e-10 {
+                break;
+            }
+        }
+
+        Reference {
+            basis,
+            orbitals: orbitals.unwrap(),
+            s,
+            h,
+            eri,
+            e_scf: energy,
+        }
+    }
+}
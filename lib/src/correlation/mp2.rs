@@ -0,0 +1,248 @@
+use crate::{
+    data::orbitals::Orbitals,
+    gto_integrals::mo_transform::{mo_transform, orbital_space, MOIntegrals, TransformBuffer},
+    linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer},
+};
+
+/// Spin components of the MP2 correlation energy
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MP2Energy {
+    /// opposite-spin (αβ) pairs
+    pub os: f64,
+    /// same-spin (αα and ββ) pairs
+    pub ss: f64,
+}
+
+impl MP2Energy {
+    pub fn total(&self) -> f64 {
+        self.os + self.ss
+    }
+
+    /// Spin-component scaled energy c_os E_os + c_ss E_ss
+    pub fn scaled(&self, c_os: f64, c_ss: f64) -> f64 {
+        c_os * self.os + c_ss * self.ss
+    }
+
+    /// SCS-MP2, S. Grimme, J. Chem. Phys. 118, 9095 (2003)
+    pub fn scs(&self) -> f64 {
+        self.scaled(6.0 / 5.0, 1.0 / 3.0)
+    }
+
+    /// SOS-MP2, Y. Jung et al., J. Chem. Phys. 121, 9793 (2004)
+    pub fn sos(&self) -> f64 {
+        self.scaled(1.3, 0.0)
+    }
+}
+
+/// Correlated occupied and virtual orbitals of one spin
+pub(crate) struct OrbitalSpace {
+    pub(crate) c_occ: FMatrix,
+    pub(crate) c_virt: FMatrix,
+    pub(crate) e_occ: Vec<f64>,
+    pub(crate) e_virt: Vec<f64>,
+}
+
+impl OrbitalSpace {
+    pub(crate) fn new(orbitals: &Orbitals, op: usize, n_frozen: usize) -> Self {
+        let n_mo = orbitals.c(op).cols;
+        let occ = n_frozen..orbitals.homo(op);
+        let virt = orbitals.homo(op)..n_mo;
+
+        Self {
+            c_occ: orbital_space(orbitals.c(op), occ.clone()),
+            c_virt: orbital_space(orbitals.c(op), virt.clone()),
+            e_occ: occ.map(|i| orbitals.e(op)[i]).collect(),
+            e_virt: virt.map(|a| orbitals.e(op)[a]).collect(),
+        }
+    }
+}
+
+/// Integrals (ia|jb) of two orbital spaces, one virtual-virtual block per occupied pair
+pub(crate) trait PairIntegrals {
+    fn block(&self, i: usize, j: usize) -> FMatrix;
+}
+
+impl PairIntegrals for MOIntegrals {
+    fn block(&self, i: usize, j: usize) -> FMatrix {
+        let [_, n_a, _, n_b] = self.dims();
+        let mut block = FMatrix::zero(n_a, n_b);
+        for a in 0..n_a {
+            for b in 0..n_b {
+                block[(a, b)] = self[(i, a, j, b)];
+            }
+        }
+        block
+    }
+}
+
+/// MP2 energy from the (ia|jb) integrals of the spin pairs (op1, op2) of the orbital
+/// spaces
+pub(crate) fn mp2_energy<T: PairIntegrals>(
+    orbitals: &Orbitals,
+    spaces: &[OrbitalSpace],
+    integrals: impl Fn(usize, usize) -> T,
+) -> MP2Energy {
+    let mut energy = MP2Energy::default();
+
+    if orbitals.restricted() {
+        let space = &spaces[0];
+        let iajb = integrals(0, 0);
+
+        for (i, e_i) in space.e_occ.iter().enumerate() {
+            for (j, e_j) in space.e_occ.iter().enumerate() {
+                let block = iajb.block(i, j);
+                for (a, e_a) in space.e_virt.iter().enumerate() {
+                    for (b, e_b) in space.e_virt.iter().enumerate() {
+                        let denominator = e_i + e_j - e_a - e_b;
+                        let direct = block[(a, b)];
+                        let exchange = block[(b, a)];
+                        energy.os += direct * direct / denominator;
+                        energy.ss += direct * (direct - exchange) / denominator;
+                    }
+                }
+            }
+        }
+    } else {
+        // same spin: antisymmetrized integrals within one spin
+        for (op, space) in spaces.iter().enumerate() {
+            let iajb = integrals(op, op);
+
+            for (i, e_i) in space.e_occ.iter().enumerate() {
+                for (j, e_j) in space.e_occ.iter().enumerate().take(i) {
+                    let block = iajb.block(i, j);
+                    for (a, e_a) in space.e_virt.iter().enumerate() {
+                        for (b, e_b) in space.e_virt.iter().enumerate().take(a) {
+                            // <ij||ab> = (ia|jb) - (ib|ja)
+                            let x = block[(a, b)] - block[(b, a)];
+                            energy.ss += x * x / (e_i + e_j - e_a - e_b);
+                        }
+                    }
+                }
+            }
+        }
+
+        // opposite spin: (i_α a_α | j_β b_β)
+        let iajb = integrals(0, 1);
+        let (alpha, beta) = (&spaces[0], &spaces[1]);
+
+        for (i, e_i) in alpha.e_occ.iter().enumerate() {
+            for (j, e_j) in beta.e_occ.iter().enumerate() {
+                let block = iajb.block(i, j);
+                for (a, e_a) in alpha.e_virt.iter().enumerate() {
+                    for (b, e_b) in beta.e_virt.iter().enumerate() {
+                        let x = block[(a, b)];
+                        energy.os += x * x / (e_i + e_j - e_a - e_b);
+                    }
+                }
+            }
+        }
+    }
+
+    energy
+}
+
+/// Second-order Møller-Plesset correlation energy of converged canonical orbitals
+/// (restricted or unrestricted) with the lowest `n_frozen` orbitals of every spin kept
+/// frozen. The half-transformed integrals are kept in `buffer`.
+pub fn mp2(
+    orbitals: &Orbitals,
+    eri: &FMatrixContainer,
+    n_frozen: usize,
+    buffer: &TransformBuffer,
+) -> MP2Energy {
+    let spaces: Vec<OrbitalSpace> = (0..orbitals.num_op())
+        .map(|op| OrbitalSpace::new(orbitals, op, n_frozen))
+        .collect();
+
+    mp2_energy(orbitals, &spaces, |op1, op2| {
+        let (first, second) = (&spaces[op1], &spaces[op2]);
+        mo_transform(
+            eri,
+            [&first.c_occ, &first.c_virt, &second.c_occ, &second.c_virt],
+            buffer,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mp2;
+    use crate::{
+        data::orbitals::Orbitals,
+        gto_integrals::mo_transform::TransformBuffer,
+        linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer, vector::FVector},
+    };
+
+    /// H2 in a minimal basis at R = 1.4 bohr, integrals from A. Szabo and N. S. Ostlund,
+    /// Modern Quantum Chemistry, 3.5.2
+    fn h2() -> (FMatrix, FVector, FMatrixContainer) {
+        let s12: f64 = 0.6593;
+        let c1 = 1.0 / (2.0 * (1.0 + s12)).sqrt();
+        let c2 = 1.0 / (2.0 * (1.0 - s12)).sqrt();
+        let c = FMatrix::new_from_vec(2, 2, &[c1, c2, c1, -c2]);
+        let eps = FVector::new_from_vec(&[-0.578, 0.6703]);
+
+        // (11|11), (11|22), (21|21), (21|11) and their permutations
+        let (v1111, v1122, v2121, v2111) = (0.7746, 0.5697, 0.2970, 0.4441);
+        let mut eri = FMatrixContainer::new();
+        eri.insert(
+            (0, 0),
+            &FMatrix::new_from_vec(2, 2, &[v1111, v2111, v2111, v1122]),
+        );
+        eri.insert(
+            (1, 0),
+            &FMatrix::new_from_vec(2, 2, &[v2111, v2121, v2121, v2111]),
+        );
+        eri.insert(
+            (1, 1),
+            &FMatrix::new_from_vec(2, 2, &[v1122, v2111, v2111, v1111]),
+        );
+
+        (c, eps, eri)
+    }
+
+    #[test]
+    fn restricted() {
+        // Szabo-Ostlund, Table 6.2: E(2) = -0.0132 for H2 at R = 1.4 bohr
+        let (c, eps, eri) = h2();
+        let energy = mp2(
+            &Orbitals::new_restricted(c, eps, 2),
+            &eri,
+            0,
+            &TransformBuffer::Memory,
+        );
+
+        assert!(
+            (energy.total() - (-0.0132)).abs() < 1e-4,
+            "{}",
+            energy.total()
+        );
+        // a single pair has no same-spin contribution
+        assert!(energy.ss.abs() < 1e-14);
+        assert!((energy.scs() - 1.2 * energy.os).abs() < 1e-14);
+    }
+
+    #[test]
+    fn unrestricted() {
+        // identical alpha and beta orbitals reproduce the restricted energy, also with the
+        // half-transformed integrals of every spin pair on disk
+        let (c, eps, eri) = h2();
+        let restricted = mp2(
+            &Orbitals::new_restricted(c.clone(), eps.clone(), 2),
+            &eri,
+            0,
+            &TransformBuffer::Memory,
+        );
+        let name = std::env::temp_dir().join("mp2_unrestricted_test.tmp");
+        let unrestricted = mp2(
+            &Orbitals::new_unrestricted([c.clone(), c], [eps.clone(), eps], [1, 1]),
+            &eri,
+            0,
+            &TransformBuffer::Disk(name.to_str().unwrap().to_string()),
+        );
+        assert!(!name.exists());
+
+        assert!((restricted.os - unrestricted.os).abs() < 1e-14);
+        assert!((restricted.ss - unrestricted.ss).abs() < 1e-14);
+    }
+}
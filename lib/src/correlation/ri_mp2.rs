@@ -0,0 +1,153 @@
+use crate::{
+    correlation::mp2::{mp2_energy, MP2Energy, OrbitalSpace, PairIntegrals},
+    data::orbitals::Orbitals,
+    gto_basis_sets::basis::Basis,
+    gto_integrals::ri::{three_center, two_center},
+    linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix},
+};
+
+use std::time::Instant;
+
+/// eigenvectors of the Coulomb metric with smaller eigenvalues are dropped
+const METRIC_THRESHOLD: f64 = 1e-10;
+
+/// Fitted integrals (ia|jb) = \sum_Q B^Q_ia B^Q_jb, with one (a, Q) matrix per occupied
+/// orbital i for each of the two orbital spaces
+struct FittedPair<'a> {
+    first: &'a [FMatrix],
+    second: &'a [FMatrix],
+}
+
+impl PairIntegrals for FittedPair<'_> {
+    fn block(&self, i: usize, j: usize) -> FMatrix {
+        &self.first[i] * self.second[j].transposed()
+    }
+}
+
+/// Inverse square root (P|Q)^-1/2 of the Coulomb metric as (kept eigenvectors x P)
+fn inverse_sqrt(metric: &FMatrix) -> FMatrix {
+    let (eigenvalues, eigenvectors) = metric.diagonalize_sym();
+    let kept: Vec<usize> = (0..eigenvalues.n)
+        .filter(|k| eigenvalues[*k] > METRIC_THRESHOLD)
+        .collect();
+
+    let mut w = FMatrix::zero(kept.len(), metric.rows);
+    for (q, k) in kept.iter().enumerate() {
+        let scale = 1.0 / eigenvalues[*k].sqrt();
+        for p in 0..metric.rows {
+            w[(q, p)] = scale * eigenvectors[(p, *k)];
+        }
+    }
+    w
+}
+
+/// Fitting coefficients B^Q_ia = \sum_P (P|Q)^-1/2 (P|ia) of an orbital space, one (a, Q)
+/// matrix per occupied orbital
+fn fitted(three: &[FMatrix], w: &FMatrix, space: &OrbitalSpace) -> Vec<FMatrix> {
+    let (n_o, n_v) = (space.c_occ.cols, space.c_virt.cols);
+    let c_occ_t = space.c_occ.transposed();
+
+    // (P|ia) as (P x ia)
+    let mut pia = FMatrix::zero(three.len(), n_o * n_v);
+    for (p, integral) in three.iter().enumerate() {
+        let x = &c_occ_t * &(integral * &space.c_virt);
+        for i in 0..n_o {
+            for a in 0..n_v {
+                pia[(p, i * n_v + a)] = x[(i, a)];
+            }
+        }
+    }
+
+    let b = w * &pia;
+    (0..n_o)
+        .map(|i| {
+            let mut b_i = FMatrix::zero(n_v, b.rows);
+            for a in 0..n_v {
+                for q in 0..b.rows {
+                    b_i[(a, q)] = b[(q, i * n_v + a)];
+                }
+            }
+            b_i
+        })
+        .collect()
+}
+
+/// MP2 correlation energy with the resolution of the identity (density fitting) in an
+/// auxiliary basis, F. Weigend and M. Häser, Theor. Chem. Acc. 97, 331 (1997).
+/// Restricted or unrestricted canonical orbitals with the lowest `n_frozen` orbitals of
+/// every spin kept frozen.
+pub fn ri_mp2(orbitals: &Orbitals, basis: &Basis, aux: &Basis, n_frozen: usize) -> MP2Energy {
+    let spaces: Vec<OrbitalSpace> = (0..orbitals.num_op())
+        .map(|op| OrbitalSpace::new(orbitals, op, n_frozen))
+        .collect();
+
+    print!("Calculating two-center integrals (P|Q) ... ");
+    let t = Instant::now();
+    let metric = two_center(aux);
+    println!("done ({:?})", t.elapsed());
+
+    print!("Calculating metric (P|Q)^-1/2 ... ");
+    let t = Instant::now();
+    let w = inverse_sqrt(&metric);
+    println!(
+        "done ({:?}), {} of {} auxiliary functions kept",
+        t.elapsed(),
+        w.rows,
+        aux.dim()
+    );
+
+    print!("Calculating three-center integrals (P|μν) ... ");
+    let t = Instant::now();
+    let three = three_center(basis, aux);
+    println!("done ({:?})", t.elapsed());
+
+    print!("Transforming to fitted integrals B(Q|ia) ... ");
+    let t = Instant::now();
+    let b: Vec<Vec<FMatrix>> = spaces
+        .iter()
+        .map(|space| fitted(&three, &w, space))
+        .collect();
+    println!("done ({:?})", t.elapsed());
+
+    print!("Calculating MP2 energy ... ");
+    let t = Instant::now();
+    let energy = mp2_energy(orbitals, &spaces, |op1, op2| FittedPair {
+        first: &b[op1],
+        second: &b[op2],
+    });
+    println!("done ({:?})", t.elapsed());
+
+    energy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ri_mp2;
+    use crate::{
+        correlation::{mp2::mp2, tests::rhf},
+        gto_basis_sets::{auxiliary::AuxiliaryBasis, BasisSet},
+        gto_integrals::mo_transform::TransformBuffer,
+        tests::water_xz,
+    };
+
+    #[test]
+    fn canonical() {
+        // RI-MP2 with an even-tempered auxiliary basis against canonical MP2 for
+        // water/STO-3G, using the converged RHF orbitals
+        let atoms = water_xz();
+        let rhf = rhf(&atoms, &BasisSet::sto_3g);
+
+        let canonical = mp2(&rhf.orbitals, &rhf.eri, 0, &TransformBuffer::Memory);
+        let aux = AuxiliaryBasis::EvenTempered.load(&rhf.basis, &atoms);
+        let fitted = ri_mp2(&rhf.orbitals, &rhf.basis, &aux, 0);
+
+        assert!(canonical.total() < -0.03, "{}", canonical.total());
+        assert!(
+            (fitted.total() - canonical.total()).abs() < 5e-5,
+            "{} != {}",
+            fitted.total(),
+            canonical.total()
+        );
+        assert!((fitted.os - canonical.os).abs() < 5e-5);
+    }
+}
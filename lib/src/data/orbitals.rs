@@ -1,20 +1,265 @@
-use crate::linear_algebra::{matrix::FMatrix, vector::FVector};
+use crate::{
+    gto_basis_sets::basis::Basis,
+    linear_algebra::{matrix::FMatrix, vector::FVector},
+};
 
-#[allow(non_camel_case_types)]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io::prelude::*,
+};
+
+/// Wave function the orbitals belong to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OrbitalType {
+    #[default]
+    HartreeFock,
+    KohnSham,
+    /// natural orbitals of a correlated wave function
+    Natural,
+}
+
+impl Display for OrbitalType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrbitalType::HartreeFock => write!(f, "Hartree-Fock"),
+            OrbitalType::KohnSham => write!(f, "Kohn-Sham"),
+            OrbitalType::Natural => write!(f, "natural"),
+        }
+    }
+}
+
+/// Converged orbitals of an SCF calculation. Restricted wave functions carry a single
+/// set of orbitals, unrestricted ones an alpha and a beta set.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Orbitals {
-    C: [FMatrix; 2],  // MO coefficients AO x MO
-    E: [FVector; 2],  // orbital energies
-    ON: [FVector; 2], // occupation number vectors
+    c: Vec<FMatrix>,  // MO coefficients AO x MO
+    e: Vec<FVector>,  // orbital energies
+    on: Vec<FVector>, // occupation number vectors
+
+    homo: Vec<usize>, // number of occupied orbitals
+
+    kind: OrbitalType,
+}
+
+impl Orbitals {
+    /// Doubly occupied orbitals for the `n_electrons / 2` lowest orbitals
+    pub fn new_restricted(c: FMatrix, e: FVector, n_electrons: usize) -> Self {
+        let homo = n_electrons / 2;
+        let on = Self::occupation(c.cols, homo, 2.0);
+
+        Self {
+            c: vec![c],
+            e: vec![e],
+            on: vec![on],
+            homo: vec![homo],
+            kind: OrbitalType::HartreeFock,
+        }
+    }
 
-    // number of electrons
-    N: usize,         // total number of electrons
-    Nα: usize,       // number of alpha electrons
-    Nβ: usize,       // number of electrons
-    homo: [usize; 2], // number of electrons
+    /// Singly occupied alpha and beta orbitals
+    pub fn new_unrestricted(c: [FMatrix; 2], e: [FVector; 2], n_electrons: [usize; 2]) -> Self {
+        let on = [0, 1].map(|op| Self::occupation(c[op].cols, n_electrons[op], 1.0));
+        let [ca, cb] = c;
+        let [ea, eb] = e;
+        let [oa, ob] = on;
 
-    num_op: usize,
+        Self {
+            c: vec![ca, cb],
+            e: vec![ea, eb],
+            on: vec![oa, ob],
+            homo: n_electrons.to_vec(),
+            kind: OrbitalType::HartreeFock,
+        }
+    }
+
+    /// Natural orbitals with fractional occupation numbers between 0 and 2, e.g. of a
+    /// multiconfigurational wave function
+    pub fn new_natural(c: FMatrix, e: FVector, on: FVector) -> Self {
+        let homo = (on.iter().sum::<f64>() / 2.0).round() as usize;
+
+        Self {
+            c: vec![c],
+            e: vec![e],
+            on: vec![on],
+            homo: vec![homo],
+            kind: OrbitalType::Natural,
+        }
+    }
+
+    /// Marks the orbitals as Kohn-Sham (or other) orbitals
+    pub fn set_kind(&mut self, kind: OrbitalType) {
+        self.kind = kind;
+    }
+
+    /// Replaces the occupation numbers of a set of orbitals, e.g. by fractional ones
+    pub fn set_occupations(&mut self, op: usize, on: FVector) {
+        assert_eq!(
+            on.len(),
+            self.c[op].cols,
+            "One occupation number per orbital"
+        );
+        self.on[op] = on;
+    }
+
+    fn occupation(n_mo: usize, n_occ: usize, value: f64) -> FVector {
+        let mut on = FVector::zero(n_mo);
+        for i in 0..n_occ {
+            on[i] = value;
+        }
+        on
+    }
+
+    /// Density matrix of a set of orbitals: Dμν = \sum_i n_i Cμi Cνi
+    pub fn density(&self, op: usize) -> FMatrix {
+        let c = &self.c[op];
+        let mut d = FMatrix::zero(c.rows, c.rows);
+        for i in (0..c.cols).filter(|&i| self.on[op][i] != 0.0) {
+            for mu in 0..c.rows {
+                for nu in 0..c.rows {
+                    d[(mu, nu)] += self.on[op][i] * c[(mu, i)] * c[(nu, i)];
+                }
+            }
+        }
+        d
+    }
+
+    /// Values of all orbitals of a set at the given points (n_points x n_mo)
+    pub fn evaluate(&self, basis: &Basis, points: &[[f64; 3]], op: usize) -> FMatrix {
+        basis.evaluate(points) * &self.c[op]
+    }
+
+    pub fn store(&self, name: &str) {
+        let mut buffer =
+            File::create(name.to_owned() + ".orbitals").expect("Unable to create Orbitals file");
+        write!(
+            buffer,
+            "{}",
+            serde_json::to_string(self).expect("Unable to serialize Orbitals")
+        )
+        .expect("Unable to write to file");
+    }
+
+    pub fn retrieve(name: &str) -> Self {
+        let mut file =
+            File::open(name.to_owned() + ".orbitals").expect("Unable to open file for reading");
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer)
+            .expect("Unable to read Orbitals file");
+        serde_json::from_str(&buffer).expect("Unable to deserialize Orbitals")
+    }
 }
 
-// impl Orbitals {
-//     pub fn new(guess: &FMatrix) -> Self {}
-// }
+/// Getters
+impl Orbitals {
+    /// number of orbital sets (1: restricted, 2: unrestricted)
+    pub fn num_op(&self) -> usize {
+        self.c.len()
+    }
+
+    pub fn restricted(&self) -> bool {
+        self.num_op() == 1
+    }
+
+    pub fn c(&self, op: usize) -> &FMatrix {
+        &self.c[op]
+    }
+
+    pub fn e(&self, op: usize) -> &FVector {
+        &self.e[op]
+    }
+
+    pub fn on(&self, op: usize) -> &FVector {
+        &self.on[op]
+    }
+
+    pub fn homo(&self, op: usize) -> usize {
+        self.homo[op]
+    }
+
+    pub fn kind(&self) -> OrbitalType {
+        self.kind
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn occupation() {
+        let c = FMatrix::zero(4, 4);
+        let e = FVector::zero(4);
+
+        let rhf = Orbitals::new_restricted(c.clone(), e.clone(), 4);
+        assert!(rhf.restricted());
+        assert_eq!(rhf.homo(0), 2);
+        assert_eq!(*rhf.on(0), FVector::new_from_vec(&[2.0, 2.0, 0.0, 0.0]));
+
+        let uhf = Orbitals::new_unrestricted([c.clone(), c], [e.clone(), e], [3, 1]);
+        assert_eq!(uhf.num_op(), 2);
+        assert_eq!(*uhf.on(0), FVector::new_from_vec(&[1.0, 1.0, 1.0, 0.0]));
+        assert_eq!(*uhf.on(1), FVector::new_from_vec(&[1.0, 0.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn kind() {
+        let c = FMatrix::zero(2, 2);
+        let e = FVector::zero(2);
+
+        let mut rhf = Orbitals::new_restricted(c.clone(), e.clone(), 2);
+        assert_eq!(rhf.kind(), OrbitalType::HartreeFock);
+        rhf.set_kind(OrbitalType::KohnSham);
+        let json = serde_json::to_string(&rhf).unwrap();
+        let ks: Orbitals = serde_json::from_str(&json).unwrap();
+        assert_eq!(ks.kind(), OrbitalType::KohnSham);
+
+        let on = FVector::new_from_vec(&[1.9, 0.1]);
+        assert_eq!(Orbitals::new_natural(c, e, on).kind(), OrbitalType::Natural);
+    }
+
+    #[test]
+    fn evaluate() {
+        use crate::geometry::atom::Atom;
+        use crate::gto_basis_sets::{load_basis_set, BasisSet};
+        use crate::misc::elements::Element::H;
+
+        // bonding orbital of H2 in a minimal basis
+        let atoms = vec![Atom::new(H, [0.0, 0.0, 0.0]), Atom::new(H, [0.0, 0.0, 1.4])];
+        let basis = load_basis_set(&BasisSet::sto_3g, &atoms);
+        let mut c = FMatrix::zero(2, 2);
+        c[(0, 0)] = 0.5;
+        c[(1, 0)] = 0.5;
+        c[(0, 1)] = 0.5;
+        c[(1, 1)] = -0.5;
+        let orbitals = Orbitals::new_restricted(c, FVector::zero(2), 2);
+
+        let points = [[0.0, 0.0, 0.7], [0.3, 0.1, 0.2]];
+        let ao = basis.evaluate(&points);
+        let mo = orbitals.evaluate(&basis, &points, 0);
+        for i in 0..points.len() {
+            assert!((mo[(i, 0)] - 0.5 * (ao[(i, 0)] + ao[(i, 1)])).abs() < 1e-14);
+            assert!((mo[(i, 1)] - 0.5 * (ao[(i, 0)] - ao[(i, 1)])).abs() < 1e-14);
+        }
+        // antibonding orbital has a node at the bond midpoint
+        assert!(mo[(0, 1)].abs() < 1e-14);
+    }
+
+    #[test]
+    fn density() {
+        let mut c = FMatrix::zero(2, 2);
+        let x = 0.5_f64.sqrt();
+        c[(0, 0)] = x;
+        c[(1, 0)] = x;
+        c[(0, 1)] = x;
+        c[(1, 1)] = -x;
+
+        let d = Orbitals::new_restricted(c, FVector::zero(2), 2).density(0);
+        for mu in 0..2 {
+            for nu in 0..2 {
+                assert!((d[(mu, nu)] - 1.0).abs() < 1e-14);
+            }
+        }
+    }
+}
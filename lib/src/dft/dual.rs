@@ -0,0 +1,193 @@
+//! Forward mode automatic differentiation
+//!
+//! Functionals are written once in terms of [`Dual`] numbers, which carry the
+//! derivatives with respect to the five density variables (ρα, ρβ, σαα, σαβ, σββ)
+//! alongside the value.
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// number of independent variables
+pub const N_VAR: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dual {
+    pub v: f64,
+    pub d: [f64; N_VAR],
+}
+
+impl Dual {
+    pub fn constant(v: f64) -> Self {
+        Self { v, d: [0.0; N_VAR] }
+    }
+
+    /// independent variable `i`
+    pub fn variable(v: f64, i: usize) -> Self {
+        let mut d = [0.0; N_VAR];
+        d[i] = 1.0;
+        Self { v, d }
+    }
+
+    /// apply the chain rule for f(self) with value `f` and derivative `df`
+    fn chain(&self, f: f64, df: f64) -> Self {
+        Self {
+            v: f,
+            d: self.d.map(|x| df * x),
+        }
+    }
+
+    pub fn powf(&self, n: f64) -> Self {
+        if self.v == 0.0 {
+            // 0^n with n > 0, the derivative vanishes for n > 1
+            return Self::constant(0.0);
+        }
+        self.chain(self.v.powf(n), n * self.v.powf(n - 1.0))
+    }
+
+    pub fn sqrt(&self) -> Self {
+        if self.v <= 0.0 {
+            return Self::constant(0.0);
+        }
+        let s = self.v.sqrt();
+        self.chain(s, 0.5 / s)
+    }
+
+    pub fn exp(&self) -> Self {
+        let e = self.v.exp();
+        self.chain(e, e)
+    }
+
+    pub fn ln(&self) -> Self {
+        self.chain(self.v.ln(), 1.0 / self.v)
+    }
+
+    pub fn atan(&self) -> Self {
+        self.chain(self.v.atan(), 1.0 / (1.0 + self.v * self.v))
+    }
+
+    pub fn asinh(&self) -> Self {
+        self.chain(self.v.asinh(), 1.0 / (1.0 + self.v * self.v).sqrt())
+    }
+
+    /// restrict the value to [min, max], outside the derivatives vanish
+    pub fn clamp(&self, min: f64, max: f64) -> Self {
+        if self.v < min {
+            Self::constant(min)
+        } else if self.v > max {
+            Self::constant(max)
+        } else {
+            *self
+        }
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+
+    fn add(self, rhs: Dual) -> Dual {
+        let mut d = self.d;
+        for (x, y) in d.iter_mut().zip(rhs.d) {
+            *x += y;
+        }
+        Dual {
+            v: self.v + rhs.v,
+            d,
+        }
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+
+    fn sub(self, rhs: Dual) -> Dual {
+        self + (-rhs)
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+
+    fn mul(self, rhs: Dual) -> Dual {
+        let mut d = [0.0; N_VAR];
+        for (i, x) in d.iter_mut().enumerate() {
+            *x = self.d[i] * rhs.v + self.v * rhs.d[i];
+        }
+        Dual {
+            v: self.v * rhs.v,
+            d,
+        }
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+
+    fn div(self, rhs: Dual) -> Dual {
+        let mut d = [0.0; N_VAR];
+        for (i, x) in d.iter_mut().enumerate() {
+            *x = (self.d[i] * rhs.v - self.v * rhs.d[i]) / (rhs.v * rhs.v);
+        }
+        Dual {
+            v: self.v / rhs.v,
+            d,
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+
+    fn neg(self) -> Dual {
+        Dual {
+            v: -self.v,
+            d: self.d.map(|x| -x),
+        }
+    }
+}
+
+/// arithmetic with plain numbers
+macro_rules! scalar_ops {
+    ($($imp:ident, $method:ident);*) => {$(
+        impl $imp<f64> for Dual {
+            type Output = Dual;
+
+            fn $method(self, rhs: f64) -> Dual {
+                self.$method(Dual::constant(rhs))
+            }
+        }
+
+        impl $imp<Dual> for f64 {
+            type Output = Dual;
+
+            fn $method(self, rhs: Dual) -> Dual {
+                Dual::constant(self).$method(rhs)
+            }
+        }
+    )*};
+}
+
+scalar_ops!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::Dual;
+
+    #[test]
+    fn derivatives() {
+        let x = Dual::variable(0.7, 0);
+        let y = Dual::variable(1.3, 1);
+
+        // f = x^2 y + ln(y) / x
+        let f = x * x * y + y.ln() / x;
+        assert!((f.v - (0.49 * 1.3 + 1.3_f64.ln() / 0.7)).abs() < 1e-14);
+        assert!((f.d[0] - (2.0 * 0.7 * 1.3 - 1.3_f64.ln() / 0.49)).abs() < 1e-14);
+        assert!((f.d[1] - (0.49 + 1.0 / (1.3 * 0.7))).abs() < 1e-14);
+
+        // g = atan(asinh(x)) exp(-x) sqrt(x)^3
+        let g = |x: f64| x.asinh().atan() * (-x).exp() * x.sqrt().powf(3.0);
+        let dg = x.asinh().atan() * (-x).exp() * x.sqrt().powf(3.0);
+        let h = 1e-6;
+        let fd = (g(0.7 + h) - g(0.7 - h)) / (2.0 * h);
+        assert!((dg.v - g(0.7)).abs() < 1e-14);
+        assert!((dg.d[0] - fd).abs() < 1e-9);
+    }
+}
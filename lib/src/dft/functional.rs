@@ -0,0 +1,228 @@
+use crate::dft::{
+    dual::Dual,
+    gga::{b88, lyp, pbe_c, pbe_x},
+    lda::{slater, vwn5},
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// densities below this threshold are treated as zero
+const RHO_THRESHOLD: f64 = 1e-14;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Functional {
+    Slater,
+    SVWN5,
+    BLYP,
+    #[default]
+    PBE,
+    /// B3LYP with VWN5 as in ORCA and Turbomole (Gaussian uses VWN3)
+    B3LYP,
+    PBE0,
+}
+
+impl FromStr for Functional {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let functional = match s.to_uppercase().as_str() {
+            "SLATER" | "HFS" => Functional::Slater,
+            "SVWN5" | "SVWN" | "LDA" => Functional::SVWN5,
+            "BLYP" => Functional::BLYP,
+            "PBE" => Functional::PBE,
+            "B3LYP" => Functional::B3LYP,
+            "PBE0" => Functional::PBE0,
+            _ => return Err("Unknown functional"),
+        };
+
+        Ok(functional)
+    }
+}
+
+impl Display for Functional {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Functional::Slater => write!(f, "Slater"),
+            Functional::SVWN5 => write!(f, "SVWN5"),
+            Functional::BLYP => write!(f, "BLYP"),
+            Functional::PBE => write!(f, "PBE"),
+            Functional::B3LYP => write!(f, "B3LYP"),
+            Functional::PBE0 => write!(f, "PBE0"),
+        }
+    }
+}
+
+/// Energy density and its derivatives at a single point
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct XCPoint {
+    pub e: f64,
+    /// ∂e/∂ρα, ∂e/∂ρβ
+    pub vrho: [f64; 2],
+    /// ∂e/∂σαα, ∂e/∂σαβ, ∂e/∂σββ
+    pub vsigma: [f64; 3],
+}
+
+impl Functional {
+    /// fraction of exact (Hartree-Fock) exchange
+    pub fn exact_exchange(&self) -> f64 {
+        match self {
+            Functional::B3LYP => 0.2,
+            Functional::PBE0 => 0.25,
+            _ => 0.0,
+        }
+    }
+
+    /// whether the functional depends on the density gradient
+    pub fn gga(&self) -> bool {
+        !matches!(self, Functional::Slater | Functional::SVWN5)
+    }
+
+    /// Evaluate the functional for spin densities `rho` = (ρα, ρβ) and the gradient
+    /// invariants `sigma` = (∇ρα·∇ρα, ∇ρα·∇ρβ, ∇ρβ·∇ρβ)
+    pub fn evaluate(&self, rho: [f64; 2], sigma: [f64; 3]) -> XCPoint {
+        // vanishing spin channels enter as constants
+        let present = rho.map(|r| r >= RHO_THRESHOLD);
+        if !present[0] && !present[1] {
+            return XCPoint::default();
+        }
+        let variable = |value: f64, i: usize, present: bool| match present {
+            true => Dual::variable(value, i),
+            false => Dual::constant(0.0),
+        };
+        let rho_a = variable(rho[0], 0, present[0]);
+        let rho_b = variable(rho[1], 1, present[1]);
+        let sigma = [
+            variable(sigma[0], 2, present[0]),
+            variable(sigma[1], 3, present[0] && present[1]),
+            variable(sigma[2], 4, present[1]),
+        ];
+
+        // exchange functionals act on both spin channels separately
+        let exchange = |f: &dyn Fn(Dual, Dual) -> Dual| {
+            let mut e = Dual::constant(0.0);
+            if present[0] {
+                e = e + f(rho_a, sigma[0]);
+            }
+            if present[1] {
+                e = e + f(rho_b, sigma[2]);
+            }
+            e
+        };
+        let slater = || exchange(&|rho, _| slater(rho));
+        let b88 = || exchange(&b88);
+        let pbe_x = || exchange(&pbe_x);
+
+        let e = match self {
+            Functional::Slater => slater(),
+            Functional::SVWN5 => slater() + vwn5(rho_a, rho_b),
+            Functional::BLYP => slater() + b88() + lyp(rho_a, rho_b, sigma),
+            Functional::PBE => pbe_x() + pbe_c(rho_a, rho_b, sigma),
+            Functional::B3LYP => {
+                0.8 * slater()
+                    + 0.72 * b88()
+                    + 0.19 * vwn5(rho_a, rho_b)
+                    + 0.81 * lyp(rho_a, rho_b, sigma)
+            }
+            Functional::PBE0 => 0.75 * pbe_x() + pbe_c(rho_a, rho_b, sigma),
+        };
+
+        XCPoint {
+            e: e.v,
+            vrho: [e.d[0], e.d[1]],
+            vsigma: [e.d[2], e.d[3], e.d[4]],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Functional;
+    use crate::dft::{
+        dual::Dual,
+        gga::{b88, lyp, pbe_c, pbe_x},
+        lda::{slater, vwn5},
+    };
+    use crate::linear_algebra::constants::PI;
+
+    /// Integrate an energy density over the exact, fully spin polarized density of the
+    /// hydrogen atom ρα = exp(-2r) / π, σαα = 4 ρα^2
+    fn hydrogen(f: impl Fn(Dual, Dual, [Dual; 3]) -> Dual) -> f64 {
+        let h = 1e-3;
+        (1..30000)
+            .map(|i| {
+                let r = i as f64 * h;
+                let rho = (-2.0 * r).exp() / PI;
+                let sigma = [4.0 * rho * rho, 0.0, 0.0].map(Dual::constant);
+                4.0 * PI * r * r * h * f(Dual::constant(rho), Dual::constant(0.0), sigma).v
+            })
+            .sum()
+    }
+
+    #[test]
+    fn hydrogen_atom() {
+        // exchange: LSD, B88 and PBE; correlation: VWN5, PBE and LYP
+        let reference: [(f64, &dyn Fn(Dual, Dual, [Dual; 3]) -> Dual); 6] = [
+            (-0.2680, &|ra, _, _| slater(ra)),
+            (-0.3098, &|ra, _, s| slater(ra) + b88(ra, s[0])),
+            (-0.3059, &|ra, _, s| pbe_x(ra, s[0])),
+            (-0.0222, &|ra, rb, _| vwn5(ra, rb)),
+            (-0.0060, &|ra, rb, s| pbe_c(ra, rb, s)),
+            (0.0, &|ra, rb, s| lyp(ra, rb, s)),
+        ];
+
+        for (value, f) in reference {
+            let e = hydrogen(f);
+            assert!((e - value).abs() < 5e-4, "{e} != {value}");
+        }
+    }
+
+    #[test]
+    fn derivatives() {
+        // analytic potentials against finite differences for a spin polarized point
+        let rho = [0.31, 0.17];
+        let sigma = [0.12, 0.05, 0.07];
+        let h = 1e-6;
+
+        for functional in [
+            Functional::Slater,
+            Functional::SVWN5,
+            Functional::BLYP,
+            Functional::PBE,
+            Functional::B3LYP,
+            Functional::PBE0,
+        ] {
+            let point = functional.evaluate(rho, sigma);
+            let derivatives = [
+                point.vrho[0],
+                point.vrho[1],
+                point.vsigma[0],
+                point.vsigma[1],
+                point.vsigma[2],
+            ];
+
+            for (i, derivative) in derivatives.iter().enumerate() {
+                let e = |x: f64| {
+                    let mut variables = [rho[0], rho[1], sigma[0], sigma[1], sigma[2]];
+                    variables[i] += x;
+                    let [ra, rb, saa, sab, sbb] = variables;
+                    functional.evaluate([ra, rb], [saa, sab, sbb]).e
+                };
+                let fd = (e(h) - e(-h)) / (2.0 * h);
+                assert!((derivative - fd).abs() < 1e-7, "{functional} {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        use std::str::FromStr;
+
+        assert_eq!(Functional::from_str("b3lyp"), Ok(Functional::B3LYP));
+        assert_eq!(Functional::from_str("PBE0"), Ok(Functional::PBE0));
+        assert_eq!(Functional::B3LYP.exact_exchange(), 0.2);
+        assert!(!Functional::SVWN5.gga());
+    }
+}
@@ -0,0 +1,88 @@
+//! Generalized gradient approximation, spin-polarized energy densities per volume
+
+use crate::{
+    dft::{
+        dual::Dual,
+        lda::{pw92_epsilon, zeta},
+    },
+    linear_algebra::constants::PI,
+};
+
+/// Becke 88 gradient correction to the exchange of a single spin channel
+pub fn b88(rho: Dual, sigma: Dual) -> Dual {
+    let beta = 0.0042;
+    let rho43 = rho.powf(4.0 / 3.0);
+    let x = sigma.sqrt() / rho43;
+
+    -beta * rho43 * x * x / (1.0 + 6.0 * beta * x * x.asinh())
+}
+
+/// PBE exchange of a single spin channel, using Ex[ρα, ρβ] = (Ex[2ρα] + Ex[2ρβ]) / 2
+pub fn pbe_x(rho: Dual, sigma: Dual) -> Dual {
+    let kappa = 0.804;
+    let mu = 0.219_514_972_764_517_1;
+
+    // spin-unpolarized quantities of the doubled density
+    let rho = 2.0 * rho;
+    let sigma = 4.0 * sigma;
+
+    let s2 = sigma / (4.0 * (3.0 * PI * PI).powf(2.0 / 3.0) * rho.powf(8.0 / 3.0));
+    let f_x = 1.0 + kappa - kappa / (1.0 + mu * s2 / kappa);
+
+    0.5 * (-0.75 * (3.0 / PI).powf(1.0 / 3.0) * rho.powf(4.0 / 3.0) * f_x)
+}
+
+/// PBE correlation
+pub fn pbe_c(rho_a: Dual, rho_b: Dual, sigma: [Dual; 3]) -> Dual {
+    let beta = 0.066_724_550_603_149_22;
+    let gamma = (1.0 - 2.0_f64.ln()) / (PI * PI);
+
+    let rho = rho_a + rho_b;
+    let sigma = sigma[0] + 2.0 * sigma[1] + sigma[2];
+    let zeta = zeta(rho_a, rho_b);
+    let ec = pw92_epsilon(rho_a, rho_b);
+
+    let phi = 0.5 * ((1.0 + zeta).powf(2.0 / 3.0) + (1.0 - zeta).powf(2.0 / 3.0));
+    let phi3 = phi * phi * phi;
+    let k_f = (3.0 * PI * PI * rho).powf(1.0 / 3.0);
+    let k_s = (4.0 * k_f / PI).sqrt();
+    let t2 = sigma / (4.0 * phi * phi * k_s * k_s * rho * rho);
+
+    let a = beta / gamma / ((-ec / (gamma * phi3)).exp() - 1.0);
+    let at2 = a * t2;
+    let h = gamma * phi3 * (1.0 + beta / gamma * t2 * (1.0 + at2) / (1.0 + at2 + at2 * at2)).ln();
+
+    rho * (ec + h)
+}
+
+/// Lee-Yang-Parr correlation in the form of Miehlich, Savin, Stoll and Preuss
+pub fn lyp(rho_a: Dual, rho_b: Dual, sigma: [Dual; 3]) -> Dual {
+    let a = 0.04918;
+    let b = 0.132;
+    let c = 0.2533;
+    let d = 0.349;
+    let c_f = 0.3 * (3.0 * PI * PI).powf(2.0 / 3.0);
+
+    let rho = rho_a + rho_b;
+    let [sigma_aa, sigma_ab, sigma_bb] = sigma;
+    let sigma = sigma_aa + 2.0 * sigma_ab + sigma_bb;
+
+    let rho13 = rho.powf(-1.0 / 3.0);
+    let denominator = 1.0 + d * rho13;
+    let omega = (-c * rho13).exp() / denominator * rho.powf(-11.0 / 3.0);
+    let delta = c * rho13 + d * rho13 / denominator;
+
+    let first = -a * 4.0 / denominator * rho_a * rho_b / rho;
+
+    let bracket = rho_a
+        * rho_b
+        * (2.0_f64.powf(11.0 / 3.0) * c_f * (rho_a.powf(8.0 / 3.0) + rho_b.powf(8.0 / 3.0))
+            + (47.0 / 18.0 - 7.0 / 18.0 * delta) * sigma
+            - (2.5 - delta / 18.0) * (sigma_aa + sigma_bb)
+            - (delta - 11.0) / 9.0 * (rho_a / rho * sigma_aa + rho_b / rho * sigma_bb))
+        - 2.0 / 3.0 * rho * rho * sigma
+        + (2.0 / 3.0 * rho * rho - rho_a * rho_a) * sigma_bb
+        + (2.0 / 3.0 * rho * rho - rho_b * rho_b) * sigma_aa;
+
+    first - a * b * omega * bracket
+}
@@ -0,0 +1,90 @@
+//! Local density approximation, spin-polarized energy densities per volume
+
+use crate::{dft::dual::Dual, linear_algebra::constants::PI};
+
+/// second derivative of the spin interpolation function f(ζ) at ζ = 0
+const FPP: f64 = 1.709_920_934_161_365_6;
+
+/// Wigner-Seitz radius
+fn rs(rho: Dual) -> Dual {
+    (3.0 / (4.0 * PI) / rho).powf(1.0 / 3.0)
+}
+
+/// relative spin polarization, kept away from ±1 to keep derivatives finite
+pub(crate) fn zeta(rho_a: Dual, rho_b: Dual) -> Dual {
+    ((rho_a - rho_b) / (rho_a + rho_b)).clamp(-1.0 + 1e-12, 1.0 - 1e-12)
+}
+
+/// spin interpolation function f(ζ)
+fn f_zeta(zeta: Dual) -> Dual {
+    ((1.0 + zeta).powf(4.0 / 3.0) + (1.0 - zeta).powf(4.0 / 3.0) - 2.0)
+        / (2.0_f64.powf(4.0 / 3.0) - 2.0)
+}
+
+/// Slater (Dirac) exchange of a single spin channel
+pub fn slater(rho: Dual) -> Dual {
+    -1.5 * (3.0 / (4.0 * PI)).powf(1.0 / 3.0) * rho.powf(4.0 / 3.0)
+}
+
+/// VWN interpolation formula for one set of parameters
+fn vwn_g(x: Dual, a: f64, b: f64, c: f64, x0: f64) -> Dual {
+    let big_x = |x: Dual| x * x + b * x + c;
+    let q = (4.0 * c - b * b).sqrt();
+    let x_x0 = x0 * x0 + b * x0 + c;
+    let atan = (q / (2.0 * x + b)).atan();
+
+    a * ((x * x / big_x(x)).ln() + 2.0 * b / q * atan
+        - b * x0 / x_x0 * (((x - x0) * (x - x0) / big_x(x)).ln() + 2.0 * (b + 2.0 * x0) / q * atan))
+}
+
+/// Vosko-Wilk-Nusair correlation (VWN5)
+pub fn vwn5(rho_a: Dual, rho_b: Dual) -> Dual {
+    let rho = rho_a + rho_b;
+    let x = rs(rho).sqrt();
+    let zeta = zeta(rho_a, rho_b);
+
+    let para = vwn_g(x, 0.031_090_7, 3.727_44, 12.935_2, -0.104_98);
+    let ferro = vwn_g(x, 0.015_545_35, 7.060_42, 18.057_8, -0.325);
+    let alpha = vwn_g(x, -1.0 / (6.0 * PI * PI), 1.131_07, 13.004_5, -0.004_758_4);
+
+    let f = f_zeta(zeta);
+    let z4 = zeta * zeta * zeta * zeta;
+    rho * (para + alpha * f / FPP * (1.0 - z4) + (ferro - para) * f * z4)
+}
+
+/// Perdew-Wang interpolation formula for one set of parameters
+fn pw92_g(rs: Dual, a: f64, alpha1: f64, beta: [f64; 4]) -> Dual {
+    let sqrt_rs = rs.sqrt();
+    let denominator =
+        2.0 * a * (beta[0] * sqrt_rs + beta[1] * rs + beta[2] * rs * sqrt_rs + beta[3] * rs * rs);
+    -2.0 * a * (1.0 + alpha1 * rs) * (1.0 + 1.0 / denominator).ln()
+}
+
+/// Perdew-Wang 92 correlation energy per particle, with the parameters used in PBE
+pub(crate) fn pw92_epsilon(rho_a: Dual, rho_b: Dual) -> Dual {
+    let rs = rs(rho_a + rho_b);
+    let zeta = zeta(rho_a, rho_b);
+
+    let ec0 = pw92_g(
+        rs,
+        0.031_090_7,
+        0.213_70,
+        [7.595_7, 3.587_6, 1.638_2, 0.492_94],
+    );
+    let ec1 = pw92_g(
+        rs,
+        0.015_545_35,
+        0.205_48,
+        [14.118_9, 6.197_7, 3.366_2, 0.625_17],
+    );
+    let minus_alpha = pw92_g(
+        rs,
+        0.016_886_9,
+        0.111_25,
+        [10.357, 3.623_1, 0.880_26, 0.496_71],
+    );
+
+    let f = f_zeta(zeta);
+    let z4 = zeta * zeta * zeta * zeta;
+    ec0 - minus_alpha * f / FPP * (1.0 - z4) + (ec1 - ec0) * f * z4
+}
@@ -0,0 +1,9 @@
+//! Kohn-Sham density functional theory: exchange-correlation functionals and the
+//! numerical exchange-correlation potential
+
+mod dual;
+mod gga;
+mod lda;
+
+pub mod functional;
+pub mod xc;
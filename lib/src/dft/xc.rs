@@ -0,0 +1,161 @@
+use crate::{
+    dft::functional::Functional, grid::MolecularGrid, gto_basis_sets::basis::Basis,
+    linear_algebra::matrix::FMatrix,
+};
+
+/// number of grid points evaluated at once
+const BATCH_SIZE: usize = 2048;
+
+/// Density and its gradient of a single spin channel on a batch of points
+struct SpinDensity {
+    rho: Vec<f64>,
+    gradient: Vec<[f64; 3]>,
+}
+
+/// ρ(r) = \sum_{μν} Dμν φμ(r) φν(r) and ∇ρ(r) = 2 \sum_{μν} Dμν φμ(r) ∇φν(r)
+fn spin_density(ao: &FMatrix, gradient: Option<&[FMatrix; 3]>, d: &FMatrix) -> SpinDensity {
+    let x = ao * d;
+    let n = ao.rows;
+
+    let rho = (0..n)
+        .map(|i| x.row(i).zip(ao.row(i)).map(|(a, b)| a * b).sum())
+        .collect();
+    let gradient = match gradient {
+        Some(g) => (0..n)
+            .map(|i| {
+                [0, 1, 2].map(|k| 2.0 * x.row(i).zip(g[k].row(i)).map(|(a, b)| a * b).sum::<f64>())
+            })
+            .collect(),
+        None => vec![[0.0; 3]; n],
+    };
+
+    SpinDensity { rho, gradient }
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Exchange-correlation energy and potential matrices for the spin densities `d`.
+///
+/// A single (restricted) density is split into equal α and β parts and a single
+/// potential is returned, otherwise one potential per spin:
+///     Vμν = ∫ ∂e/∂ρ φμ φν + (2 ∂e/∂σαα ∇ρα + ∂e/∂σαβ ∇ρβ) · ∇(φμ φν)
+pub fn xc(
+    basis: &Basis,
+    grid: &MolecularGrid,
+    functional: &Functional,
+    d: &[FMatrix],
+) -> (f64, Vec<FMatrix>) {
+    let restricted = d.len() == 1;
+    let d_spin = if restricted {
+        vec![0.5 * &d[0]]
+    } else {
+        d.to_vec()
+    };
+    let order = usize::from(functional.gga());
+
+    let dim = basis.dim();
+    let mut e_xc = 0.0;
+    let mut v = vec![FMatrix::zero(dim, dim); d_spin.len()];
+
+    for (points, weights) in grid
+        .points()
+        .chunks(BATCH_SIZE)
+        .zip(grid.weights().chunks(BATCH_SIZE))
+    {
+        let values = basis.evaluate_derivatives(points, order);
+        let ao = &values.values;
+        let gradient = values.gradient.as_ref();
+
+        let densities: Vec<SpinDensity> = d_spin
+            .iter()
+            .map(|d| spin_density(ao, gradient, d))
+            .collect();
+        let (alpha, beta) = (&densities[0], densities.last().unwrap());
+
+        // Z_iμ = w_i (0.5 ∂e/∂ρ φμ + g·∇φμ), V = ao^T Z + Z^T ao
+        let mut z = vec![FMatrix::zero(points.len(), dim); d_spin.len()];
+        for (i, w) in weights.iter().enumerate() {
+            let (ga, gb) = (&alpha.gradient[i], &beta.gradient[i]);
+            let sigma = [dot(ga, ga), dot(ga, gb), dot(gb, gb)];
+            let point = functional.evaluate([alpha.rho[i], beta.rho[i]], sigma);
+            e_xc += w * point.e;
+
+            for (op, z) in z.iter_mut().enumerate() {
+                let (own, other, vsigma) = match op {
+                    0 => (ga, gb, point.vsigma[0]),
+                    _ => (gb, ga, point.vsigma[2]),
+                };
+                let g = [0, 1, 2].map(|k| w * (2.0 * vsigma * own[k] + point.vsigma[1] * other[k]));
+                let vrho = 0.5 * w * point.vrho[op];
+
+                for mu in 0..dim {
+                    let mut value = vrho * ao[(i, mu)];
+                    if let Some(gradient) = gradient {
+                        value += (0..3).map(|k| g[k] * gradient[k][(i, mu)]).sum::<f64>();
+                    }
+                    z[(i, mu)] = value;
+                }
+            }
+        }
+
+        let ao_t = ao.transposed();
+        for (v, z) in v.iter_mut().zip(&z) {
+            let x = &ao_t * z;
+            *v += x.transposed() + x;
+        }
+    }
+
+    (e_xc, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::xc;
+    use crate::dft::functional::Functional;
+    use crate::geometry::atom::Atom;
+    use crate::grid::{GridSettings, MolecularGrid};
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::linear_algebra::{matrix::FMatrix, traits::Dot};
+    use crate::misc::elements::Element::H;
+
+    #[test]
+    fn potential() {
+        // the potential is the derivative of the energy with respect to the density
+        let atoms = vec![
+            Atom::new(H, [0.0, 0.0, -0.7]),
+            Atom::new(H, [0.0, 0.0, 0.7]),
+        ];
+        let basis = load_basis_set(&BasisSet::_6_31g, &atoms);
+        let settings = GridSettings {
+            n_radial: 50,
+            n_angular: 110,
+            ..Default::default()
+        };
+        let grid = MolecularGrid::from_atoms(&atoms, &settings);
+
+        let c = [0.3, 0.2, 0.3, 0.2];
+        let d = FMatrix::new_from_vec(4, 4, &c.map(|a| c.map(|b| a * b)).concat());
+        let mut delta = FMatrix::zero(4, 4);
+        delta[(0, 1)] = 1.0;
+        delta[(1, 0)] = 1.0;
+        delta[(2, 2)] = 1.0;
+
+        for functional in [Functional::SVWN5, Functional::PBE, Functional::B3LYP] {
+            let (_, v) = xc(&basis, &grid, &functional, &[d.clone()]);
+            let h = 1e-5;
+            let e = |x: f64| xc(&basis, &grid, &functional, &[&d + x * &delta]).0;
+            let fd = (e(h) - e(-h)) / (2.0 * h);
+            assert!(
+                (v[0].dot(&delta) - fd).abs() < 1e-7,
+                "{functional}: {} != {fd}",
+                v[0].dot(&delta)
+            );
+
+            // unrestricted with equal spin densities
+            let (_, v_u) = xc(&basis, &grid, &functional, &[0.5 * &d, 0.5 * &d]);
+            assert!((&v_u[0] - &v[0]).iter().all(|x| x.abs() < 1e-12));
+        }
+    }
+}
@@ -0,0 +1,422 @@
+//! Gaussian cube file output
+//!
+//! Orbitals, densities and the electrostatic potential are evaluated on a regular
+//! grid spanning the molecule plus some padding. All quantities are in atomic units.
+
+use crate::{
+    data::orbitals::Orbitals,
+    export::primitive_expansion,
+    geometry::atom::Atom,
+    gto_basis_sets::basis::Basis,
+    gto_integrals::nuclear_electron_attraction::point_charge_potential,
+    linear_algebra::{functions::distance, matrix::FMatrix},
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    fs::File,
+    io,
+};
+
+/// number of points evaluated at once
+const BATCH_SIZE: usize = 4096;
+
+/// Regular grid with equal spacing in all directions
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubeGrid {
+    origin: [f64; 3],
+    spacing: f64,
+    n: [usize; 3],
+}
+
+impl CubeGrid {
+    /// Box around all atoms extended by `padding` in every direction
+    pub fn new(atoms: &[Atom], padding: f64, spacing: f64) -> Self {
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for atom in atoms {
+            for x in 0..3 {
+                min[x] = min[x].min(atom.origin[x]);
+                max[x] = max[x].max(atom.origin[x]);
+            }
+        }
+
+        let origin = min.map(|x| x - padding);
+        let n =
+            [0, 1, 2].map(|x| ((max[x] - min[x] + 2.0 * padding) / spacing).ceil() as usize + 1);
+
+        Self { origin, spacing, n }
+    }
+
+    /// All grid points, with z running fastest and x slowest
+    pub fn points(&self) -> Vec<[f64; 3]> {
+        let mut points = Vec::with_capacity(self.n_points());
+        for i in 0..self.n[0] {
+            for j in 0..self.n[1] {
+                for k in 0..self.n[2] {
+                    points.push([
+                        self.origin[0] + i as f64 * self.spacing,
+                        self.origin[1] + j as f64 * self.spacing,
+                        self.origin[2] + k as f64 * self.spacing,
+                    ]);
+                }
+            }
+        }
+        points
+    }
+}
+
+/// Getters
+impl CubeGrid {
+    pub fn origin(&self) -> &[f64; 3] {
+        &self.origin
+    }
+
+    pub fn spacing(&self) -> f64 {
+        self.spacing
+    }
+
+    pub fn n(&self) -> &[usize; 3] {
+        &self.n
+    }
+
+    pub fn n_points(&self) -> usize {
+        self.n.iter().product()
+    }
+}
+
+/// Quantities that can be written to a cube file
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CubeProperty {
+    /// orbital `mo` (0-based) of the set of orbitals `op`
+    Orbital {
+        op: usize,
+        mo: usize,
+    },
+    Density,
+    SpinDensity,
+    ESP,
+}
+
+impl CubeProperty {
+    /// suffix of the file name, e.g. `mo5a` for the fifth alpha orbital
+    pub fn suffix(&self, orbitals: &Orbitals) -> String {
+        match self {
+            CubeProperty::Orbital { op, mo } => match (orbitals.restricted(), op) {
+                (true, _) => format!("mo{}", mo + 1),
+                (false, 0) => format!("mo{}a", mo + 1),
+                (false, _) => format!("mo{}b", mo + 1),
+            },
+            CubeProperty::Density => "dens".to_string(),
+            CubeProperty::SpinDensity => "spin".to_string(),
+            CubeProperty::ESP => "esp".to_string(),
+        }
+    }
+}
+
+impl Display for CubeProperty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CubeProperty::Orbital { op, mo } => write!(f, "Orbital {} (set {})", mo + 1, op)?,
+            CubeProperty::Density => write!(f, "Electron density")?,
+            CubeProperty::SpinDensity => write!(f, "Spin density")?,
+            CubeProperty::ESP => write!(f, "Electrostatic potential")?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Evaluate a property at the given points
+pub fn evaluate(
+    property: &CubeProperty,
+    points: &[[f64; 3]],
+    atoms: &[Atom],
+    basis: &Basis,
+    orbitals: &Orbitals,
+) -> Vec<f64> {
+    match property {
+        CubeProperty::Orbital { op, mo } => {
+            let c = orbitals.c(*op);
+            batched(points, basis, |ao, i| {
+                (0..ao.cols).map(|mu| ao[(i, mu)] * c[(mu, *mo)]).sum()
+            })
+        }
+        CubeProperty::Density | CubeProperty::SpinDensity => {
+            // ρ = \sum_op s_op \sum_μν Dμν φμ φν, s = -1 for the beta spin density
+            let d: Vec<FMatrix> = (0..orbitals.num_op())
+                .map(|op| orbitals.density(op))
+                .collect();
+            let sign = |op: usize| match property {
+                CubeProperty::SpinDensity if op == 1 => -1.0,
+                _ => 1.0,
+            };
+
+            batched(points, basis, |ao, i| {
+                let mut value = 0.0;
+                for (op, d) in d.iter().enumerate() {
+                    for mu in 0..ao.cols {
+                        if ao[(i, mu)] == 0.0 {
+                            continue;
+                        }
+                        let d_phi: f64 = (0..ao.cols).map(|nu| d[(mu, nu)] * ao[(i, nu)]).sum();
+                        value += sign(op) * ao[(i, mu)] * d_phi;
+                    }
+                }
+                value
+            })
+        }
+        CubeProperty::ESP => esp(points, atoms, basis, orbitals),
+    }
+}
+
+/// Evaluate the basis functions batch-wise and apply `f(ao values, point index)`
+fn batched<F>(points: &[[f64; 3]], basis: &Basis, f: F) -> Vec<f64>
+where
+    F: Fn(&FMatrix, usize) -> f64,
+{
+    let mut values = Vec::with_capacity(points.len());
+    for batch in points.chunks(BATCH_SIZE) {
+        let ao = basis.evaluate(batch);
+        values.extend((0..batch.len()).map(|i| f(&ao, i)));
+    }
+    values
+}
+
+/// Electrostatic potential of nuclei and electrons. The electronic part is evaluated
+/// in the primitive basis, neglecting primitive pairs with a negligible contribution.
+fn esp(points: &[[f64; 3]], atoms: &[Atom], basis: &Basis, orbitals: &Orbitals) -> Vec<f64> {
+    let (primitives, coefficients) = primitive_expansion(basis, orbitals);
+
+    // (p, q, factor) of all significant primitive pairs with p >= q
+    let mut pairs = Vec::new();
+    for p in 0..primitives.len() {
+        for q in 0..=p {
+            let mut d = 0.0;
+            for (op, c) in coefficients.iter().enumerate() {
+                for i in 0..c.cols {
+                    d += orbitals.on(op)[i] * c[(p, i)] * c[(q, i)];
+                }
+            }
+
+            let a = &primitives[p];
+            let b = &primitives[q];
+            let r_ab = distance(&atoms[a.atom].origin, &atoms[b.atom].origin);
+            let screening = (-a.exp * b.exp / (a.exp + b.exp) * r_ab * r_ab).exp();
+            if (d * screening).abs() < 1e-12 {
+                continue;
+            }
+
+            let factor = if p == q { d } else { 2.0 * d };
+            pairs.push((p, q, factor));
+        }
+    }
+
+    points
+        .iter()
+        .map(|point| {
+            let nuclear: f64 = atoms
+                .iter()
+                .map(|atom| (atom, distance(point, &atom.origin)))
+                // the potential diverges at the nuclei
+                .filter(|(_, r)| *r > 1e-10)
+                .map(|(atom, r)| f64::from(atom.z()) / r)
+                .sum();
+
+            let charge = [(1.0, *point)];
+            let electronic: f64 = pairs
+                .iter()
+                .map(|&(p, q, factor)| {
+                    let a = &primitives[p];
+                    let b = &primitives[q];
+                    factor
+                        * point_charge_potential(
+                            &a.exp,
+                            &a.ml.map(i16::from),
+                            &atoms[a.atom].origin,
+                            &b.exp,
+                            &b.ml.map(i16::from),
+                            &atoms[b.atom].origin,
+                            &charge,
+                        )
+                })
+                .sum();
+
+            nuclear - electronic
+        })
+        .collect()
+}
+
+/// Write values on the grid in Gaussian cube format
+pub fn write_cube(
+    grid: &CubeGrid,
+    atoms: &[Atom],
+    comment: &str,
+    values: &[f64],
+    w: &mut dyn io::Write,
+) -> io::Result<()> {
+    if values.len() != grid.n_points() {
+        panic!(
+            "Number of values ({}) does not match the grid ({})",
+            values.len(),
+            grid.n_points()
+        );
+    }
+
+    writeln!(w, "ferric")?;
+    writeln!(w, "{}", comment)?;
+    writeln!(
+        w,
+        "{:5} {:12.6} {:12.6} {:12.6}",
+        atoms.len(),
+        grid.origin[0],
+        grid.origin[1],
+        grid.origin[2]
+    )?;
+    for x in 0..3 {
+        let mut axis = [0.0; 3];
+        axis[x] = grid.spacing;
+        writeln!(
+            w,
+            "{:5} {:12.6} {:12.6} {:12.6}",
+            grid.n[x], axis[0], axis[1], axis[2]
+        )?;
+    }
+    for atom in atoms {
+        writeln!(
+            w,
+            "{:5} {:12.6} {:12.6} {:12.6} {:12.6}",
+            atom.z(),
+            f64::from(atom.z()),
+            atom.origin[0],
+            atom.origin[1],
+            atom.origin[2]
+        )?;
+    }
+
+    // values along z in lines of at most six
+    for line in values.chunks(grid.n[2]) {
+        for chunk in line.chunks(6) {
+            let chunk: Vec<String> = chunk.iter().map(|x| format!("{:13.5E}", x)).collect();
+            writeln!(w, "{}", chunk.join(""))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Evaluate a property on the grid and write it to `{name}.{suffix}.cube`. Returns the
+/// file name.
+pub fn store_cube(
+    name: &str,
+    property: &CubeProperty,
+    grid: &CubeGrid,
+    atoms: &[Atom],
+    basis: &Basis,
+    orbitals: &Orbitals,
+) -> String {
+    let values = evaluate(property, &grid.points(), atoms, basis, orbitals);
+
+    let filename = format!("{}.{}.cube", name, property.suffix(orbitals));
+    let mut file = File::create(&filename).expect("Unable to create file");
+    write_cube(grid, atoms, &property.to_string(), &values, &mut file)
+        .expect("Unable to write cube file");
+
+    filename
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::linear_algebra::vector::FVector;
+    use crate::misc::elements::Element::H;
+    use crate::tests::water_xz;
+
+    #[test]
+    fn grid() {
+        let grid = CubeGrid::new(&water_xz(), 2.0, 0.5);
+        assert_eq!(grid.origin()[0], -1.4523499293 - 2.0);
+        assert_eq!(grid.n()[1], 9);
+        assert_eq!(grid.points().len(), grid.n_points());
+        assert_eq!(grid.points()[1][2], grid.origin()[2] + 0.5);
+    }
+
+    #[test]
+    fn density() {
+        // a single hydrogen 1s orbital: ρ = φ², spin density equals density
+        let atoms = vec![Atom::new(H, [0.0; 3])];
+        let basis = load_basis_set(&BasisSet::sto_3g, &atoms);
+        let mut c = FMatrix::zero(1, 1);
+        c[(0, 0)] = 1.0;
+        let orbitals = Orbitals::new_unrestricted(
+            [c.clone(), c],
+            [FVector::zero(1), FVector::zero(1)],
+            [1, 0],
+        );
+
+        let points = [[0.1, 0.2, 0.3], [1.0, -0.5, 0.0]];
+        let phi = evaluate(
+            &CubeProperty::Orbital { op: 0, mo: 0 },
+            &points,
+            &atoms,
+            &basis,
+            &orbitals,
+        );
+        let rho = evaluate(&CubeProperty::Density, &points, &atoms, &basis, &orbitals);
+        let spin = evaluate(
+            &CubeProperty::SpinDensity,
+            &points,
+            &atoms,
+            &basis,
+            &orbitals,
+        );
+        for i in 0..points.len() {
+            assert!((rho[i] - phi[i] * phi[i]).abs() < 1e-14);
+            assert!((spin[i] - rho[i]).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn esp() {
+        let atoms = water_xz();
+        let basis = load_basis_set(&BasisSet::sto_3g, &atoms);
+        let n = basis.dim();
+        let mut c = FMatrix::zero(n, n);
+        for i in 0..n {
+            c[(i, i)] = 1.0;
+        }
+        // two electrons in the O 1s function, which far away look like a point charge
+        let orbitals = Orbitals::new_restricted(c, FVector::zero(n), 2);
+
+        let point = [0.0, 0.0, 40.0];
+        let v = evaluate(&CubeProperty::ESP, &[point], &atoms, &basis, &orbitals)[0];
+        let nuclear: f64 = atoms
+            .iter()
+            .map(|a| f64::from(a.z()) / distance(&point, &a.origin))
+            .sum();
+        let r = distance(&point, &atoms[0].origin);
+        assert!((v - (nuclear - 2.0 / r)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn write() {
+        let atoms = water_xz();
+        let grid = CubeGrid::new(&atoms, 1.0, 1.0);
+        let values = vec![0.5; grid.n_points()];
+
+        let mut buffer = Vec::new();
+        write_cube(&grid, &atoms, "test", &values, &mut buffer).unwrap();
+        let out = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[1], "test");
+        assert!(lines[2].starts_with("    3"));
+        assert!(lines[6].starts_with("    8"));
+        // at most six values along z, i.e. a single line for every (x, y)
+        let n = grid.n();
+        assert!(n[2] <= 6);
+        assert_eq!(lines.len(), 2 + 4 + 3 + n[0] * n[1]);
+        assert_eq!(out.matches("5.00000E-1").count(), grid.n_points());
+    }
+}
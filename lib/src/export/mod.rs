@@ -0,0 +1,180 @@
+pub mod cube;
+pub mod molden;
+pub mod mwfn;
+pub mod wfx;
+
+use crate::{
+    data::orbitals::Orbitals,
+    gto_basis_sets::basis::{gaussian_layout, Basis},
+    linear_algebra::{functions::Factorial, matrix::FMatrix},
+};
+
+/// For every AO in the order of the target format the index of the corresponding
+/// ferric AO and the factor its MO coefficients have to be scaled with.
+///
+/// Spherical p functions are ordered x, y, z, higher ones m = 0, +1, -1, +2, -2, ...
+/// and cartesian ones as given by `cartesian`. Cartesian functions of the target format
+/// share the normalization of x^l, whereas ferric normalizes every component on its own.
+fn ao_order(basis: &Basis, cartesian: &[&[[u8; 3]]], format: &str) -> Vec<(usize, f64)> {
+    let mut order = Vec::with_capacity(basis.dim());
+
+    for (sn, shell) in basis.shells().iter().enumerate() {
+        let l = *shell.l();
+        if usize::from(l) >= cartesian.len() {
+            panic!("{} format does not support l = {}", format, l);
+        }
+
+        let offset = basis.offset(sn);
+        let n = basis.shell_dim(sn) / shell.n_contractions();
+
+        for c in 0..shell.n_contractions() {
+            let base = offset + c * n;
+
+            if basis.cartesian() {
+                let layout = gaussian_layout(&l);
+                for ml in cartesian[usize::from(l)] {
+                    let i = layout.iter().position(|x| x == ml).unwrap();
+                    order.push((base + i, cartesian_scale(&l, ml)));
+                }
+            } else if l == 1 {
+                // ferric orders p as m = -1, 0, +1, i.e. y, z, x, the formats as x, y, z
+                order.extend([(base + 2, 1.0), (base, 1.0), (base + 1, 1.0)]);
+            } else {
+                // m = 0, +1, -1, +2, -2, ...
+                let l = l as isize;
+                for k in 0..(2 * l + 1) {
+                    let m = if k % 2 == 1 { (k + 1) / 2 } else { -k / 2 };
+                    order.push((base + (m + l) as usize, 1.0));
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// ratio of the normalization of a cartesian component and that of x^l
+fn cartesian_scale(l: &u8, ml: &[u8; 3]) -> f64 {
+    ((2 * i16::from(*l) - 1).factorial2() as f64
+        / ml.iter()
+            .map(|&x| (2 * i16::from(x) - 1).factorial2() as f64)
+            .product::<f64>())
+    .sqrt()
+}
+
+/// Unnormalized cartesian Gaussian x^l y^m z^n exp(-a r^2)
+#[derive(Debug, Clone, PartialEq)]
+struct Primitive {
+    atom: usize,
+    ml: [u8; 3],
+    exp: f64,
+}
+
+/// Expand all MOs in unnormalized cartesian primitives. Returns the primitives and
+/// for every set of orbitals the coefficient matrix (primitive x MO).
+fn primitive_expansion(basis: &Basis, orbitals: &Orbitals) -> (Vec<Primitive>, Vec<FMatrix>) {
+    let mut primitives = Vec::new();
+    // (primitive, AO, value) of the AO -> primitive transformation
+    let mut elements = Vec::new();
+
+    for (sn, shell) in basis.shells().iter().enumerate() {
+        let l = shell.l();
+        let offset = basis.offset(sn);
+        let n = basis.shell_dim(sn) / shell.n_contractions();
+
+        for c in 0..shell.n_contractions() {
+            for (k, cbf) in shell.cbf().iter().enumerate() {
+                for p in 0..cbf.exps().len() {
+                    let coef = cbf.coefs()[c][p] * cbf.norm()[p];
+                    if coef == 0.0 {
+                        continue;
+                    }
+
+                    let index = primitives.len();
+                    primitives.push(Primitive {
+                        atom: shell.atom(),
+                        ml: *cbf.ml(),
+                        exp: cbf.exps()[p],
+                    });
+
+                    if basis.cartesian() {
+                        elements.push((index, offset + c * n + k, coef));
+                    } else {
+                        let t = basis.trafo_matrix(l);
+                        for m in 0..n {
+                            if t[(m, k)] != 0.0 {
+                                elements.push((index, offset + c * n + m, t[(m, k)] * coef));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut x = FMatrix::zero(primitives.len(), basis.dim());
+    for (p, μ, value) in elements {
+        x[(p, μ)] = value;
+    }
+
+    let coefficients = (0..orbitals.num_op())
+        .map(|op| &x * orbitals.c(op))
+        .collect();
+
+    (primitives, coefficients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::gto_integrals::overlap::overlap;
+    use crate::linear_algebra::vector::FVector;
+    use crate::tests::water_xz;
+
+    #[test]
+    fn primitive_expansion() {
+        let atoms = water_xz();
+
+        for cartesian in [false, true] {
+            let mut basis = load_basis_set(&BasisSet::cc_pvdz, &atoms);
+            basis.set_cartesian(cartesian);
+            let n = basis.dim();
+
+            let mut c = FMatrix::zero(n, n);
+            for i in 0..n {
+                c[(i, i)] = 1.0;
+            }
+            let orbitals = Orbitals::new_restricted(c, FVector::zero(n), 10);
+
+            // every (normalized) AO expanded in primitives has to have unit norm
+            let (primitives, coefficients) = super::primitive_expansion(&basis, &orbitals);
+            let x = &coefficients[0];
+            for μ in 0..n {
+                let mut norm = 0.0;
+                for (p, a) in primitives.iter().enumerate() {
+                    for (q, b) in primitives.iter().enumerate() {
+                        norm += x[(p, μ)]
+                            * x[(q, μ)]
+                            * overlap(
+                                &a.exp,
+                                &a.ml.map(i16::from),
+                                &atoms[a.atom].origin,
+                                &b.exp,
+                                &b.ml.map(i16::from),
+                                &atoms[b.atom].origin,
+                            );
+                    }
+                }
+                assert!((norm - 1.0).abs() < 1e-10, "AO {μ}: {norm}");
+            }
+        }
+    }
+
+    #[test]
+    fn cartesian_scale() {
+        assert_eq!(super::cartesian_scale(&2, &[2, 0, 0]), 1.0);
+        assert!((super::cartesian_scale(&2, &[1, 1, 0]) - 3.0_f64.sqrt()).abs() < 1e-14);
+        assert!((super::cartesian_scale(&3, &[1, 1, 1]) - 15.0_f64.sqrt()).abs() < 1e-14);
+    }
+}
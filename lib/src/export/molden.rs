@@ -0,0 +1,239 @@
+//! Molden file output
+//!
+//! # AO ordering
+//!
+//! Within a shell ferric orders spherical harmonic functions by m = -l, ..., l and
+//! cartesian functions as given by `gaussian_layout` (xx, xy, xz, yy, yz, zz, ...).
+//! Molden expects
+//!
+//! | l | spherical               | cartesian                                          |
+//! |---|-------------------------|----------------------------------------------------|
+//! | p | x, y, z                 | x, y, z                                            |
+//! | d | 0, +1, -1, +2, -2       | xx, yy, zz, xy, xz, yz                             |
+//! | f | 0, +1, -1, ..., +3, -3  | xxx, yyy, zzz, xyy, xxy, xxz, xzz, yzz, yyz, xyz   |
+//! | g | 0, +1, -1, ..., +4, -4  | xxxx, yyyy, zzzz, xxxy, xxxz, xyyy, yyyz, xzzz,    |
+//! |   |                         | yzzz, xxyy, xxzz, yyzz, xxyz, xyyz, xyzz           |
+//!
+//! Both use the same real solid harmonics, so spherical coefficients are only
+//! reordered. Molden normalizes all cartesian components of a shell like x^l, whereas
+//! ferric normalizes every component on its own; cartesian coefficients are scaled
+//! accordingly. Molden does not support functions beyond g.
+//!
+//! Generally contracted shells are written as one shell per contracted function,
+//! which matches the AO order of ferric.
+
+use crate::{
+    data::orbitals::Orbitals,
+    export::ao_order,
+    geometry::atom::Atom,
+    gto_basis_sets::{
+        basis::Basis,
+        export::{BasisWriter, Molden},
+    },
+};
+
+use std::{fs::File, io};
+
+/// cartesian components in Molden order for l = 0, ..., 4
+const MOLDEN_CARTESIAN: [&[[u8; 3]]; 5] = [
+    &[[0, 0, 0]],
+    &[[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    &[
+        [2, 0, 0],
+        [0, 2, 0],
+        [0, 0, 2],
+        [1, 1, 0],
+        [1, 0, 1],
+        [0, 1, 1],
+    ],
+    &[
+        [3, 0, 0],
+        [0, 3, 0],
+        [0, 0, 3],
+        [1, 2, 0],
+        [2, 1, 0],
+        [2, 0, 1],
+        [1, 0, 2],
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 1, 1],
+    ],
+    &[
+        [4, 0, 0],
+        [0, 4, 0],
+        [0, 0, 4],
+        [3, 1, 0],
+        [3, 0, 1],
+        [1, 3, 0],
+        [0, 3, 1],
+        [1, 0, 3],
+        [0, 1, 3],
+        [2, 2, 0],
+        [2, 0, 2],
+        [0, 2, 2],
+        [2, 1, 1],
+        [1, 2, 1],
+        [1, 1, 2],
+    ],
+];
+
+/// For every AO in Molden order the index of the corresponding ferric AO and the
+/// factor its MO coefficients have to be scaled with
+pub fn molden_ao_order(basis: &Basis) -> Vec<(usize, f64)> {
+    ao_order(basis, &MOLDEN_CARTESIAN, "Molden")
+}
+
+/// Write geometry, basis set and orbitals in Molden format
+pub fn write_molden(
+    atoms: &[Atom],
+    basis: &Basis,
+    orbitals: &Orbitals,
+    w: &mut dyn io::Write,
+) -> io::Result<()> {
+    writeln!(w, "[Molden Format]")?;
+    writeln!(w, "[Title]")?;
+    writeln!(w, "ferric")?;
+
+    // --------------------------------
+    // geometry
+    // --------------------------------
+    writeln!(w, "[Atoms] AU")?;
+    for (i, atom) in atoms.iter().enumerate() {
+        writeln!(
+            w,
+            "{:2} {:4} {:3} {:18.10} {:18.10} {:18.10}",
+            atom.el.to_string().trim(),
+            i + 1,
+            atom.z(),
+            atom.origin[0],
+            atom.origin[1],
+            atom.origin[2]
+        )?;
+    }
+
+    // --------------------------------
+    // basis set
+    // --------------------------------
+    Molden.write(basis, atoms, w)?;
+    if !basis.cartesian() {
+        writeln!(w, "[5D7F]")?;
+        writeln!(w, "[9G]")?;
+    }
+
+    // --------------------------------
+    // orbitals
+    // --------------------------------
+    let order = molden_ao_order(basis);
+
+    writeln!(w, "[MO]")?;
+    for op in 0..orbitals.num_op() {
+        let c = orbitals.c(op);
+        let spin = if op == 0 { "Alpha" } else { "Beta" };
+
+        for j in 0..c.cols {
+            writeln!(w, " Sym= A")?;
+            writeln!(w, " Ene= {:16.10}", orbitals.e(op)[j])?;
+            writeln!(w, " Spin= {}", spin)?;
+            writeln!(w, " Occup= {:10.6}", orbitals.on(op)[j])?;
+            for (μ, (i, scale)) in order.iter().enumerate() {
+                writeln!(w, "{:5} {:18.12}", μ + 1, c[(*i, j)] * scale)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Write Molden file `{name}.molden`
+pub fn store_molden(name: &str, atoms: &[Atom], basis: &Basis, orbitals: &Orbitals) {
+    let mut file = File::create(name.to_owned() + ".molden").expect("Unable to create file");
+    write_molden(atoms, basis, orbitals, &mut file).expect("Unable to write Molden file");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gto_basis_sets::{basis::cdim, load_basis_set, BasisSet};
+    use crate::linear_algebra::{matrix::FMatrix, vector::FVector};
+    use crate::tests::water_xz;
+
+    #[test]
+    fn ao_order_spherical() {
+        // def2-SVP O: 3s (0-2), 2p (3-8), 1d (9-13)
+        let basis = load_basis_set(&BasisSet::def2_svp, &water_xz());
+        let order = molden_ao_order(&basis);
+        assert_eq!(order.len(), basis.dim());
+
+        // ferric p: y, z, x; Molden p: x, y, z
+        let p: Vec<usize> = order[3..6].iter().map(|(i, _)| *i).collect();
+        assert_eq!(p, vec![5, 3, 4]);
+        let d: Vec<usize> = order[9..14].iter().map(|(i, _)| *i).collect();
+        assert_eq!(d, vec![11, 12, 10, 13, 9]);
+        assert!(order.iter().all(|(_, scale)| *scale == 1.0));
+
+        // every ferric AO appears exactly once
+        let mut ao: Vec<usize> = order.iter().map(|(i, _)| *i).collect();
+        ao.sort();
+        assert_eq!(ao, (0..basis.dim()).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn ao_order_cartesian() {
+        let mut basis = load_basis_set(&BasisSet::def2_svp, &water_xz());
+        basis.set_cartesian(true);
+        let order = molden_ao_order(&basis);
+        assert_eq!(order.len(), 25);
+
+        // ferric: xx, xy, xz, yy, yz, zz; Molden: xx, yy, zz, xy, xz, yz
+        let d: Vec<usize> = order[9..15].iter().map(|(i, _)| *i).collect();
+        assert_eq!(d, vec![9, 12, 14, 10, 11, 13]);
+        assert_eq!(order[9].1, 1.0);
+        assert!((order[12].1 - 3.0_f64.sqrt()).abs() < 1e-14);
+
+        for l in 0..=4 {
+            assert_eq!(MOLDEN_CARTESIAN[l].len(), cdim(&(l as u8)));
+        }
+    }
+
+    #[test]
+    fn write() {
+        let atoms = water_xz();
+        let basis = load_basis_set(&BasisSet::sto_3g, &atoms);
+        let n = basis.dim();
+
+        let mut c = FMatrix::zero(n, n);
+        for i in 0..n {
+            c[(i, i)] = 1.0;
+        }
+        let orbitals = Orbitals::new_restricted(c, FVector::zero(n), 10);
+
+        let mut buffer = Vec::new();
+        write_molden(&atoms, &basis, &orbitals, &mut buffer).unwrap();
+        let out = String::from_utf8(buffer).unwrap();
+
+        assert!(out.starts_with("[Molden Format]\n"));
+        assert!(out.contains("[Atoms] AU\nO     1   8"));
+        assert!(out.contains("[GTO]\n"));
+        assert!(out.contains("[5D7F]\n"));
+        assert_eq!(out.matches(" Sym= A").count(), n);
+        assert_eq!(out.matches(" Occup=   2.000000").count(), 5);
+        assert_eq!(out.matches(" Spin= Alpha").count(), n);
+
+        // STO-3G O: 1s, 2s, 2p (AOs 2-4). The MO made of the ferric p AO along x, y or z
+        // has its coefficient in the Molden p_x, p_y or p_z slot 3, 4 or 5.
+        let t = basis.trafo_matrix(&1);
+        for k in 0..3 {
+            let axis = (0..3)
+                .max_by(|&a, &b| t[(k, a)].abs().total_cmp(&t[(k, b)].abs()))
+                .unwrap();
+            let mo = out.split(" Sym= A").nth(2 + k + 1).unwrap();
+            let slot: Vec<&str> = mo
+                .lines()
+                .filter(|line| line.ends_with(" 1.000000000000"))
+                .collect();
+            assert_eq!(slot.len(), 1);
+            let index: usize = slot[0].split_whitespace().next().unwrap().parse().unwrap();
+            assert_eq!(index, axis + 3);
+        }
+    }
+}
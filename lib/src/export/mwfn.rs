@@ -0,0 +1,325 @@
+//! Multiwfn `.mwfn` file output
+//!
+//! Contrary to the wfx format the basis set is written shell by shell, like in a
+//! Gaussian fchk file, and all orbitals are kept. The AO order follows Gaussian
+//!
+//! | l | spherical               | cartesian                                            |
+//! |---|-------------------------|------------------------------------------------------|
+//! | d | 0, +1, -1, +2, -2       | xx, yy, zz, xy, xz, yz                               |
+//! | f | 0, +1, -1, ..., +3, -3  | xxx, yyy, zzz, xyy, xxy, xxz, xzz, yzz, yyz, xyz     |
+//! | g | 0, +1, -1, ..., +4, -4  | zzzz, yzzz, yyzz, yyyz, yyyy, xzzz, ..., xxxy, xxxx  |
+//! | h | 0, +1, -1, ..., +5, -5  | zzzzz, yzzzz, yyzzz, ..., xxxxy, xxxxx               |
+//!
+//! Contraction coefficients refer to normalized primitives and include the
+//! normalization of the contracted function.
+
+use crate::{
+    data::orbitals::Orbitals,
+    export::ao_order,
+    geometry::Geometry,
+    gto_basis_sets::basis::{cdim, Basis},
+};
+
+use std::{fs::File, io};
+
+/// cartesian components in Gaussian order for l = 0, ..., 5
+const GAUSSIAN_CARTESIAN: [&[[u8; 3]]; 6] = [
+    &[[0, 0, 0]],
+    &[[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    &[
+        [2, 0, 0],
+        [0, 2, 0],
+        [0, 0, 2],
+        [1, 1, 0],
+        [1, 0, 1],
+        [0, 1, 1],
+    ],
+    &[
+        [3, 0, 0],
+        [0, 3, 0],
+        [0, 0, 3],
+        [1, 2, 0],
+        [2, 1, 0],
+        [2, 0, 1],
+        [1, 0, 2],
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 1, 1],
+    ],
+    &[
+        [0, 0, 4],
+        [0, 1, 3],
+        [0, 2, 2],
+        [0, 3, 1],
+        [0, 4, 0],
+        [1, 0, 3],
+        [1, 1, 2],
+        [1, 2, 1],
+        [1, 3, 0],
+        [2, 0, 2],
+        [2, 1, 1],
+        [2, 2, 0],
+        [3, 0, 1],
+        [3, 1, 0],
+        [4, 0, 0],
+    ],
+    &[
+        [0, 0, 5],
+        [0, 1, 4],
+        [0, 2, 3],
+        [0, 3, 2],
+        [0, 4, 1],
+        [0, 5, 0],
+        [1, 0, 4],
+        [1, 1, 3],
+        [1, 2, 2],
+        [1, 3, 1],
+        [1, 4, 0],
+        [2, 0, 3],
+        [2, 1, 2],
+        [2, 2, 1],
+        [2, 3, 0],
+        [3, 0, 2],
+        [3, 1, 1],
+        [3, 2, 0],
+        [4, 0, 1],
+        [4, 1, 0],
+        [5, 0, 0],
+    ],
+];
+
+/// format values five per line
+fn columns<T: ToString>(w: &mut dyn io::Write, values: impl Iterator<Item = T>) -> io::Result<()> {
+    let values: Vec<String> = values.map(|x| x.to_string()).collect();
+    for chunk in values.chunks(5) {
+        writeln!(w, "{}", chunk.join(" "))?;
+    }
+    Ok(())
+}
+
+fn sci(x: f64) -> String {
+    format!("{:16.8E}", x)
+}
+
+/// Write geometry, basis set and orbitals in Multiwfn mwfn format. `energy` is the
+/// total energy and `virial_ratio` -V/T of the wave function.
+pub fn write_mwfn(
+    geometry: &Geometry,
+    basis: &Basis,
+    orbitals: &Orbitals,
+    energy: f64,
+    virial_ratio: f64,
+    w: &mut dyn io::Write,
+) -> io::Result<()> {
+    let atoms = geometry.molecule.atoms();
+
+    writeln!(w, "# Generated by ferric")?;
+    writeln!(w, "Wfntype= {}", if orbitals.restricted() { 0 } else { 1 })?;
+    writeln!(w, "Charge= {:.6}", f64::from(geometry.charge))?;
+    writeln!(w, "Naelec= {:.6}", geometry.n_electrons_alpha as f64)?;
+    writeln!(w, "Nbelec= {:.6}", geometry.n_electrons_beta as f64)?;
+    writeln!(w, "E_tot= {}", sci(energy).trim())?;
+    writeln!(w, "VT_ratio= {}", sci(virial_ratio).trim())?;
+
+    // --------------------------------
+    // atoms
+    // --------------------------------
+    writeln!(w, "\n# Atom information")?;
+    writeln!(w, "Ncenter= {}", atoms.len())?;
+    writeln!(w, "$Centers")?;
+    for (i, atom) in atoms.iter().enumerate() {
+        writeln!(
+            w,
+            "{:6} {:2} {:3} {:6.3} {:16.8} {:16.8} {:16.8}",
+            i + 1,
+            atom.el.to_string().trim(),
+            atom.z(),
+            f64::from(atom.z()),
+            atom.origin[0],
+            atom.origin[1],
+            atom.origin[2]
+        )?;
+    }
+
+    // --------------------------------
+    // basis set
+    // --------------------------------
+    // one (segmented) shell per contracted function
+    let shells: Vec<(&u8, usize, Vec<(f64, f64)>)> = basis
+        .shells()
+        .iter()
+        .flat_map(|shell| {
+            let coefs = shell.cbf()[0].coefs();
+            let exps = shell.cbf()[0].exps();
+            coefs.iter().map(move |c| {
+                let primitives = exps
+                    .iter()
+                    .zip(c)
+                    .filter(|(_, c)| **c != 0.0)
+                    .map(|(e, c)| (*e, *c))
+                    .collect();
+                (shell.l(), shell.atom(), primitives)
+            })
+        })
+        .collect();
+
+    let n_prim_shell: usize = shells.iter().map(|(_, _, p)| p.len()).sum();
+    let n_prims: usize = shells.iter().map(|(l, _, p)| p.len() * cdim(l)).sum();
+
+    writeln!(w, "\n# Basis function information")?;
+    writeln!(w, "Nbasis= {}", basis.dim())?;
+    writeln!(w, "Nindbasis= {}", orbitals.c(0).cols)?;
+    writeln!(w, "Nprims= {}", n_prims)?;
+    writeln!(w, "Nshell= {}", shells.len())?;
+    writeln!(w, "Nprimshell= {}", n_prim_shell)?;
+
+    writeln!(w, "$Shell types")?;
+    columns(
+        w,
+        shells.iter().map(|(l, _, _)| {
+            let l = i16::from(**l);
+            if basis.cartesian() || l < 2 {
+                l
+            } else {
+                -l
+            }
+        }),
+    )?;
+    writeln!(w, "$Shell centers")?;
+    columns(w, shells.iter().map(|(_, atom, _)| atom + 1))?;
+    writeln!(w, "$Shell contraction degrees")?;
+    columns(w, shells.iter().map(|(_, _, p)| p.len()))?;
+    writeln!(w, "$Primitive exponents")?;
+    columns(
+        w,
+        shells
+            .iter()
+            .flat_map(|(_, _, p)| p.iter().map(|(e, _)| sci(*e))),
+    )?;
+    writeln!(w, "$Contraction coefficients")?;
+    columns(
+        w,
+        shells
+            .iter()
+            .flat_map(|(_, _, p)| p.iter().map(|(_, c)| sci(*c))),
+    )?;
+
+    // --------------------------------
+    // orbitals
+    // --------------------------------
+    let order = ao_order(basis, &GAUSSIAN_CARTESIAN, "mwfn");
+
+    writeln!(w, "\n# Orbital information (nindbasis orbitals)")?;
+    for op in 0..orbitals.num_op() {
+        let c = orbitals.c(op);
+        let orbital_type = if orbitals.restricted() { 0 } else { op + 1 };
+
+        for j in 0..c.cols {
+            writeln!(w, "\nIndex= {:9}", op * c.cols + j + 1)?;
+            writeln!(w, "Type= {}", orbital_type)?;
+            writeln!(w, "Energy= {}", sci(orbitals.e(op)[j]).trim())?;
+            writeln!(w, "Occ= {:.6}", orbitals.on(op)[j])?;
+            writeln!(w, "Sym= ?")?;
+            writeln!(w, "$Coeff")?;
+            columns(w, order.iter().map(|(i, scale)| sci(c[(*i, j)] * scale)))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write Multiwfn file `{name}.mwfn`
+pub fn store_mwfn(
+    name: &str,
+    geometry: &Geometry,
+    basis: &Basis,
+    orbitals: &Orbitals,
+    energy: f64,
+    virial_ratio: f64,
+) {
+    let mut file = File::create(name.to_owned() + ".mwfn").expect("Unable to create file");
+    write_mwfn(geometry, basis, orbitals, energy, virial_ratio, &mut file)
+        .expect("Unable to write mwfn file");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Unit;
+    use crate::gto_basis_sets::{basis::gaussian_layout, load_basis_set, BasisSet};
+    use crate::linear_algebra::{matrix::FMatrix, vector::FVector};
+    use crate::tests::water_xz;
+
+    #[test]
+    fn gaussian_cartesian() {
+        for l in 0..=5 {
+            let mut order = GAUSSIAN_CARTESIAN[l as usize].to_vec();
+            let mut layout = gaussian_layout(&l);
+            order.sort();
+            layout.sort();
+            assert_eq!(order, layout);
+        }
+    }
+
+    #[test]
+    fn p_order() {
+        // cc-pVDZ O: 3s (0-2), 2p (3-8); ferric p: y, z, x, Gaussian p: x, y, z
+        let atoms = water_xz();
+        let basis = load_basis_set(&BasisSet::cc_pvdz, &atoms);
+        let order = ao_order(&basis, &GAUSSIAN_CARTESIAN, "mwfn");
+        let p: Vec<usize> = order[3..9].iter().map(|(i, _)| *i).collect();
+        assert_eq!(p, vec![5, 3, 4, 8, 6, 7]);
+    }
+
+    #[test]
+    fn write() {
+        let geometry = Geometry::new(
+            water_xz(),
+            0,
+            1,
+            Unit::AtomicUnits,
+        );
+        let basis = load_basis_set(&BasisSet::cc_pvdz, geometry.molecule.atoms());
+        let n = basis.dim();
+
+        let mut c = FMatrix::zero(n, n);
+        for i in 0..n {
+            c[(i, i)] = 1.0;
+        }
+        let orbitals = Orbitals::new_unrestricted(
+            [c.clone(), c],
+            [FVector::zero(n), FVector::zero(n)],
+            [5, 5],
+        );
+
+        let mut buffer = Vec::new();
+        write_mwfn(&geometry, &basis, &orbitals, -76.0, 2.0, &mut buffer).unwrap();
+        let out = String::from_utf8(buffer).unwrap();
+
+        // cc-pVDZ: O 3s2p1d, H 2s1p
+        assert!(out.contains("Wfntype= 1\n"));
+        assert!(out.contains("Nbasis= 24\n"));
+        assert!(out.contains("Nshell= 12\n"));
+        assert!(out.contains("$Shell types\n0 0 0 1 1\n-2 0 0 1 0\n0 1\n"));
+        assert_eq!(out.matches("$Coeff").count(), 2 * n);
+        assert_eq!(out.matches("Type= 2\n").count(), n);
+        assert_eq!(out.matches("Occ= 1.000000").count(), 10);
+
+        // the MO made of the ferric p AO along x, y or z of the first O p shell (AOs 3-5)
+        // has its coefficient in the $Coeff entry 4, 5 or 6 (p_x, p_y, p_z)
+        let t = basis.trafo_matrix(&1);
+        for k in 0..3 {
+            let axis = (0..3)
+                .max_by(|&a, &b| t[(k, a)].abs().total_cmp(&t[(k, b)].abs()))
+                .unwrap();
+            let mo = out.split("$Coeff\n").nth(3 + k + 1).unwrap();
+            let coefficients: Vec<f64> = mo
+                .split_whitespace()
+                .take(n)
+                .map(|x| x.parse().unwrap())
+                .collect();
+            let slot = coefficients.iter().position(|x| *x == 1.0);
+            assert_eq!(slot, Some(3 + axis));
+        }
+    }
+}
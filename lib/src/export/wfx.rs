@@ -0,0 +1,349 @@
+//! AIMAll `.wfx` file output
+//!
+//! The wave function is written as an expansion of the occupied MOs in unnormalized
+//! cartesian primitives. Primitive types follow the AIMAll numbering
+//!
+//! | types  | components                                                          |
+//! |--------|---------------------------------------------------------------------|
+//! | 1      | s                                                                   |
+//! | 2-4    | x, y, z                                                             |
+//! | 5-10   | xx, yy, zz, xy, xz, yz                                              |
+//! | 11-20  | xxx, yyy, zzz, xxy, xxz, yyz, xyy, xzz, yzz, xyz                    |
+//! | 21-35  | xxxx, yyyy, zzzz, xxxy, xxxz, xyyy, yyyz, xzzz, yzzz, xxyy, xxzz,   |
+//! |        | yyzz, xxyz, xyyz, xyzz                                              |
+//! | 36-56  | zzzzz, yzzzz, yyzzz, ..., xxxxy, xxxxx                              |
+//!
+//! h functions use the same (reversed) order as Multiwfn, which reads and writes
+//! wfx files with up to h functions.
+
+use crate::{
+    data::orbitals::Orbitals, export::primitive_expansion, geometry::Geometry,
+    gto_basis_sets::basis::Basis,
+};
+
+use std::{fs::File, io};
+
+/// cartesian components of the primitive types for l = 0, ..., 5
+const WFX_TYPES: [&[[u8; 3]]; 6] = [
+    &[[0, 0, 0]],
+    &[[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    &[
+        [2, 0, 0],
+        [0, 2, 0],
+        [0, 0, 2],
+        [1, 1, 0],
+        [1, 0, 1],
+        [0, 1, 1],
+    ],
+    &[
+        [3, 0, 0],
+        [0, 3, 0],
+        [0, 0, 3],
+        [2, 1, 0],
+        [2, 0, 1],
+        [0, 2, 1],
+        [1, 2, 0],
+        [1, 0, 2],
+        [0, 1, 2],
+        [1, 1, 1],
+    ],
+    &[
+        [4, 0, 0],
+        [0, 4, 0],
+        [0, 0, 4],
+        [3, 1, 0],
+        [3, 0, 1],
+        [1, 3, 0],
+        [0, 3, 1],
+        [1, 0, 3],
+        [0, 1, 3],
+        [2, 2, 0],
+        [2, 0, 2],
+        [0, 2, 2],
+        [2, 1, 1],
+        [1, 2, 1],
+        [1, 1, 2],
+    ],
+    &[
+        [0, 0, 5],
+        [0, 1, 4],
+        [0, 2, 3],
+        [0, 3, 2],
+        [0, 4, 1],
+        [0, 5, 0],
+        [1, 0, 4],
+        [1, 1, 3],
+        [1, 2, 2],
+        [1, 3, 1],
+        [1, 4, 0],
+        [2, 0, 3],
+        [2, 1, 2],
+        [2, 2, 1],
+        [2, 3, 0],
+        [3, 0, 2],
+        [3, 1, 1],
+        [3, 2, 0],
+        [4, 0, 1],
+        [4, 1, 0],
+        [5, 0, 0],
+    ],
+];
+
+/// AIMAll primitive type (1-based) of a cartesian component
+fn primitive_type(ml: &[u8; 3]) -> usize {
+    let l = usize::from(ml.iter().sum::<u8>());
+    if l >= WFX_TYPES.len() {
+        panic!("wfx format does not support l = {}", l);
+    }
+
+    let offset: usize = WFX_TYPES[..l].iter().map(|t| t.len()).sum();
+    offset + WFX_TYPES[l].iter().position(|x| x == ml).unwrap() + 1
+}
+
+fn tag(w: &mut dyn io::Write, name: &str, lines: &[String]) -> io::Result<()> {
+    writeln!(w, "<{}>", name)?;
+    for line in lines {
+        writeln!(w, "{}", line)?;
+    }
+    writeln!(w, "</{}>", name)
+}
+
+/// format values five per line
+fn columns<T: ToString>(values: impl Iterator<Item = T>) -> Vec<String> {
+    let values: Vec<String> = values.map(|x| x.to_string()).collect();
+    values.chunks(5).map(|chunk| chunk.join(" ")).collect()
+}
+
+fn sci(x: f64) -> String {
+    format!("{:20.12e}", x)
+}
+
+/// Write the occupied orbitals in AIMAll wfx format. `energy` is the total energy and
+/// `virial_ratio` -V/T of the wave function.
+pub fn write_wfx(
+    geometry: &Geometry,
+    basis: &Basis,
+    orbitals: &Orbitals,
+    energy: f64,
+    virial_ratio: f64,
+    w: &mut dyn io::Write,
+) -> io::Result<()> {
+    let atoms = geometry.molecule.atoms();
+    let (primitives, coefficients) = primitive_expansion(basis, orbitals);
+
+    // (set of orbitals, MO) of all occupied orbitals
+    let occupied: Vec<(usize, usize)> = (0..orbitals.num_op())
+        .flat_map(|op| {
+            (0..orbitals.c(op).cols)
+                .filter(move |&j| orbitals.on(op)[j] > 0.0)
+                .map(move |j| (op, j))
+        })
+        .collect();
+
+    tag(w, "Title", &["ferric".to_string()])?;
+    tag(w, "Keywords", &["GTO".to_string()])?;
+    tag(w, "Number of Nuclei", &[atoms.len().to_string()])?;
+    tag(
+        w,
+        "Number of Occupied Molecular Orbitals",
+        &[occupied.len().to_string()],
+    )?;
+    tag(w, "Number of Perturbations", &["0".to_string()])?;
+    tag(w, "Net Charge", &[geometry.charge.to_string()])?;
+    tag(
+        w,
+        "Number of Electrons",
+        &[geometry.n_electrons.to_string()],
+    )?;
+    tag(
+        w,
+        "Number of Alpha Electrons",
+        &[geometry.n_electrons_alpha.to_string()],
+    )?;
+    tag(
+        w,
+        "Number of Beta Electrons",
+        &[geometry.n_electrons_beta.to_string()],
+    )?;
+    tag(
+        w,
+        "Electronic Spin Multiplicity",
+        &[geometry.multiplicity.to_string()],
+    )?;
+    tag(w, "Number of Core Electrons", &["0".to_string()])?;
+
+    // --------------------------------
+    // nuclei
+    // --------------------------------
+    let names: Vec<String> = atoms
+        .iter()
+        .enumerate()
+        .map(|(i, atom)| format!("{}{}", atom.el.to_string().trim(), i + 1))
+        .collect();
+    tag(w, "Nuclear Names", &names)?;
+    tag(
+        w,
+        "Atomic Numbers",
+        &atoms.iter().map(|a| a.z().to_string()).collect::<Vec<_>>(),
+    )?;
+    tag(
+        w,
+        "Nuclear Charges",
+        &atoms
+            .iter()
+            .map(|a| sci(f64::from(a.z())))
+            .collect::<Vec<_>>(),
+    )?;
+    tag(
+        w,
+        "Nuclear Cartesian Coordinates",
+        &atoms
+            .iter()
+            .map(|a| a.origin.map(sci).join(" "))
+            .collect::<Vec<_>>(),
+    )?;
+
+    // --------------------------------
+    // primitives
+    // --------------------------------
+    tag(w, "Number of Primitives", &[primitives.len().to_string()])?;
+    tag(
+        w,
+        "Primitive Centers",
+        &columns(primitives.iter().map(|p| p.atom + 1)),
+    )?;
+    tag(
+        w,
+        "Primitive Types",
+        &columns(primitives.iter().map(|p| primitive_type(&p.ml))),
+    )?;
+    tag(
+        w,
+        "Primitive Exponents",
+        &columns(primitives.iter().map(|p| sci(p.exp))),
+    )?;
+
+    // --------------------------------
+    // orbitals
+    // --------------------------------
+    tag(
+        w,
+        "Molecular Orbital Occupation Numbers",
+        &occupied
+            .iter()
+            .map(|&(op, j)| sci(orbitals.on(op)[j]))
+            .collect::<Vec<_>>(),
+    )?;
+    tag(
+        w,
+        "Molecular Orbital Energies",
+        &occupied
+            .iter()
+            .map(|&(op, j)| sci(orbitals.e(op)[j]))
+            .collect::<Vec<_>>(),
+    )?;
+    tag(
+        w,
+        "Molecular Orbital Spin Types",
+        &occupied
+            .iter()
+            .map(|&(op, _)| match (orbitals.restricted(), op) {
+                (true, _) => "Alpha and Beta".to_string(),
+                (false, 0) => "Alpha".to_string(),
+                (false, _) => "Beta".to_string(),
+            })
+            .collect::<Vec<_>>(),
+    )?;
+
+    writeln!(w, "<Molecular Orbital Primitive Coefficients>")?;
+    for (i, &(op, j)) in occupied.iter().enumerate() {
+        tag(w, "MO Number", &[(i + 1).to_string()])?;
+        let c = &coefficients[op];
+        for line in columns((0..c.rows).map(|p| sci(c[(p, j)]))) {
+            writeln!(w, "{}", line)?;
+        }
+    }
+    writeln!(w, "</Molecular Orbital Primitive Coefficients>")?;
+
+    tag(w, "Energy", &[sci(energy)])?;
+    tag(w, "Virial Ratio (-V/T)", &[sci(virial_ratio)])?;
+
+    Ok(())
+}
+
+/// Write wfx file `{name}.wfx`
+pub fn store_wfx(
+    name: &str,
+    geometry: &Geometry,
+    basis: &Basis,
+    orbitals: &Orbitals,
+    energy: f64,
+    virial_ratio: f64,
+) {
+    let mut file = File::create(name.to_owned() + ".wfx").expect("Unable to create file");
+    write_wfx(geometry, basis, orbitals, energy, virial_ratio, &mut file)
+        .expect("Unable to write wfx file");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Unit;
+    use crate::gto_basis_sets::{
+        basis::{cdim, gaussian_layout},
+        load_basis_set, BasisSet,
+    };
+    use crate::linear_algebra::{matrix::FMatrix, vector::FVector};
+    use crate::tests::water_xz;
+
+    #[test]
+    fn primitive_types() {
+        assert_eq!(primitive_type(&[0, 0, 0]), 1);
+        assert_eq!(primitive_type(&[0, 0, 1]), 4);
+        assert_eq!(primitive_type(&[0, 1, 1]), 10);
+        assert_eq!(primitive_type(&[1, 1, 1]), 20);
+        assert_eq!(primitive_type(&[1, 1, 2]), 35);
+        assert_eq!(primitive_type(&[0, 0, 5]), 36);
+        assert_eq!(primitive_type(&[5, 0, 0]), 56);
+
+        // every component appears exactly once
+        for l in 0..=5 {
+            let mut types = WFX_TYPES[l as usize].to_vec();
+            let mut layout = gaussian_layout(&l);
+            assert_eq!(types.len(), cdim(&l));
+            types.sort();
+            layout.sort();
+            assert_eq!(types, layout);
+        }
+    }
+
+    #[test]
+    fn write() {
+        let geometry = Geometry::new(
+            water_xz(),
+            0,
+            1,
+            Unit::AtomicUnits,
+        );
+        let basis = load_basis_set(&BasisSet::sto_3g, geometry.molecule.atoms());
+        let n = basis.dim();
+
+        let mut c = FMatrix::zero(n, n);
+        for i in 0..n {
+            c[(i, i)] = 1.0;
+        }
+        let orbitals = Orbitals::new_restricted(c, FVector::zero(n), 10);
+
+        let mut buffer = Vec::new();
+        write_wfx(&geometry, &basis, &orbitals, -74.96, 2.0, &mut buffer).unwrap();
+        let out = String::from_utf8(buffer).unwrap();
+
+        // STO-3G: 3 + 3 + 3x3 primitives on O and 3 on each H
+        assert!(out.contains("<Number of Primitives>\n21\n</Number of Primitives>"));
+        assert!(out.contains("<Number of Occupied Molecular Orbitals>\n5\n"));
+        assert!(out.contains("<Nuclear Names>\nO1\nH2\nH3\n</Nuclear Names>"));
+        assert_eq!(out.matches("<MO Number>").count(), 5);
+        assert_eq!(out.matches("Alpha and Beta").count(), 5);
+        assert!(out.contains("<Virial Ratio (-V/T)>"));
+    }
+}
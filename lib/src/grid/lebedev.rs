@@ -0,0 +1,258 @@
+use crate::linear_algebra::constants::PI;
+
+/// available Lebedev grids and the degree up to which they are exact
+pub const LEBEDEV_ORDERS: [(usize, usize); 11] = [
+    (6, 3),
+    (14, 5),
+    (26, 7),
+    (38, 9),
+    (50, 11),
+    (86, 15),
+    (110, 17),
+    (146, 19),
+    (170, 21),
+    (194, 23),
+    (302, 29),
+];
+
+/// Expand the symmetry unique generators of a Lebedev grid to unit vectors and weights
+/// (normalized to 4π). Generator types follow V. I. Lebedev and D. N. Laikov,
+/// Dokl. Math. 59, 477 (1999):
+///     1: (1, 0, 0), 2: (0, a, a), 3: (a, a, a), 4: (a, a, b), 5: (a, b, 0), 6: (a, b, c)
+pub fn lebedev(n: usize) -> Vec<([f64; 3], f64)> {
+    let table = match n {
+        6 => LEBEDEV_6,
+        14 => LEBEDEV_14,
+        26 => LEBEDEV_26,
+        38 => LEBEDEV_38,
+        50 => LEBEDEV_50,
+        86 => LEBEDEV_86,
+        110 => LEBEDEV_110,
+        146 => LEBEDEV_146,
+        170 => LEBEDEV_170,
+        194 => LEBEDEV_194,
+        302 => LEBEDEV_302,
+        _ => panic!("Lebedev grid with {} points is not available", n),
+    };
+
+    let mut grid = Vec::with_capacity(n);
+    for &(kind, a, b, w) in table {
+        let w = 4.0 * PI * w;
+        let points = match kind {
+            1 => permutations([1.0, 0.0, 0.0]),
+            2 => permutations([0.0, 0.5_f64.sqrt(), 0.5_f64.sqrt()]),
+            3 => permutations([(1.0_f64 / 3.0).sqrt(); 3]),
+            4 => permutations([a, a, (1.0 - 2.0 * a * a).sqrt()]),
+            5 => permutations([a, (1.0 - a * a).sqrt(), 0.0]),
+            6 => permutations([a, b, (1.0 - a * a - b * b).sqrt()]),
+            _ => panic!("Unknown Lebedev generator {}", kind),
+        };
+        grid.extend(points.into_iter().map(|p| (p, w)));
+    }
+
+    grid
+}
+
+/// All distinct signed permutations of a vector
+fn permutations(v: [f64; 3]) -> Vec<[f64; 3]> {
+    const ORDERS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    let mut points: Vec<[f64; 3]> = Vec::new();
+    for order in ORDERS {
+        for signs in 0..8 {
+            let point = [0, 1, 2].map(|k| {
+                let x = v[order[k]];
+                if signs & (1 << k) != 0 {
+                    -x
+                } else {
+                    x
+                }
+            });
+            if !points.contains(&point) {
+                points.push(point);
+            }
+        }
+    }
+
+    points
+}
+
+// --------------------------------
+// generators, weights normalized to 1
+// --------------------------------
+
+/// 6 points, exact up to degree 3
+const LEBEDEV_6: &[(u8, f64, f64, f64)] = &[(1, 0.0, 0.0, 0.16666666666666666)];
+
+/// 14 points, exact up to degree 5
+const LEBEDEV_14: &[(u8, f64, f64, f64)] =
+    &[(1, 0.0, 0.0, 0.06666666666666667), (3, 0.0, 0.0, 0.075)];
+
+/// 26 points, exact up to degree 7
+const LEBEDEV_26: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.047619047619047616),
+    (2, 0.0, 0.0, 0.03809523809523809),
+    (3, 0.0, 0.0, 0.03214285714285714),
+];
+
+/// 38 points, exact up to degree 9
+const LEBEDEV_38: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.009523809523809525),
+    (3, 0.0, 0.0, 0.03214285714285714),
+    (5, 0.45970084338098305, 0.0, 0.02857142857142857),
+];
+
+/// 50 points, exact up to degree 11
+const LEBEDEV_50: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.012698412698412698),
+    (2, 0.0, 0.0, 0.022574955908289243),
+    (3, 0.0, 0.0, 0.02109375),
+    (4, 0.30151134457776363, 0.0, 0.02017333553791887),
+];
+
+/// 86 points, exact up to degree 15
+const LEBEDEV_86: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.011544011544011544),
+    (3, 0.0, 0.0, 0.011943909085856283),
+    (4, 0.36960284645415026, 0.0, 0.011110555710603402),
+    (4, 0.6943540066026663, 0.0, 0.011876501294537142),
+    (5, 0.3742430390903412, 0.0, 0.011812303746904475),
+];
+
+/// 110 points, exact up to degree 17
+const LEBEDEV_110: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.0038282704949371615),
+    (3, 0.0, 0.0, 0.009793737512487513),
+    (4, 0.18511563534473616, 0.0, 0.008211737283191111),
+    (4, 0.6904210483822921, 0.0, 0.009942814891178103),
+    (4, 0.39568947305594193, 0.0, 0.009595471336070962),
+    (5, 0.4783690288121502, 0.0, 0.009694996361663029),
+];
+
+/// 146 points, exact up to degree 19
+const LEBEDEV_146: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.0005996313688621381),
+    (2, 0.0, 0.0, 0.0073729997186207565),
+    (3, 0.0, 0.0, 0.007210515360144488),
+    (4, 0.6764410400114264, 0.0, 0.007116355493117555),
+    (4, 0.41749612279654535, 0.0, 0.006753829486314478),
+    (4, 0.15746766720390817, 0.0, 0.007574394159054034),
+    (
+        6,
+        0.14035538117131832,
+        0.44933283232695576,
+        0.0069910873533032625,
+    ),
+];
+
+/// 170 points, exact up to degree 21
+const LEBEDEV_170: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.005544842902037364),
+    (2, 0.0, 0.0, 0.006071332770670752),
+    (3, 0.0, 0.0, 0.006383674773515093),
+    (4, 0.25512526211141345, 0.0, 0.00518338758774779),
+    (4, 0.6743601460362766, 0.0, 0.006317929009813725),
+    (4, 0.431891069671941, 0.0, 0.006201670006589077),
+    (5, 0.26139313603359887, 0.0, 0.005477143385137348),
+    (
+        6,
+        0.49904531617960374,
+        0.14466307443251153,
+        0.005968383987681156,
+    ),
+];
+
+/// 194 points, exact up to degree 23
+const LEBEDEV_194: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.0017823404472446112),
+    (2, 0.0, 0.0, 0.005716905949977102),
+    (3, 0.0, 0.0, 0.005573383178848738),
+    (4, 0.6712973442695226, 0.0, 0.005608704082587997),
+    (4, 0.28924656275754385, 0.0, 0.005158237711805383),
+    (4, 0.4446933178717437, 0.0, 0.0055187714672736135),
+    (4, 0.12993354476500668, 0.0, 0.004106777028169394),
+    (5, 0.34577021976112826, 0.0, 0.005051846064614809),
+    (
+        6,
+        0.15904171053835295,
+        0.8360360154824589,
+        0.0055302489162330935,
+    ),
+];
+
+/// 302 points, exact up to degree 29
+const LEBEDEV_302: &[(u8, f64, f64, f64)] = &[
+    (1, 0.0, 0.0, 0.0008545911725128148),
+    (3, 0.0, 0.0, 0.0035991192850255713),
+    (4, 0.3515640345570105, 0.0, 0.0034497884243058835),
+    (4, 0.6566329410219611, 0.0, 0.003604822601419882),
+    (4, 0.4729054132581005, 0.0, 0.003576729661743367),
+    (4, 0.09618308522614784, 0.0, 0.002352101413689164),
+    (4, 0.22196452362941785, 0.0, 0.0031089531224136754),
+    (4, 0.7011766416089544, 0.0, 0.0036500458076772556),
+    (5, 0.26441528870606623, 0.0, 0.0029823449631718037),
+    (5, 0.5718955891878961, 0.0, 0.00360082093221646),
+    (
+        6,
+        0.2510034751770465,
+        0.8000727494073951,
+        0.003571540554273387,
+    ),
+    (
+        6,
+        0.12335485325833274,
+        0.4127724083168531,
+        0.0033923122050061702,
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{lebedev, LEBEDEV_ORDERS};
+    use crate::linear_algebra::constants::PI;
+
+    /// (n)!! for odd n
+    fn double_factorial(n: i32) -> f64 {
+        (1..=n).rev().step_by(2).map(f64::from).product()
+    }
+
+    #[test]
+    fn monomials() {
+        // ∫ x^i y^j z^k dΩ over the unit sphere, odd exponents vanish by symmetry
+        for (n, degree) in LEBEDEV_ORDERS {
+            let grid = lebedev(n);
+            assert_eq!(grid.len(), n);
+
+            let degree = degree as i32;
+            for i in (0..=degree).step_by(2) {
+                for j in (0..=degree - i).step_by(2) {
+                    for k in (0..=degree - i - j).step_by(2) {
+                        let exact = 4.0
+                            * PI
+                            * double_factorial(i - 1)
+                            * double_factorial(j - 1)
+                            * double_factorial(k - 1)
+                            / double_factorial(i + j + k + 1);
+                        let value: f64 = grid
+                            .iter()
+                            .map(|(p, w)| w * p[0].powi(i) * p[1].powi(j) * p[2].powi(k))
+                            .sum();
+                        assert!((value - exact).abs() < 1e-13, "{n}: {i} {j} {k}");
+                    }
+                }
+            }
+
+            // unit vectors
+            for (p, _) in grid {
+                assert!((p[0] * p[0] + p[1] * p[1] + p[2] * p[2] - 1.0).abs() < 1e-14);
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Molecular integration grids: atom centred radial and Lebedev angular grids, combined
+//! by a fuzzy cell partitioning of space into atomic contributions
+
+pub mod lebedev;
+pub mod partition;
+pub mod radial;
+
+use crate::{
+    geometry::{atom::Atom, Geometry},
+    grid::{
+        lebedev::{lebedev, LEBEDEV_ORDERS},
+        partition::{AtomicPartition, Partition},
+        radial::RadialScheme,
+    },
+    linear_algebra::constants::ANG_AU,
+};
+
+/// points with smaller weights are dropped
+const WEIGHT_THRESHOLD: f64 = 1e-15;
+
+#[derive(Clone, Debug)]
+pub struct GridSettings {
+    pub radial_scheme: RadialScheme,
+    pub n_radial: usize,
+    pub n_angular: usize,
+    pub partition: Partition,
+
+    /// fewer angular points close to the nuclei
+    pub pruning: bool,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        Self {
+            radial_scheme: RadialScheme::TreutlerAhlrichs,
+            n_radial: 75,
+            n_angular: 302,
+            partition: Partition::Stratmann,
+            pruning: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MolecularGrid {
+    points: Vec<[f64; 3]>,
+    weights: Vec<f64>,
+}
+
+/// Getters
+impl MolecularGrid {
+    pub fn points(&self) -> &[[f64; 3]] {
+        &self.points
+    }
+
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+impl MolecularGrid {
+    pub fn new(geometry: &Geometry, settings: &GridSettings) -> Self {
+        Self::from_atoms(geometry.molecule.atoms(), settings)
+    }
+
+    pub fn from_atoms(atoms: &[Atom], settings: &GridSettings) -> Self {
+        if !LEBEDEV_ORDERS.iter().any(|(n, _)| *n == settings.n_angular) {
+            panic!(
+                "Lebedev grid with {} points is not available",
+                settings.n_angular
+            );
+        }
+
+        let partition = AtomicPartition::new(settings.partition, atoms);
+        let mut grid = Self::default();
+
+        // angular grids for the regions of an atom
+        let orders = match settings.pruning {
+            true => prune(settings.n_angular),
+            false => [settings.n_angular; 3],
+        };
+        let angular = orders.map(lebedev);
+
+        for (a, atom) in atoms.iter().enumerate() {
+            let radius = atom.el.bragg_radius() * ANG_AU;
+            for (r, w_r) in settings.radial_scheme.grid(settings.n_radial, atom) {
+                let region = match r / radius {
+                    x if x < 0.25 => 0,
+                    x if x < 0.5 => 1,
+                    _ => 2,
+                };
+
+                for (direction, w_a) in &angular[region] {
+                    let point = [0, 1, 2].map(|k| atom.origin[k] + r * direction[k]);
+                    let weight = w_r * w_a * partition.weight(a, &point);
+                    if weight.abs() > WEIGHT_THRESHOLD {
+                        grid.points.push(point);
+                        grid.weights.push(weight);
+                    }
+                }
+            }
+        }
+
+        grid
+    }
+}
+
+/// Angular grids of the inner, intermediate and valence region of an atom, split at a
+/// quarter and half of the Bragg-Slater radius, in the spirit of the regional pruning of
+/// O. Treutler and R. Ahlrichs, J. Chem. Phys. 102, 346 (1995)
+fn prune(n_angular: usize) -> [usize; 3] {
+    // smaller requested grids are never enlarged
+    let [inner, intermediate] = [26, 110].map(|n: usize| n.min(n_angular));
+
+    [inner, intermediate, n_angular]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{partition::Partition, radial::RadialScheme, GridSettings, MolecularGrid};
+    use crate::geometry::{Geometry, Unit};
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::linear_algebra::{constants::PI, matrix::FMatrix};
+    use crate::tests::water_xz;
+
+    #[test]
+    fn gaussians() {
+        // normalized Gaussians on every atom of water
+        let atoms = water_xz();
+        for radial_scheme in [
+            RadialScheme::Becke,
+            RadialScheme::TreutlerAhlrichs,
+            RadialScheme::MuraKnowles,
+        ] {
+            for partition in [Partition::Becke, Partition::Stratmann] {
+                let settings = GridSettings {
+                    radial_scheme,
+                    partition,
+                    ..Default::default()
+                };
+                let grid = MolecularGrid::from_atoms(&atoms, &settings);
+                assert_eq!(grid.points().len(), grid.weights().len());
+
+                let value: f64 = grid
+                    .points()
+                    .iter()
+                    .zip(grid.weights())
+                    .map(|(p, w)| {
+                        atoms
+                            .iter()
+                            .map(|atom| {
+                                let r2: f64 = (0..3).map(|k| (p[k] - atom.origin[k]).powi(2)).sum();
+                                (2.0 / PI).powf(1.5) * (-2.0 * r2).exp()
+                            })
+                            .sum::<f64>()
+                            * w
+                    })
+                    .sum();
+                assert!(
+                    (value - 3.0).abs() < 1e-5,
+                    "{radial_scheme} {partition}: {value}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn pruning() {
+        let atoms = water_xz();
+        let pruned = MolecularGrid::from_atoms(&atoms, &GridSettings::default());
+        let full = MolecularGrid::from_atoms(
+            &atoms,
+            &GridSettings {
+                pruning: false,
+                ..Default::default()
+            },
+        );
+        assert!(pruned.len() < full.len());
+    }
+
+    #[test]
+    fn scf_density() {
+        // converged RHF/STO-3G orbitals of water, the density has to integrate to the
+        // number of electrons
+        let geometry = Geometry::new(water_xz(), 0, 1, Unit::AtomicUnits);
+        let basis = load_basis_set(&BasisSet::sto_3g, geometry.molecule.atoms());
+        let c_occ = [
+            [0.9941648336, -0.2333722457, 0.0, 0.1023934134, 0.0],
+            [0.0262534779, 0.8389494781, 0.0, -0.5319965930, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 1.0],
+            [0.0042040932, 0.1245099128, 0.0, 0.7734503744, 0.0],
+            [0.0, 0.0, 0.6059591145, 0.0, 0.0],
+            [-0.0058082875, 0.1576515660, 0.4459806012, 0.2837219494, 0.0],
+            [
+                -0.0058082875,
+                0.1576515660,
+                -0.4459806012,
+                0.2837219494,
+                0.0,
+            ],
+        ];
+        let c_occ = FMatrix::new_from_vec(7, 5, &c_occ.concat());
+        let d = 2.0 * &c_occ * c_occ.transposed();
+
+        let grid = MolecularGrid::new(&geometry, &GridSettings::default());
+        let ao = basis.evaluate(grid.points());
+        let x = &ao * &d;
+        let n_electrons: f64 = grid
+            .weights()
+            .iter()
+            .enumerate()
+            .map(|(i, w)| w * x.row(i).zip(ao.row(i)).map(|(a, b)| a * b).sum::<f64>())
+            .sum();
+
+        assert!(
+            (n_electrons - geometry.n_electrons as f64).abs() < 1e-5,
+            "{n_electrons}"
+        );
+    }
+}
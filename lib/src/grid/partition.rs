@@ -0,0 +1,180 @@
+use crate::{geometry::atom::Atom, linear_algebra::functions::distance};
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// half width of the Stratmann cell function
+const STRATMANN_A: f64 = 0.64;
+
+/// Atomic partitioning of the molecular integral into fuzzy cells
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Partition {
+    /// A. D. Becke, J. Chem. Phys. 88, 2547 (1988)
+    Becke,
+    /// R. E. Stratmann, G. E. Scuseria and M. J. Frisch, Chem. Phys. Lett. 257, 213 (1996)
+    #[default]
+    Stratmann,
+}
+
+impl FromStr for Partition {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let partition = match s.to_uppercase().as_str() {
+            "BECKE" => Partition::Becke,
+            "STRATMANN" | "SSF" => Partition::Stratmann,
+            _ => return Err("Unknown partitioning"),
+        };
+
+        Ok(partition)
+    }
+}
+
+impl Display for Partition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Partition::Becke => write!(f, "Becke"),
+            Partition::Stratmann => write!(f, "Stratmann"),
+        }
+    }
+}
+
+/// Partition weights P_a(r) / \sum_b P_b(r) of the atoms of a molecule
+pub(crate) struct AtomicPartition<'a> {
+    partition: Partition,
+    atoms: &'a [Atom],
+
+    /// inverse interatomic distances
+    inverse_distances: Vec<Vec<f64>>,
+    /// distance to the nearest neighbour of every atom
+    nearest: Vec<f64>,
+}
+
+impl<'a> AtomicPartition<'a> {
+    pub(crate) fn new(partition: Partition, atoms: &'a [Atom]) -> Self {
+        let distances: Vec<Vec<f64>> = atoms
+            .iter()
+            .map(|a| {
+                atoms
+                    .iter()
+                    .map(|b| distance(&a.origin, &b.origin))
+                    .collect()
+            })
+            .collect();
+        let inverse_distances = distances
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|r| if *r > 0.0 { 1.0 / r } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        let nearest = distances
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, r)| *r)
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+
+        Self {
+            partition,
+            atoms,
+            inverse_distances,
+            nearest,
+        }
+    }
+
+    /// cell function s(μ) of the confocal elliptical coordinate μ
+    fn cell_function(&self, mu: f64) -> f64 {
+        match self.partition {
+            Partition::Becke => {
+                // three times iterated polynomial
+                let p = |x: f64| 1.5 * x - 0.5 * x.powi(3);
+                0.5 * (1.0 - p(p(p(mu))))
+            }
+            Partition::Stratmann => {
+                if mu <= -STRATMANN_A {
+                    1.0
+                } else if mu >= STRATMANN_A {
+                    0.0
+                } else {
+                    let x = mu / STRATMANN_A;
+                    let g =
+                        (35.0 * x - 35.0 * x.powi(3) + 21.0 * x.powi(5) - 5.0 * x.powi(7)) / 16.0;
+                    0.5 * (1.0 - g)
+                }
+            }
+        }
+    }
+
+    /// Weight of atom `a` at a point
+    pub(crate) fn weight(&self, a: usize, point: &[f64; 3]) -> f64 {
+        let r: Vec<f64> = self
+            .atoms
+            .iter()
+            .map(|atom| distance(point, &atom.origin))
+            .collect();
+
+        // Stratmann: points close to their own nucleus belong to it completely
+        if self.partition == Partition::Stratmann
+            && r[a] < 0.5 * (1.0 - STRATMANN_A) * self.nearest[a]
+        {
+            return 1.0;
+        }
+
+        let cell = |i: usize| -> f64 {
+            let mut p = 1.0;
+            for j in 0..self.atoms.len() {
+                if j != i {
+                    p *= self.cell_function((r[i] - r[j]) * self.inverse_distances[i][j]);
+                    if p == 0.0 {
+                        break;
+                    }
+                }
+            }
+            p
+        };
+
+        let own = cell(a);
+        if own == 0.0 {
+            return 0.0;
+        }
+        let total: f64 = (0..self.atoms.len()).map(cell).sum();
+        own / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AtomicPartition, Partition};
+    use crate::tests::water_xz;
+
+    #[test]
+    fn partition_of_unity() {
+        let atoms = water_xz();
+        let points = [
+            [0.1, 0.2, 0.3],
+            [1.0, -0.5, 0.7],
+            [-1.4, 0.0, 0.9],
+            [3.0, 2.0, 1.0],
+        ];
+
+        for partition in [Partition::Becke, Partition::Stratmann] {
+            let weights = AtomicPartition::new(partition, &atoms);
+            for point in &points {
+                let sum: f64 = (0..atoms.len()).map(|a| weights.weight(a, point)).sum();
+                assert!((sum - 1.0).abs() < 1e-14, "{partition}: {sum}");
+            }
+
+            // at a nucleus
+            assert!((weights.weight(0, &atoms[0].origin) - 1.0).abs() < 1e-14);
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    geometry::atom::Atom,
+    linear_algebra::constants::{ANG_AU, PI},
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum RadialScheme {
+    /// A. D. Becke, J. Chem. Phys. 88, 2547 (1988)
+    Becke,
+    /// M4 mapping with α = 0.6, O. Treutler and R. Ahlrichs, J. Chem. Phys. 102, 346 (1995)
+    #[default]
+    TreutlerAhlrichs,
+    /// M. E. Mura and P. J. Knowles, J. Chem. Phys. 104, 9848 (1996)
+    MuraKnowles,
+}
+
+impl FromStr for RadialScheme {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let scheme = match s.to_uppercase().replace(['-', '_'], "").as_str() {
+            "BECKE" => RadialScheme::Becke,
+            "TREUTLER" | "TREUTLERAHLRICHS" | "TA" => RadialScheme::TreutlerAhlrichs,
+            "MURAKNOWLES" | "MK" => RadialScheme::MuraKnowles,
+            _ => return Err("Unknown radial grid"),
+        };
+
+        Ok(scheme)
+    }
+}
+
+impl Display for RadialScheme {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RadialScheme::Becke => write!(f, "Becke"),
+            RadialScheme::TreutlerAhlrichs => write!(f, "Treutler-Ahlrichs"),
+            RadialScheme::MuraKnowles => write!(f, "Mura-Knowles"),
+        }
+    }
+}
+
+impl RadialScheme {
+    /// Radial quadrature with `n` points for an atom as (r, w), the weights include r^2
+    pub fn grid(&self, n: usize, atom: &Atom) -> Vec<(f64, f64)> {
+        match self {
+            RadialScheme::Becke => {
+                // half the Bragg-Slater radius, except for hydrogen
+                let scale = match atom.z() {
+                    1 => 1.0,
+                    _ => 0.5,
+                };
+                becke(n, scale * atom.el.bragg_radius() * ANG_AU)
+            }
+            RadialScheme::TreutlerAhlrichs => treutler_ahlrichs(n, treutler_xi(atom.z())),
+            RadialScheme::MuraKnowles => {
+                // larger extent for alkali and alkaline earth metals
+                let alpha = match atom.z() {
+                    3 | 4 | 11 | 12 | 19 | 20 => 7.0,
+                    _ => 5.0,
+                };
+                mura_knowles(n, alpha)
+            }
+        }
+    }
+}
+
+/// Gauss-Chebyshev quadrature of the second kind: nodes x_i on (-1, 1) and weights
+/// for ∫ f(x) dx
+fn chebyshev_second_kind(n: usize) -> impl Iterator<Item = (f64, f64)> {
+    (1..=n).map(move |i| {
+        let t = i as f64 * PI / (n as f64 + 1.0);
+        let x = t.cos();
+        (x, PI / (n as f64 + 1.0) * t.sin())
+    })
+}
+
+/// r = rm (1 + x) / (1 - x)
+fn becke(n: usize, rm: f64) -> Vec<(f64, f64)> {
+    chebyshev_second_kind(n)
+        .map(|(x, w)| {
+            let r = rm * (1.0 + x) / (1.0 - x);
+            let dr = 2.0 * rm / (1.0 - x).powi(2);
+            (r, w * dr * r * r)
+        })
+        .collect()
+}
+
+/// r = ξ / ln 2 (1 + x)^0.6 ln(2 / (1 - x))
+fn treutler_ahlrichs(n: usize, xi: f64) -> Vec<(f64, f64)> {
+    const ALPHA: f64 = 0.6;
+    let ln2 = 2.0_f64.ln();
+
+    chebyshev_second_kind(n)
+        .map(|(x, w)| {
+            let log = (2.0 / (1.0 - x)).ln();
+            let r = xi / ln2 * (1.0 + x).powf(ALPHA) * log;
+            let dr = xi / ln2
+                * (ALPHA * (1.0 + x).powf(ALPHA - 1.0) * log + (1.0 + x).powf(ALPHA) / (1.0 - x));
+            (r, w * dr * r * r)
+        })
+        .collect()
+}
+
+/// Scaling factors ξ of the Treutler-Ahlrichs mapping, ξ = 1 beyond Kr
+fn treutler_xi(z: u8) -> f64 {
+    const XI: [f64; 36] = [
+        0.8, 0.9, // H, He
+        1.8, 1.4, 1.3, 1.1, 0.9, 0.9, 0.9, 0.9, // Li - Ne
+        1.4, 1.3, 1.3, 1.2, 1.1, 1.0, 1.0, 1.0, // Na - Ar
+        1.5, 1.4, 1.3, 1.2, 1.2, 1.2, 1.2, 1.2, 1.2, 1.1, 1.1, 1.1, // K - Zn
+        1.1, 1.0, 0.9, 0.9, 0.9, 0.9, // Ga - Kr
+    ];
+
+    XI.get(usize::from(z) - 1).copied().unwrap_or(1.0)
+}
+
+/// r = -α ln(1 - x^3) with the midpoint rule on (0, 1)
+fn mura_knowles(n: usize, alpha: f64) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|i| {
+            let x = (i as f64 + 0.5) / n as f64;
+            let r = -alpha * (1.0 - x.powi(3)).ln();
+            let dr = 3.0 * alpha * x * x / (1.0 - x.powi(3));
+            (r, dr * r * r / n as f64)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{treutler_xi, RadialScheme};
+    use crate::geometry::atom::Atom;
+    use crate::linear_algebra::constants::PI;
+    use crate::misc::elements::Element::{H, O};
+
+    #[test]
+    fn radial() {
+        // ∫ r^2 exp(-a r^2) dr = sqrt(π) / (4 a^1.5) for tight and diffuse exponents
+        for scheme in [
+            RadialScheme::Becke,
+            RadialScheme::TreutlerAhlrichs,
+            RadialScheme::MuraKnowles,
+        ] {
+            for atom in [Atom::new(H, [0.0; 3]), Atom::new(O, [0.0; 3])] {
+                let grid = scheme.grid(100, &atom);
+                for a in [1e-1_f64, 1.0, 1e2, 1e4] {
+                    let exact = PI.sqrt() / (4.0 * a * a.sqrt());
+                    let value: f64 = grid.iter().map(|(r, w)| w * (-a * r * r).exp()).sum();
+                    assert!(
+                        ((value - exact) / exact).abs() < 1e-6,
+                        "{scheme} {} {a}: {value} != {exact}",
+                        atom.el
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        use std::str::FromStr;
+
+        assert_eq!(RadialScheme::from_str("becke"), Ok(RadialScheme::Becke));
+        assert_eq!(
+            RadialScheme::from_str("Treutler-Ahlrichs"),
+            Ok(RadialScheme::TreutlerAhlrichs)
+        );
+        assert_eq!(
+            RadialScheme::from_str("mura_knowles"),
+            Ok(RadialScheme::MuraKnowles)
+        );
+    }
+
+    #[test]
+    fn treutler_xi_default() {
+        assert_eq!(treutler_xi(8), 0.9);
+        assert_eq!(treutler_xi(54), 1.0);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::geometry::atom::Atom;
+use crate::gto_basis_sets::basis::{Basis, Shell};
+
+pub fn load_aug_cc_pvdz(atoms: &[Atom]) -> Basis {
+    println!("Loading aug-cc-pVDZ basis set");
+    const MAX_ATOMIC_NUMBER: usize = 8;
+
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); MAX_ATOMIC_NUMBER + 1];
+
+    // ----------------------------
+    // Element #1, Hydrogen
+    // ----------------------------
+    shells[1] = Vec::with_capacity(4);
+    // s
+    shells[1].push(Shell::new_general(
+        0,
+        vec![13.0100000, 1.9620000, 0.4446000, 0.1220000],
+        vec![
+            vec![0.0196850, 0.1379770, 0.4781480, 0.5012400],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // s
+    shells[1].push(Shell::new(0, vec![0.0297400], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![0.7270000], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![0.1410000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #6, Carbon
+    // ----------------------------
+    shells[6] = Vec::with_capacity(6);
+    // s
+    shells[6].push(Shell::new_general(
+        0,
+        vec![
+            6665.0000000,
+            1000.0000000,
+            228.0000000,
+            64.7100000,
+            21.0600000,
+            7.4950000,
+            2.7970000,
+            0.5215000,
+            0.1596000,
+        ],
+        vec![
+            vec![
+                0.0006920, 0.0053290, 0.0270770, 0.1017180, 0.2747400, 0.4485640, 0.2850740,
+                0.0152040, -0.0031910,
+            ],
+            vec![
+                -0.0001460, -0.0011540, -0.0057250, -0.0233120, -0.0639550, -0.1499810, -0.1272620,
+                0.5445290, 0.5804960,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[6].push(Shell::new(0, vec![0.0469000], vec![1.0000000]));
+    // p
+    shells[6].push(Shell::new_general(
+        1,
+        vec![9.4390000, 2.0020000, 0.5456000, 0.1517000],
+        vec![
+            vec![0.0381090, 0.2094800, 0.5085570, 0.4688420],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[6].push(Shell::new(1, vec![0.0404100], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.5500000], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.1510000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #7, Nitrogen
+    // ----------------------------
+    shells[7] = Vec::with_capacity(6);
+    // s
+    shells[7].push(Shell::new_general(
+        0,
+        vec![
+            9046.0000000,
+            1357.0000000,
+            309.3000000,
+            87.7300000,
+            28.5600000,
+            10.2100000,
+            3.8380000,
+            0.7466000,
+            0.2248000,
+        ],
+        vec![
+            vec![
+                0.0007000, 0.0053890, 0.0274060, 0.1032070, 0.2787230, 0.4485400, 0.2782380,
+                0.0154400, -0.0028640,
+            ],
+            vec![
+                -0.0001530, -0.0012080, -0.0059920, -0.0245440, -0.0674590, -0.1580780, -0.1218310,
+                0.5490030, 0.5788150,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[7].push(Shell::new(0, vec![0.0612400], vec![1.0000000]));
+    // p
+    shells[7].push(Shell::new_general(
+        1,
+        vec![13.5500000, 2.9170000, 0.7973000, 0.2185000],
+        vec![
+            vec![0.0399190, 0.2171690, 0.5103190, 0.4622140],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[7].push(Shell::new(1, vec![0.0561100], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.8170000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.2300000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #8, Oxygen
+    // ----------------------------
+    shells[8] = Vec::with_capacity(6);
+    // s
+    shells[8].push(Shell::new_general(
+        0,
+        vec![
+            11720.0000000,
+            1759.0000000,
+            400.8000000,
+            113.7000000,
+            37.0300000,
+            13.2700000,
+            5.0250000,
+            1.0130000,
+            0.3023000,
+        ],
+        vec![
+            vec![
+                0.0007100, 0.0054700, 0.0278370, 0.1048000, 0.2830620, 0.4487190, 0.2709520,
+                0.0154580, -0.0025850,
+            ],
+            vec![
+                -0.0001600, -0.0012630, -0.0062670, -0.0257160, -0.0709240, -0.1654110, -0.1169550,
+                0.5573680, 0.5727590,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[8].push(Shell::new(0, vec![0.0789600], vec![1.0000000]));
+    // p
+    shells[8].push(Shell::new_general(
+        1,
+        vec![17.7000000, 3.8540000, 1.0460000, 0.2753000],
+        vec![
+            vec![0.0430180, 0.2289130, 0.5087280, 0.4605310],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[8].push(Shell::new(1, vec![0.0685600], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![1.1850000], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![0.3320000], vec![1.0000000]));
+
+    Basis::new(atoms, shells)
+}
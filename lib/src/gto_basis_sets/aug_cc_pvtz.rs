@@ -0,0 +1,227 @@
+use crate::geometry::atom::Atom;
+use crate::gto_basis_sets::basis::{Basis, Shell};
+
+pub fn load_aug_cc_pvtz(atoms: &[Atom]) -> Basis {
+    println!("Loading aug-cc-pVTZ basis set");
+    const MAX_ATOMIC_NUMBER: usize = 8;
+
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); MAX_ATOMIC_NUMBER + 1];
+
+    // ----------------------------
+    // Element #1, Hydrogen
+    // ----------------------------
+    shells[1] = Vec::with_capacity(7);
+    // s
+    shells[1].push(Shell::new_general(
+        0,
+        vec![33.8700000, 5.0950000, 1.1590000, 0.3258000, 0.1027000],
+        vec![
+            vec![0.0060680, 0.0453080, 0.2028220, 0.5039030, 0.3834210],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000, 0.0000000],
+            vec![0.0000000, 0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // s
+    shells[1].push(Shell::new(0, vec![0.0252600], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![1.4070000], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![0.3880000], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![0.1020000], vec![1.0000000]));
+    // d
+    shells[1].push(Shell::new(2, vec![1.0570000], vec![1.0000000]));
+    // d
+    shells[1].push(Shell::new(2, vec![0.2470000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #6, Carbon
+    // ----------------------------
+    shells[6] = Vec::with_capacity(9);
+    // s
+    shells[6].push(Shell::new_general(
+        0,
+        vec![
+            8236.0000000,
+            1235.0000000,
+            280.8000000,
+            79.2700000,
+            25.5900000,
+            8.9970000,
+            3.3190000,
+            0.9059000,
+            0.3643000,
+            0.1285000,
+        ],
+        vec![
+            vec![
+                0.0005310, 0.0041080, 0.0210870, 0.0818530, 0.2348170, 0.4344010, 0.3461290,
+                0.0393780, -0.0089830, 0.0023850,
+            ],
+            vec![
+                -0.0001130, -0.0008780, -0.0045400, -0.0181330, -0.0557600, -0.1268950, -0.1703520,
+                0.1403820, 0.5986840, 0.3953890,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                1.0000000, 0.0000000, 0.0000000,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[6].push(Shell::new(0, vec![0.0440200], vec![1.0000000]));
+    // p
+    shells[6].push(Shell::new_general(
+        1,
+        vec![18.7100000, 4.1330000, 1.2000000, 0.3827000, 0.1209000],
+        vec![
+            vec![0.0140310, 0.0868660, 0.2902160, 0.5010080, 0.3434060],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000, 0.0000000],
+            vec![0.0000000, 0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[6].push(Shell::new(1, vec![0.0356900], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![1.0970000], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.3180000], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.1000000], vec![1.0000000]));
+    // f
+    shells[6].push(Shell::new(3, vec![0.7610000], vec![1.0000000]));
+    // f
+    shells[6].push(Shell::new(3, vec![0.2680000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #7, Nitrogen
+    // ----------------------------
+    shells[7] = Vec::with_capacity(9);
+    // s
+    shells[7].push(Shell::new_general(
+        0,
+        vec![
+            11420.0000000,
+            1712.0000000,
+            389.3000000,
+            110.0000000,
+            35.5700000,
+            12.5400000,
+            4.6440000,
+            1.2930000,
+            0.5118000,
+            0.1787000,
+        ],
+        vec![
+            vec![
+                0.0005230, 0.0040450, 0.0207750, 0.0807270, 0.2330740, 0.4335010, 0.3474720,
+                0.0412620, -0.0085080, 0.0023840,
+            ],
+            vec![
+                -0.0001150, -0.0008950, -0.0046240, -0.0185280, -0.0573390, -0.1320760, -0.1725100,
+                0.1518140, 0.5999440, 0.3874620,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                1.0000000, 0.0000000, 0.0000000,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[7].push(Shell::new(0, vec![0.0576000], vec![1.0000000]));
+    // p
+    shells[7].push(Shell::new_general(
+        1,
+        vec![26.6300000, 5.9480000, 1.7420000, 0.5550000, 0.1725000],
+        vec![
+            vec![0.0146700, 0.0917640, 0.2986830, 0.4984870, 0.3370230],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000, 0.0000000],
+            vec![0.0000000, 0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[7].push(Shell::new(1, vec![0.0491000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![1.6540000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.4690000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.1510000], vec![1.0000000]));
+    // f
+    shells[7].push(Shell::new(3, vec![1.0930000], vec![1.0000000]));
+    // f
+    shells[7].push(Shell::new(3, vec![0.3640000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #8, Oxygen
+    // ----------------------------
+    shells[8] = Vec::with_capacity(9);
+    // s
+    shells[8].push(Shell::new_general(
+        0,
+        vec![
+            15330.0000000,
+            2299.0000000,
+            522.4000000,
+            147.3000000,
+            47.5500000,
+            16.7600000,
+            6.2070000,
+            1.7520000,
+            0.6882000,
+            0.2384000,
+        ],
+        vec![
+            vec![
+                0.0005080, 0.0039290, 0.0202430, 0.0791810, 0.2306870, 0.4331180, 0.3502600,
+                0.0427670, -0.0081540, 0.0023810,
+            ],
+            vec![
+                -0.0001150, -0.0008950, -0.0046360, -0.0187240, -0.0584630, -0.1364630, -0.1757400,
+                0.1609340, 0.6034180, 0.3787650,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                1.0000000, 0.0000000, 0.0000000,
+            ],
+            vec![
+                0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000, 0.0000000,
+                0.0000000, 0.0000000, 1.0000000,
+            ],
+        ],
+    ));
+    // s
+    shells[8].push(Shell::new(0, vec![0.0737600], vec![1.0000000]));
+    // p
+    shells[8].push(Shell::new_general(
+        1,
+        vec![34.4600000, 7.7490000, 2.2800000, 0.7156000, 0.2140000],
+        vec![
+            vec![0.0159280, 0.0997400, 0.3104920, 0.4910260, 0.3363370],
+            vec![0.0000000, 0.0000000, 0.0000000, 1.0000000, 0.0000000],
+            vec![0.0000000, 0.0000000, 0.0000000, 0.0000000, 1.0000000],
+        ],
+    ));
+    // p
+    shells[8].push(Shell::new(1, vec![0.0597400], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![2.3140000], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![0.6450000], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![0.2140000], vec![1.0000000]));
+    // f
+    shells[8].push(Shell::new(3, vec![1.4280000], vec![1.0000000]));
+    // f
+    shells[8].push(Shell::new(3, vec![0.5000000], vec![1.0000000]));
+
+    Basis::new(atoms, shells)
+}
@@ -0,0 +1,262 @@
+use crate::{
+    geometry::atom::Atom,
+    gto_basis_sets::basis::{Basis, Shell},
+    misc::elements::Element,
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    fs,
+    str::FromStr,
+};
+
+/// ratio of successive exponents of the generated even-tempered basis sets
+const ETB_BETA: f64 = 2.0;
+
+/// Auxiliary (fitting) basis set for the resolution of the identity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum AuxiliaryBasis {
+    /// even-tempered functions generated from the exponents of the orbital basis set
+    #[default]
+    EvenTempered,
+    /// basis set file in Gaussian94 format, e.g. def2-SVP-RI from the Basis Set Exchange
+    File(String),
+}
+
+impl FromStr for AuxiliaryBasis {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let aux = match s.to_lowercase().as_str() {
+            "etb" | "auto" => AuxiliaryBasis::EvenTempered,
+            _ => AuxiliaryBasis::File(s.to_string()),
+        };
+
+        Ok(aux)
+    }
+}
+
+impl Display for AuxiliaryBasis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuxiliaryBasis::EvenTempered => write!(f, "even-tempered"),
+            AuxiliaryBasis::File(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl AuxiliaryBasis {
+    /// Auxiliary basis on the atoms, matching the cartesian flag of the orbital basis
+    pub fn load(&self, basis: &Basis, atoms: &[Atom]) -> Basis {
+        let mut aux = match self {
+            AuxiliaryBasis::EvenTempered => even_tempered(basis, atoms),
+            AuxiliaryBasis::File(name) => load_gaussian94(name, atoms),
+        };
+        aux.set_cartesian(basis.cartesian());
+
+        aux
+    }
+}
+
+/// Even-tempered auxiliary basis for the orbital products of a basis set: for every
+/// angular momentum l1 + l2 of a product the exponents span the range of the sums of the
+/// (geometrically averaged) orbital exponents, in the spirit of the automatic auxiliary
+/// basis sets of PySCF. All atoms get auxiliary functions up to one above the highest
+/// angular momentum of the molecule, so that products of functions on different atoms can
+/// be fitted as well. Higher product angular momenta contribute little to the fitted
+/// energies but would dominate the size of the auxiliary basis.
+fn even_tempered(basis: &Basis, atoms: &[Atom]) -> Basis {
+    println!("Generating even-tempered auxiliary basis set");
+
+    let max_z = atoms
+        .iter()
+        .map(|atom| usize::from(atom.z()))
+        .max()
+        .unwrap_or(0);
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); max_z + 1];
+    let l_max = basis
+        .shells()
+        .iter()
+        .map(|shell| usize::from(*shell.l()))
+        .max()
+        .unwrap_or(0);
+
+    for (i, atom) in atoms.iter().enumerate() {
+        let z = usize::from(atom.z());
+        if !shells[z].is_empty() {
+            continue;
+        }
+
+        // exponent range of the orbital basis per angular momentum
+        let mut range = vec![(f64::INFINITY, 0.0_f64); l_max + 1];
+        for shell in basis.shells().iter().filter(|shell| shell.atom() == i) {
+            let l = usize::from(*shell.l());
+            for e in shell.exps() {
+                range[l] = (range[l].0.min(*e), range[l].1.max(*e));
+            }
+        }
+
+        // angular momenta missing on this atom get its overall exponent range
+        let overall = range.iter().fold((f64::INFINITY, 0.0_f64), |x, y| {
+            (x.0.min(y.0), x.1.max(y.1))
+        });
+        for r in range.iter_mut().filter(|r| !r.0.is_finite()) {
+            *r = overall;
+        }
+
+        for l in 0..=l_max + 1 {
+            let mut e_min = f64::INFINITY;
+            let mut e_max: f64 = 0.0;
+            for l1 in l.saturating_sub(l_max)..=l.min(l_max) {
+                let ((min1, max1), (min2, max2)) = (range[l1], range[l - l1]);
+                e_min = e_min.min((min1 * min2).sqrt());
+                e_max = e_max.max((max1 * max2).sqrt());
+            }
+
+            // sum of two exponents on the same centre
+            let (e_min, e_max) = (2.0 * e_min, 2.0 * e_max);
+            let n = ((e_max / e_min).ln() / ETB_BETA.ln()).ceil() as i32 + 1;
+            for k in (0..n).rev() {
+                shells[z].push(Shell::new(
+                    l as u8,
+                    vec![e_min * ETB_BETA.powi(k)],
+                    vec![1.0],
+                ));
+            }
+        }
+    }
+
+    Basis::new(atoms, shells)
+}
+
+/// Load a basis set from a file in Gaussian94 format
+pub fn load_gaussian94(name: &str, atoms: &[Atom]) -> Basis {
+    println!("Loading basis set from {}", name);
+
+    let content = fs::read_to_string(name).expect("Unable to read basis set file");
+    let number = |s: &str| -> f64 {
+        s.replace(['D', 'd'], "E")
+            .parse()
+            .expect("Unable to parse basis set file")
+    };
+
+    let max_z = atoms
+        .iter()
+        .map(|atom| usize::from(atom.z()))
+        .max()
+        .unwrap_or(0);
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); max_z + 1];
+
+    let mut lines = content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('!'));
+    let mut element: Option<usize> = None;
+    while let Some(line) = lines.next() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        if line.starts_with("****") {
+            element = None;
+            continue;
+        }
+
+        // element header, e.g. "O     0"
+        let z = match element {
+            Some(z) => z,
+            None => {
+                let symbol = fields[0].trim_start_matches('-');
+                let symbol = symbol[..1].to_uppercase() + &symbol[1..].to_lowercase();
+                let el = Element::from_str(&symbol).expect("Unknown element in basis set file");
+                element = Some(usize::from(el.atomic_number()));
+                continue;
+            }
+        };
+
+        // shell header, e.g. "S   3   1.00", followed by the primitives
+        let kind = fields[0].to_uppercase();
+        let n_primitives: usize = fields[1].parse().expect("Unable to parse basis set file");
+        let scale = fields.get(2).map_or(1.0, |s| number(s));
+        let primitives: Vec<Vec<f64>> = (0..n_primitives)
+            .map(|_| {
+                lines
+                    .next()
+                    .expect("Unexpected end of basis set file")
+                    .split_whitespace()
+                    .map(number)
+                    .collect()
+            })
+            .collect();
+        let exps: Vec<f64> = primitives.iter().map(|p| p[0] * scale * scale).collect();
+        let coefs = |k: usize| -> Vec<f64> { primitives.iter().map(|p| p[k]).collect() };
+
+        if z > max_z {
+            continue;
+        }
+
+        if kind == "SP" || kind == "L" {
+            shells[z].extend(Shell::sp(exps, coefs(1), coefs(2)));
+        } else {
+            let l = ['S', 'P', 'D', 'F', 'G', 'H', 'I']
+                .iter()
+                .position(|c| kind == c.to_string())
+                .expect("Unknown shell type in basis set file");
+            shells[z].push(Shell::new(l as u8, exps, coefs(1)));
+        }
+    }
+
+    for atom in atoms {
+        if shells[usize::from(atom.z())].is_empty() {
+            panic!("No basis functions for {} in {}", atom.el, name);
+        }
+    }
+
+    Basis::new(atoms, shells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_gaussian94, AuxiliaryBasis};
+    use crate::geometry::atom::Atom;
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::misc::elements::Element::{H, O};
+
+    #[test]
+    fn gaussian94() {
+        let name = std::env::temp_dir().join("gaussian94_test.gbs");
+        std::fs::write(
+            &name,
+            "! test\n****\nH     0\nS   2   1.00\n      0.3425250914D+01       0.1543289673D+00\n      \
+             0.6239137298D+00       0.5353281423D+00\nP   1   1.00\n 0.8 1.0\n****\n\
+             O     0\nSP   1   1.00\n 0.5 0.3 0.7\n****\n",
+        )
+        .unwrap();
+
+        let atoms = vec![Atom::new(O, [0.0; 3]), Atom::new(H, [0.0, 0.0, 1.0])];
+        let basis = load_gaussian94(name.to_str().unwrap(), &atoms);
+        std::fs::remove_file(name).unwrap();
+
+        // O: s, p; H: s, p
+        assert_eq!(basis.shells().len(), 4);
+        assert_eq!(basis.dim(), 8);
+        assert_eq!(basis.shells()[2].exps(), &[3.425250914, 0.6239137298]);
+    }
+
+    #[test]
+    fn even_tempered() {
+        let atoms = vec![Atom::new(O, [0.0; 3]), Atom::new(H, [0.0, 0.0, 1.0])];
+        let basis = load_basis_set(&BasisSet::def2_svp, &atoms);
+        let aux = AuxiliaryBasis::EvenTempered.load(&basis, &atoms);
+
+        // up to f functions for the d functions of oxygen, also on hydrogen
+        for atom in 0..atoms.len() {
+            let l_max = aux
+                .shells()
+                .iter()
+                .filter(|shell| shell.atom() == atom)
+                .map(|shell| *shell.l())
+                .max();
+            assert_eq!(l_max, Some(3));
+        }
+        assert!(aux.dim() > 3 * basis.dim());
+    }
+}
@@ -123,12 +123,8 @@ element_names = {
     "118": "Oganesson",
 }
 
-labels = {
-    0: "s",
-    1: "p",
-    2: "d",
-    3: "f",
-}
+# orbital labels indexed by angular momentum (j is skipped by convention)
+labels = "spdfghiklm"
 
 
 # parse URL
@@ -174,7 +170,14 @@ def run():
 
     # basis set name
     name = parsed_json["name"]
-    lowercase = name.lower().replace("-", "_")
+    lowercase = (
+        name.lower()
+        .replace("-", "_")
+        .replace("*", "_star")
+        .replace("(", "_")
+        .replace(",", "_")
+        .replace(")", "")
+    )
 
     # sorted list of element numbers
     keys = [int(i) for i in parsed_json["elements"]]
@@ -209,8 +212,22 @@ pub fn load_{lowercase}(atoms: &[Atom]) -> Basis {{
         for shell in shells:
             nl = len(shell["angular_momentum"])
             nshell = len(shell["coefficients"])
+            # SP shell: s and p share exponents
+            if shell["angular_momentum"] == [0, 1]:
+                exps = [float(i) for i in shell["exponents"]]
+                coeffs_s = [float(i) for i in shell["coefficients"][0]]
+                coeffs_p = [float(i) for i in shell["coefficients"][1]]
+                print(
+                    f"""    // sp
+    shells[{el}].extend(Shell::sp(
+        vec!{exps},
+        vec!{coeffs_s},
+        vec!{coeffs_p},
+    ));
+"""
+                )
             # 1 angular momentum, for every coefficient
-            if nl == nshell:
+            elif nl == nshell:
                 for s in range(0, nl):
                     exps, coeffs = exp_coef(shell, s)
                     print(
@@ -222,19 +239,19 @@ pub fn load_{lowercase}(atoms: &[Atom]) -> Basis {{
     ));
 """
                     )
-            # 1 angular momentum, multiple coefficients
+            # 1 angular momentum, multiple coefficients (general contraction)
             elif nl == 1 and nshell > 1:
-                for s in range(0, nshell):
-                    exps, coeffs = exp_coef(shell, s)
-                    print(
-                        f"""    // {labels[shell["angular_momentum"][0]]}
-    shells[{el}].push(Shell::new(
+                exps = [float(i) for i in shell["exponents"]]
+                coeffs = [[float(i) for i in c] for c in shell["coefficients"]]
+                print(
+                    f"""    // {labels[shell["angular_momentum"][0]]}
+    shells[{el}].push(Shell::new_general(
         {shell["angular_momentum"][0]},
         vec!{exps},
-        vec!{coeffs},
+        vec!{[f"vec!{c}" for c in coeffs]},
     ));
-"""
-                    )
+""".replace("'", "")
+                )
             else:
                 print("Unsupported shell layout")
                 exit(1)
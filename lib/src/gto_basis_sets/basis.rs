@@ -1,5 +1,6 @@
 use crate::{
     geometry::atom::Atom,
+    gto_basis_sets::export::{BasisWriter, Orca},
     linear_algebra::{
         constants::PI,
         functions::{BinomialCoefficient, Factorial},
@@ -20,13 +21,46 @@ use serde::{Deserialize, Serialize};
 pub struct Shell {
     l: u8,
     exps: Vec<f64>,
-    coefs: Vec<f64>,
+    // contraction coefficients, one column per contracted function
+    coefs: Vec<Vec<f64>>,
 }
 
 impl Shell {
     pub fn new(l: u8, exps: Vec<f64>, coefs: Vec<f64>) -> Self {
+        Self::new_general(l, exps, vec![coefs])
+    }
+
+    /// Generally contracted shell: several contracted functions built from the same
+    /// set of primitives, as used by the correlation-consistent basis sets
+    pub fn new_general(l: u8, exps: Vec<f64>, coefs: Vec<Vec<f64>>) -> Self {
+        if coefs.iter().any(|c| c.len() != exps.len()) {
+            panic!("Number of contraction coefficients does not match number of exponents");
+        }
+
         Self { l, exps, coefs }
     }
+
+    /// SP (or L) shell as used by the Pople basis sets: an s and a p shell that share
+    /// the same exponents, but have their own contraction coefficients. Shells carry a
+    /// single angular momentum, so the SP shell is stored as its s and p halves, which
+    /// span exactly the same functions.
+    pub fn sp(exps: Vec<f64>, coefs_s: Vec<f64>, coefs_p: Vec<f64>) -> [Self; 2] {
+        [
+            Self::new(0, exps.clone(), coefs_s),
+            Self::new(1, exps, coefs_p),
+        ]
+    }
+}
+
+/// orbital labels in order of increasing angular momentum (j is skipped by convention)
+const L_LABELS: [char; 10] = ['s', 'p', 'd', 'f', 'g', 'h', 'i', 'k', 'l', 'm'];
+
+/// return the orbital label (s, p, d, ...) for angular momentum l
+pub fn l_label(l: &u8) -> char {
+    match L_LABELS.get(usize::from(*l)) {
+        Some(label) => *label,
+        None => panic!("No orbital label for l = {}", l),
+    }
 }
 
 /// return sperical harmonics basis dimension
@@ -42,6 +76,9 @@ pub fn cdim(l: &u8) -> usize {
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct BasisShell {
     origin: [f64; 3],
+    // index of the atom the shell is centered on
+    #[serde(default)]
+    atom: usize,
     shell: Shell,
 
     cbf: Vec<CartesianBasisFunction>,
@@ -51,6 +88,7 @@ impl BasisShell {
     pub fn new(origin: [f64; 3], shell: Shell) -> Self {
         let mut basis_shell = Self {
             origin,
+            atom: 0,
             shell,
             cbf: vec![],
         };
@@ -59,14 +97,37 @@ impl BasisShell {
         basis_shell
     }
 
-    /// return sperical harmonicas basis dimension
+    /// return sperical harmonicas basis dimension (all contractions)
     pub fn dim(&self) -> usize {
-        dim(&self.shell.l)
+        self.n_contractions() * dim(&self.shell.l)
     }
 
-    /// return cartesian basis dimension
+    /// return cartesian basis dimension (all contractions)
     pub fn cdim(&self) -> usize {
-        cdim(&self.shell.l)
+        self.n_contractions() * cdim(&self.shell.l)
+    }
+
+    /// number of contracted functions sharing the primitives of this shell
+    pub fn n_contractions(&self) -> usize {
+        self.shell.coefs.len()
+    }
+
+    pub fn origin(&self) -> &[f64; 3] {
+        &self.origin
+    }
+
+    pub fn atom(&self) -> usize {
+        self.atom
+    }
+
+    pub fn exps(&self) -> &[f64] {
+        &self.shell.exps
+    }
+
+    /// contraction coefficients as given by the basis set, one column per contracted
+    /// function
+    pub fn coefs(&self) -> &[Vec<f64>] {
+        &self.shell.coefs
     }
 
     // pub fn shell(&self) -> &Shell {
@@ -91,7 +152,7 @@ impl BasisShell {
                 ml: g,
                 exps: self.shell.exps.to_vec(),
                 coefs: self.shell.coefs.to_vec(),
-                norm: Vec::with_capacity(self.shell.coefs.len()),
+                norm: Vec::with_capacity(self.shell.exps.len()),
             });
         }
 
@@ -107,15 +168,21 @@ pub struct Basis {
 
     l_max: u8,
     cartesian_to_sperical_trafo: HashMap<u8, FMatrix>,
+
+    // use cartesian (6d, 10f, ...) instead of pure spherical harmonic functions
+    #[serde(default)]
+    cartesian: bool,
 }
 
 impl Basis {
     pub fn new(atoms: &[Atom], shells: Vec<Vec<Shell>>) -> Self {
         let mut basis_shells: Vec<BasisShell> = Default::default();
         let mut l_max = 0;
-        for atom in atoms {
+        for (i, atom) in atoms.iter().enumerate() {
             for shell in &shells[atom.z() as usize] {
-                basis_shells.push(BasisShell::new(atom.origin.clone(), shell.clone()));
+                let mut basis_shell = BasisShell::new(atom.origin.clone(), shell.clone());
+                basis_shell.atom = i;
+                basis_shells.push(basis_shell);
                 if shell.l > l_max {
                     l_max = shell.l;
                 }
@@ -132,12 +199,31 @@ impl Basis {
             shells: basis_shells,
             l_max,
             cartesian_to_sperical_trafo,
+            cartesian: false,
         }
     }
 
-    /// return sperical harmonicas basis dimension
+    /// Switch between cartesian and pure spherical harmonic basis functions
+    pub fn set_cartesian(&mut self, cartesian: bool) {
+        self.cartesian = cartesian;
+    }
+
+    pub fn cartesian(&self) -> bool {
+        self.cartesian
+    }
+
+    /// return basis dimension, honoring the cartesian flag
     pub fn dim(&self) -> usize {
-        self.shells.iter().map(|s| s.dim()).sum()
+        (0..self.shells.len()).map(|sn| self.shell_dim(sn)).sum()
+    }
+
+    /// return dimension of shell `sn`, honoring the cartesian flag
+    pub fn shell_dim(&self, sn: usize) -> usize {
+        if self.cartesian {
+            self.shells[sn].cdim()
+        } else {
+            self.shells[sn].dim()
+        }
     }
 
     /// return sperical harmonicas basis dimension
@@ -156,8 +242,28 @@ impl Basis {
         }
     }
 
+    /// Cartesian to spherical transformation matrix for a whole shell, i.e. one
+    /// block per contracted function
+    pub fn shell_trafo(&self, shell: &BasisShell) -> FMatrix {
+        let t = self.trafo_matrix(shell.l());
+        let n = shell.n_contractions();
+        if n == 1 {
+            return t.clone();
+        }
+
+        let mut mat = FMatrix::zero(n * t.rows, n * t.cols);
+        for c in 0..n {
+            for i in 0..t.rows {
+                for j in 0..t.cols {
+                    mat[(c * t.rows + i, c * t.cols + j)] = t[(i, j)];
+                }
+            }
+        }
+        mat
+    }
+
     pub fn offset(&self, sn: usize) -> usize {
-        (0..sn).map(|i| self.shells[i].dim()).sum()
+        (0..sn).map(|i| self.shell_dim(i)).sum()
     }
 
     // fn coffset(&self, sn: &usize) -> usize {
@@ -308,14 +414,14 @@ impl Basis {
                     .get_mut(&el)
                     .unwrap()
                     .entry(*shell.l())
-                    .and_modify(|counter| *counter += 1)
-                    .or_insert(1);
+                    .and_modify(|counter| *counter += shell.n_contractions() as u8)
+                    .or_insert(shell.n_contractions() as u8);
                 primitives
                     .get_mut(&el)
                     .unwrap()
                     .entry(*shell.l())
-                    .and_modify(|pattern| pattern.push(shell.shell.coefs.len() as u8))
-                    .or_insert(vec![shell.shell.coefs.len() as u8]);
+                    .and_modify(|pattern| pattern.push(shell.shell.exps.len() as u8))
+                    .or_insert(vec![shell.shell.exps.len() as u8]);
             }
 
             count += 1;
@@ -330,10 +436,14 @@ BASIS SET INFORMATION
 "#
         );
 
-        // map numerical value of l to orbital label s,p,d,f, etc.
-        let labels: HashMap<u8, &str> = vec![(0, "s"), (1, "p"), (2, "d"), (3, "f")]
-            .into_iter()
-            .collect();
+        println!(
+            "Angular functions: {}\n",
+            if self.cartesian {
+                "cartesian"
+            } else {
+                "spherical"
+            }
+        );
 
         // for each unique element present shell layout
         for el in &elements {
@@ -346,14 +456,14 @@ BASIS SET INFORMATION
                     .map(|(l, pattern)| format!(
                         "{}{}",
                         pattern.iter().map(|&i| i as usize).sum::<usize>(),
-                        labels.get(l).unwrap()
+                        l_label(l)
                     ))
                     .collect::<String>(),
                 contracted
                     .get(el)
                     .unwrap()
                     .iter()
-                    .map(|(l, n)| format!("{n}{}", labels.get(l).unwrap()))
+                    .map(|(l, n)| format!("{n}{}", l_label(l)))
                     .collect::<String>(),
                 primitives
                     .get(el)
@@ -368,14 +478,6 @@ BASIS SET INFORMATION
 
     /// Print basis set information in ORCA format
     pub fn print_orca(&self, atoms: &[Atom]) {
-        // list of unique elements
-        let elements: BTreeSet<Element> = atoms.iter().map(|atom| atom.el.clone()).collect();
-
-        // map numerical value of l to orbital label s,p,d,f, etc.
-        let labels: HashMap<u8, &str> = vec![(0, "S"), (1, "P"), (2, "D"), (3, "F")]
-            .into_iter()
-            .collect();
-
         // print banner
         println!(
             r#"
@@ -385,38 +487,9 @@ BASIS SET IN ORCA FORMAT
 "#
         );
 
-        // for each unique element present print basis set information
-        for element in &elements {
-            println!("# Basis set for element : {element}");
-            println!("NewGTO {element}");
-
-            // iterate over atoms until we find one that matches the current element
-            for atom in atoms {
-                if *element == atom.el {
-                    for shell in &self.shells {
-                        // dangerous to do float comparison?
-                        if atom.origin != shell.origin {
-                            continue;
-                        }
-
-                        // print shell
-                        let s = &shell.shell;
-                        println!("{} {}", labels.get(&s.l).unwrap(), s.coefs.len());
-                        for i in 0..s.coefs.len() {
-                            println!(
-                                " {:2}  {:17.10} {:17.10}",
-                                i + 1,
-                                s.exps[i],
-                                shell.cbf[0].coefs[i]
-                            );
-                        }
-                    }
-
-                    break;
-                }
-            }
-            println!(" end;\n")
-        }
+        Orca.write(self, atoms, &mut std::io::stdout())
+            .expect("Unable to write basis set");
+        println!();
     }
 }
 
@@ -426,7 +499,9 @@ pub struct CartesianBasisFunction {
     ml: [u8; 3],
 
     exps: Vec<f64>,
-    coefs: Vec<f64>,
+    // normalized contraction coefficients, one column per contracted function
+    coefs: Vec<Vec<f64>>,
+    // primitive normalization
     norm: Vec<f64>,
 }
 
@@ -452,7 +527,7 @@ impl CartesianBasisFunction {
         &self.exps
     }
 
-    pub fn coefs(&self) -> &[f64] {
+    pub fn coefs(&self) -> &[Vec<f64>] {
         &self.coefs
     }
 
@@ -482,25 +557,30 @@ impl CartesianBasisFunction {
         }
 
         let prefactor = PI.powf(1.5) * f_ijk / 2.0_f64.powf(l_total);
-        let mut norm = 0.0;
 
-        for ia in 0..self.exps.len() {
-            for ib in 0..self.exps.len() {
-                norm += self.norm[ia] * self.norm[ib] * self.coefs[ia] * self.coefs[ib]
-                    / (self.exps[ia] + self.exps[ib]).powf(l_total + 1.5);
+        for coefs in &mut self.coefs {
+            let mut norm = 0.0;
+
+            for ia in 0..self.exps.len() {
+                for ib in 0..self.exps.len() {
+                    norm += self.norm[ia] * self.norm[ib] * coefs[ia] * coefs[ib]
+                        / (self.exps[ia] + self.exps[ib]).powf(l_total + 1.5);
+                }
             }
-        }
 
-        norm *= prefactor;
-        norm = norm.powf(-0.5);
+            norm *= prefactor;
+            norm = norm.powf(-0.5);
 
-        for c in &mut self.coefs {
-            *c *= norm;
+            for c in coefs.iter_mut() {
+                *c *= norm;
+            }
         }
     }
 }
 
-fn gaussian_layout(l: &u8) -> Vec<[u8; 3]> {
+/// cartesian components (lx, ly, lz) of a shell in the order used throughout ferric,
+/// e.g. xx, xy, xz, yy, yz, zz for d functions
+pub fn gaussian_layout(l: &u8) -> Vec<[u8; 3]> {
     let n = (l + 1) * (l + 2) / 2;
     let mut layout: Vec<[u8; 3]> = Vec::with_capacity(n as usize);
 
@@ -544,7 +624,45 @@ mod tests {
     //   cbf.normalize();
     // }
 
-    use crate::gto_basis_sets::sto_3g;
+    use super::{cdim, dim, Basis, BasisShell, Shell};
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::gto_integrals::overlap::overlap;
+    use crate::linear_algebra::matrix::FMatrix;
+    use crate::tests::water_xz;
+
+    #[test]
+    fn cartesian_dim() {
+        let atoms = water_xz();
+
+        // def2-SVP: O [3s2p1d], H [2s1p]
+        let mut basis = load_basis_set(&BasisSet::def2_svp, &atoms);
+        assert!(!basis.cartesian());
+        assert_eq!(basis.dim(), 24);
+        assert_eq!(basis.cdim(), 25);
+        assert_eq!(basis.shell_dim(5), 5);
+        assert_eq!(basis.offset(6), 14);
+
+        basis.set_cartesian(true);
+        assert_eq!(basis.dim(), 25);
+        assert_eq!(basis.shell_dim(5), 6);
+        assert_eq!(basis.offset(6), 15);
+    }
+
+    #[test]
+    fn general_contraction() {
+        let atoms = water_xz();
+
+        // cc-pVDZ: O [3s2p1d], H [2s1p], each l is a single generally contracted shell
+        let basis = load_basis_set(&BasisSet::cc_pvdz, &atoms);
+        assert_eq!(basis.shells().len(), 7);
+        assert_eq!(basis.shells()[0].n_contractions(), 3);
+        assert_eq!(basis.shells()[1].n_contractions(), 2);
+        assert_eq!(basis.shell_dim(0), 3);
+        assert_eq!(basis.shell_dim(1), 6);
+        assert_eq!(basis.offset(3), 14);
+        assert_eq!(basis.dim(), 24);
+        assert_eq!(basis.cdim(), 25);
+    }
 
     #[test]
     fn gaussian_layout() {
@@ -583,5 +701,63 @@ mod tests {
             ],
             f
         );
+
+        // g and h: complete, ordered set of cartesian components
+        for l in 0..=5 {
+            let layout = super::gaussian_layout(&l);
+            assert_eq!(layout.len(), super::cdim(&l));
+            assert!(layout.iter().all(|ml| ml.iter().sum::<u8>() == l));
+            assert!(layout.windows(2).all(|w| w[0] > w[1]));
+        }
+        assert_eq!(super::gaussian_layout(&5)[0], [5, 0, 0]);
+        assert_eq!(super::gaussian_layout(&5)[20], [0, 0, 5]);
+    }
+
+    #[test]
+    fn l_label() {
+        let labels: String = (0..=5).map(|l| super::l_label(&l)).collect();
+        assert_eq!(labels, "spdfgh");
+        assert_eq!(super::l_label(&7), 'k');
+    }
+
+    #[test]
+    fn spherical_transformation() {
+        // transforming the overlap of normalized cartesian functions on the same center
+        // has to give orthonormal spherical harmonic functions
+        for l in 0..=5 {
+            let shell = BasisShell::new([0.0; 3], Shell::new(l, vec![1.3], vec![1.0]));
+            let cbf = shell.cbf();
+
+            let mut s = FMatrix::zero(cdim(&l), cdim(&l));
+            for i in 0..cdim(&l) {
+                for j in 0..cdim(&l) {
+                    s[(i, j)] = cbf[i].coefs()[0][0]
+                        * cbf[i].norm()[0]
+                        * cbf[j].coefs()[0][0]
+                        * cbf[j].norm()[0]
+                        * overlap(
+                            &1.3,
+                            &cbf[i].ml_i16(),
+                            cbf[i].origin(),
+                            &1.3,
+                            &cbf[j].ml_i16(),
+                            cbf[j].origin(),
+                        );
+                }
+            }
+
+            let t = Basis::cartesian_spherical_transformation(&l);
+            let s_sph = &t * &s * t.transposed();
+            for i in 0..dim(&l) {
+                for j in 0..dim(&l) {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert!(
+                        (s_sph[(i, j)] - expected).abs() < 1e-12,
+                        "l = {l}, ({i}, {j}): {}",
+                        s_sph[(i, j)]
+                    );
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,253 @@
+use crate::{gto_basis_sets::basis::Basis, linear_algebra::matrix::FMatrix};
+
+/// primitives with exp(-a r^2) below this bound are neglected
+const EXP_CUTOFF: f64 = 50.0;
+
+/// Values of the basis functions and optionally their derivatives on a set of points,
+/// each of dimension n_points x dim
+#[derive(Clone, Debug)]
+pub struct BasisValues {
+    pub values: FMatrix,
+    pub gradient: Option<[FMatrix; 3]>,
+    pub laplacian: Option<FMatrix>,
+}
+
+/// Factors of a single cartesian coordinate x^l exp(-a x^2) and its first and second
+/// derivative, without the exponential
+fn axis(x: f64, l: i32, a: f64) -> [f64; 3] {
+    let pow = |n: i32| if n < 0 { 0.0 } else { x.powi(n) };
+    let l_f = f64::from(l);
+
+    [
+        pow(l),
+        l_f * pow(l - 1) - 2.0 * a * pow(l + 1),
+        l_f * (l_f - 1.0) * pow(l - 2) - 2.0 * a * (2.0 * l_f + 1.0) * pow(l)
+            + 4.0 * a * a * pow(l + 2),
+    ]
+}
+
+impl Basis {
+    /// Values of all basis functions at the given points (in atomic units). Returns a
+    /// matrix of dimension n_points x dim.
+    pub fn evaluate(&self, points: &[[f64; 3]]) -> FMatrix {
+        self.evaluate_derivatives(points, 0).values
+    }
+
+    /// Values of all basis functions at the given points together with the gradient
+    /// (`order` >= 1) and the Laplacian (`order` >= 2).
+    pub fn evaluate_derivatives(&self, points: &[[f64; 3]], order: usize) -> BasisValues {
+        if order > 2 {
+            panic!("Derivatives of order {} are not supported", order);
+        }
+
+        // value, gradient (x, y, z), Laplacian
+        let n_quantities = match order {
+            0 => 1,
+            1 => 4,
+            _ => 5,
+        };
+        let mut quantities = vec![FMatrix::zero(points.len(), self.dim()); n_quantities];
+
+        for (sn, shell) in self.shells().iter().enumerate() {
+            let offset = self.offset(sn);
+            let n_contr = shell.n_contractions();
+            let n = self.shell_dim(sn) / n_contr;
+            let cdim = shell.cbf().len();
+            let min_exp = shell.exps().iter().cloned().fold(f64::INFINITY, f64::min);
+
+            // cartesian quantities of a single point, contraction c x (cartesian component)
+            let mut cartesian = vec![vec![0.0; n_contr * cdim]; n_quantities];
+
+            for (i, point) in points.iter().enumerate() {
+                let origin = shell.origin();
+                let r = [
+                    point[0] - origin[0],
+                    point[1] - origin[1],
+                    point[2] - origin[2],
+                ];
+                let r2 = r[0] * r[0] + r[1] * r[1] + r[2] * r[2];
+                if min_exp * r2 > EXP_CUTOFF {
+                    continue;
+                }
+
+                let gaussians: Vec<f64> = shell
+                    .exps()
+                    .iter()
+                    .map(|a| {
+                        if a * r2 > EXP_CUTOFF {
+                            0.0
+                        } else {
+                            (-a * r2).exp()
+                        }
+                    })
+                    .collect();
+
+                for values in cartesian.iter_mut() {
+                    values.iter_mut().for_each(|x| *x = 0.0);
+                }
+
+                for (k, cbf) in shell.cbf().iter().enumerate() {
+                    let ml = cbf.ml().map(i32::from);
+
+                    for (p, a) in shell.exps().iter().enumerate() {
+                        if gaussians[p] == 0.0 {
+                            continue;
+                        }
+
+                        let [fx, fy, fz] = [0, 1, 2].map(|x| axis(r[x], ml[x], *a));
+                        let mut q = [fx[0] * fy[0] * fz[0], 0.0, 0.0, 0.0, 0.0];
+                        if order >= 1 {
+                            q[1] = fx[1] * fy[0] * fz[0];
+                            q[2] = fx[0] * fy[1] * fz[0];
+                            q[3] = fx[0] * fy[0] * fz[1];
+                        }
+                        if order >= 2 {
+                            q[4] = fx[2] * fy[0] * fz[0]
+                                + fx[0] * fy[2] * fz[0]
+                                + fx[0] * fy[0] * fz[2];
+                        }
+
+                        for (c, coefs) in cbf.coefs().iter().enumerate() {
+                            let factor = coefs[p] * cbf.norm()[p] * gaussians[p];
+                            for (values, q) in cartesian.iter_mut().zip(q) {
+                                values[c * cdim + k] += factor * q;
+                            }
+                        }
+                    }
+                }
+
+                for (matrix, cartesian) in quantities.iter_mut().zip(&cartesian) {
+                    for c in 0..n_contr {
+                        let cartesian = &cartesian[c * cdim..(c + 1) * cdim];
+                        if self.cartesian() {
+                            for k in 0..cdim {
+                                matrix[(i, offset + c * n + k)] = cartesian[k];
+                            }
+                        } else {
+                            let t = self.trafo_matrix(shell.l());
+                            for m in 0..n {
+                                matrix[(i, offset + c * n + m)] =
+                                    (0..cdim).map(|k| t[(m, k)] * cartesian[k]).sum();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let laplacian = if order >= 2 { quantities.pop() } else { None };
+        let gradient = if order >= 1 {
+            let z = quantities.pop().unwrap();
+            let y = quantities.pop().unwrap();
+            let x = quantities.pop().unwrap();
+            Some([x, y, z])
+        } else {
+            None
+        };
+
+        BasisValues {
+            values: quantities.pop().unwrap(),
+            gradient,
+            laplacian,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::atom::Atom;
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::linear_algebra::constants::PI;
+    use crate::misc::elements::Element::H;
+    use crate::tests::water_xz;
+
+    #[test]
+    fn normalization() {
+        // radial integration along the x axis of the s and p functions of a single atom
+        let basis = load_basis_set(&BasisSet::cc_pvdz, &[Atom::new(H, [0.0; 3])]);
+        let h = 1e-3;
+        let points: Vec<[f64; 3]> = (0..12000).map(|i| [i as f64 * h, 0.0, 0.0]).collect();
+        let values = basis.evaluate(&points);
+
+        // 2 s functions, then p (m = -1, 0, +1 -> y, z, x)
+        for (ao, prefactor) in [(0, 4.0 * PI), (1, 4.0 * PI), (4, 4.0 * PI / 3.0)] {
+            let norm: f64 = (0..points.len())
+                .map(|i| h * points[i][0].powi(2) * values[(i, ao)].powi(2))
+                .sum::<f64>()
+                * prefactor;
+            assert!((norm - 1.0).abs() < 1e-6, "AO {}: {}", ao, norm);
+        }
+    }
+
+    #[test]
+    fn derivatives() {
+        // compare with central finite differences, including f functions
+        let atoms = water_xz()[..2].to_vec();
+        let basis = load_basis_set(&BasisSet::cc_pvtz, &atoms);
+        let point = [0.3, -0.2, 0.1];
+        let h = 1e-4;
+
+        let values = basis.evaluate_derivatives(&[point], 2);
+        let gradient = values.gradient.unwrap();
+        let laplacian = values.laplacian.unwrap();
+        assert_eq!(values.values, basis.evaluate(&[point]));
+
+        let mut displaced = Vec::new();
+        for x in 0..3 {
+            for sign in [1.0, -1.0] {
+                let mut p = point;
+                p[x] += sign * h;
+                displaced.push(p);
+            }
+        }
+        let v = basis.evaluate(&displaced);
+
+        for mu in 0..basis.dim() {
+            let mut fd_laplacian = 0.0;
+            for x in 0..3 {
+                let (plus, minus) = (v[(2 * x, mu)], v[(2 * x + 1, mu)]);
+                let fd_gradient = (plus - minus) / (2.0 * h);
+                assert!((gradient[x][(0, mu)] - fd_gradient).abs() < 1e-5);
+                fd_laplacian += (plus - 2.0 * values.values[(0, mu)] + minus) / (h * h);
+            }
+            let reference = laplacian[(0, mu)];
+            assert!(
+                (reference - fd_laplacian).abs() < 1e-4 * reference.abs().max(1.0),
+                "AO {}: {} != {}",
+                mu,
+                reference,
+                fd_laplacian
+            );
+        }
+    }
+
+    #[test]
+    fn spherical() {
+        // spherical values have to be the transformed cartesian ones
+        let atoms = water_xz()[..2].to_vec();
+        let points = [[0.3, -0.2, 0.1], [1.0, 0.5, -0.7], [-0.4, 0.0, 1.2]];
+
+        let spherical = load_basis_set(&BasisSet::cc_pvtz, &atoms);
+        let mut cartesian = spherical.clone();
+        cartesian.set_cartesian(true);
+
+        let v_sph = spherical.evaluate(&points);
+        let v_cart = cartesian.evaluate(&points);
+
+        let mut sph_offset = 0;
+        let mut cart_offset = 0;
+        for shell in spherical.shells() {
+            let t = spherical.shell_trafo(shell);
+            for i in 0..points.len() {
+                for m in 0..t.rows {
+                    let value: f64 = (0..t.cols)
+                        .map(|k| t[(m, k)] * v_cart[(i, cart_offset + k)])
+                        .sum();
+                    assert!((v_sph[(i, sph_offset + m)] - value).abs() < 1e-12);
+                }
+            }
+            sph_offset += t.rows;
+            cart_offset += t.cols;
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    geometry::atom::Atom,
+    gto_basis_sets::basis::{l_label, Basis, BasisShell},
+    misc::elements::Element,
+};
+
+use std::{collections::BTreeMap, io};
+
+/// Writes the basis set definition in the input format of another program
+pub trait BasisWriter {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()>;
+}
+
+/// ORCA `%basis` block with one `NewGTO` entry per element
+pub struct Orca;
+
+/// Gaussian general basis (`gen`) input
+pub struct Gaussian;
+
+/// NWChem `basis` block
+pub struct NWChem;
+
+/// Psi4 basis set file (.gbs)
+pub struct Psi4;
+
+/// `[GTO]` section of a Molden file, one entry per atom
+pub struct Molden;
+
+// --------------------------------
+// helpers
+// --------------------------------
+
+/// unique elements together with the index of the first atom of that element
+fn unique_elements(atoms: &[Atom]) -> BTreeMap<Element, usize> {
+    let mut elements = BTreeMap::new();
+    for (i, atom) in atoms.iter().enumerate() {
+        elements.entry(atom.el.clone()).or_insert(i);
+    }
+    elements
+}
+
+fn atom_shells(basis: &Basis, atom: usize) -> impl Iterator<Item = &BasisShell> {
+    basis.shells().iter().filter(move |s| s.atom() == atom)
+}
+
+/// Split a (generally contracted) shell into segmented contractions, dropping
+/// primitives with vanishing coefficients
+fn segmented(shell: &BasisShell) -> Vec<Vec<(f64, f64)>> {
+    shell
+        .coefs()
+        .iter()
+        .map(|coefs| {
+            shell
+                .exps()
+                .iter()
+                .zip(coefs)
+                .filter(|(_, c)| **c != 0.0)
+                .map(|(e, c)| (*e, *c))
+                .collect()
+        })
+        .collect()
+}
+
+fn symbol(el: &Element) -> String {
+    el.to_string().trim().to_string()
+}
+
+fn write_primitives(w: &mut dyn io::Write, primitives: &[(f64, f64)]) -> io::Result<()> {
+    for (e, c) in primitives {
+        writeln!(w, "  {:20.10} {:18.10}", e, c)?;
+    }
+    Ok(())
+}
+
+// --------------------------------
+// writers
+// --------------------------------
+
+impl BasisWriter for Orca {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "%basis")?;
+        for (el, atom) in unique_elements(atoms) {
+            writeln!(w, "  # Basis set for element : {}", symbol(&el))?;
+            writeln!(w, "  NewGTO {}", symbol(&el))?;
+            for shell in atom_shells(basis, atom) {
+                for primitives in segmented(shell) {
+                    let label = l_label(shell.l()).to_ascii_uppercase();
+                    writeln!(w, "  {} {}", label, primitives.len())?;
+                    for (i, (e, c)) in primitives.iter().enumerate() {
+                        writeln!(w, "  {:3} {:20.10} {:18.10}", i + 1, e, c)?;
+                    }
+                }
+            }
+            writeln!(w, "  end")?;
+        }
+        writeln!(w, "end")
+    }
+}
+
+impl BasisWriter for Gaussian {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()> {
+        for (el, atom) in unique_elements(atoms) {
+            writeln!(w, "{} 0", symbol(&el))?;
+            for shell in atom_shells(basis, atom) {
+                for primitives in segmented(shell) {
+                    let label = l_label(shell.l()).to_ascii_uppercase();
+                    writeln!(w, "{} {} 1.00", label, primitives.len())?;
+                    write_primitives(w, &primitives)?;
+                }
+            }
+            writeln!(w, "****")?;
+        }
+        Ok(())
+    }
+}
+
+impl BasisWriter for NWChem {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()> {
+        let angular = if basis.cartesian() {
+            "CARTESIAN"
+        } else {
+            "SPHERICAL"
+        };
+
+        writeln!(w, "BASIS \"ao basis\" {} PRINT", angular)?;
+        for (el, atom) in unique_elements(atoms) {
+            for shell in atom_shells(basis, atom) {
+                for primitives in segmented(shell) {
+                    let label = l_label(shell.l()).to_ascii_uppercase();
+                    writeln!(w, "{} {}", symbol(&el), label)?;
+                    write_primitives(w, &primitives)?;
+                }
+            }
+        }
+        writeln!(w, "END")
+    }
+}
+
+impl BasisWriter for Psi4 {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()> {
+        if basis.cartesian() {
+            writeln!(w, "cartesian")?;
+        } else {
+            writeln!(w, "spherical")?;
+        }
+        writeln!(w, "****")?;
+
+        // same element blocks as Gaussian
+        Gaussian.write(basis, atoms, w)
+    }
+}
+
+impl BasisWriter for Molden {
+    fn write(&self, basis: &Basis, atoms: &[Atom], w: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(w, "[GTO]")?;
+        for atom in 0..atoms.len() {
+            writeln!(w, "{:4} 0", atom + 1)?;
+            for shell in atom_shells(basis, atom) {
+                for primitives in segmented(shell) {
+                    writeln!(w, " {} {:3} 1.00", l_label(shell.l()), primitives.len())?;
+                    write_primitives(w, &primitives)?;
+                }
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::tests::water_xz;
+
+    fn export(writer: &dyn BasisWriter, basis_set: BasisSet) -> String {
+        let atoms = water_xz();
+        let basis = load_basis_set(&basis_set, &atoms);
+        let mut buffer = Vec::new();
+        writer.write(&basis, &atoms, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn gaussian() {
+        let out = export(&Gaussian, BasisSet::sto_3g);
+        let lines: Vec<&str> = out.lines().collect();
+
+        // elements are sorted, H before O
+        assert_eq!(lines[0], "H 0");
+        assert_eq!(lines[1], "S 3 1.00");
+        assert_eq!(lines[5], "****");
+        assert_eq!(lines[6], "O 0");
+        assert_eq!(out.matches("****").count(), 2);
+        assert_eq!(out.matches(" 1.00\n").count(), 4);
+    }
+
+    #[test]
+    fn general_contraction() {
+        // cc-pVDZ O: the generally contracted s shell gives three segmented ones,
+        // the uncontracted function without the zero coefficients
+        let out = export(&Gaussian, BasisSet::cc_pvdz);
+        let oxygen = out.split("O 0\n").nth(1).unwrap();
+        let s: Vec<&str> = oxygen.lines().filter(|l| l.starts_with("S ")).collect();
+        assert_eq!(s, vec!["S 9 1.00", "S 9 1.00", "S 1 1.00"]);
+    }
+
+    #[test]
+    fn orca() {
+        let out = export(&Orca, BasisSet::sto_3g);
+        assert!(out.starts_with("%basis\n"));
+        assert!(out.ends_with("  end\nend\n"));
+        assert_eq!(out.matches("NewGTO").count(), 2);
+        assert!(out.contains("  NewGTO O\n  S 3\n"));
+    }
+
+    #[test]
+    fn nwchem() {
+        let out = export(&NWChem, BasisSet::def2_svp);
+        assert!(out.starts_with("BASIS \"ao basis\" SPHERICAL PRINT\n"));
+        assert!(out.contains("O D\n"));
+        assert!(out.ends_with("END\n"));
+    }
+
+    #[test]
+    fn psi4() {
+        let out = export(&Psi4, BasisSet::sto_3g);
+        assert!(out.starts_with("spherical\n****\nH 0\n"));
+    }
+
+    #[test]
+    fn molden() {
+        let out = export(&Molden, BasisSet::sto_3g);
+        let lines: Vec<&str> = out.lines().collect();
+
+        // one block per atom, not per element
+        assert_eq!(lines[0], "[GTO]");
+        assert_eq!(lines[1], "   1 0");
+        assert_eq!(lines[2], " s   3 1.00");
+        assert_eq!(out.matches(" 0\n").count(), 3);
+        assert_eq!(out.matches(" p   3 1.00").count(), 1);
+    }
+}
@@ -1,10 +1,29 @@
+mod aug_cc_pvdz;
+mod aug_cc_pvtz;
+pub mod auxiliary;
 pub mod basis;
+mod cc_pvdz;
+mod cc_pvtz;
 mod def2_svp;
 mod def2_tzvp;
+pub mod evaluate;
+pub mod export;
+mod pople_3_21g;
+mod pople_6_311g_star_star;
+mod pople_6_31g;
+mod pople_6_31g_star;
 mod sto_3g;
 
+use aug_cc_pvdz::load_aug_cc_pvdz;
+use aug_cc_pvtz::load_aug_cc_pvtz;
+use cc_pvdz::load_cc_pvdz;
+use cc_pvtz::load_cc_pvtz;
 use def2_svp::load_def2_svp;
 use def2_tzvp::load_def2_tzvp;
+use pople_3_21g::load_3_21g;
+use pople_6_311g_star_star::load_6_311g_star_star;
+use pople_6_31g::load_6_31g;
+use pople_6_31g_star::load_6_31g_star;
 use sto_3g::load_sto_3g;
 
 use crate::geometry::atom::Atom;
@@ -16,6 +35,14 @@ use std::str::FromStr;
 #[derive(Debug, PartialEq)]
 pub enum BasisSet {
     sto_3g,
+    _3_21g,
+    _6_31g,
+    _6_31g_star,
+    _6_311g_star_star,
+    cc_pvdz,
+    cc_pvtz,
+    aug_cc_pvdz,
+    aug_cc_pvtz,
     def2_svp,
     def2_tzvp,
 }
@@ -31,6 +58,14 @@ impl Display for BasisSet {
         let s;
         match self {
             BasisSet::sto_3g => s = "sto-3g",
+            BasisSet::_3_21g => s = "3-21g",
+            BasisSet::_6_31g => s = "6-31g",
+            BasisSet::_6_31g_star => s = "6-31g*",
+            BasisSet::_6_311g_star_star => s = "6-311g**",
+            BasisSet::cc_pvdz => s = "cc-pvdz",
+            BasisSet::cc_pvtz => s = "cc-pvtz",
+            BasisSet::aug_cc_pvdz => s = "aug-cc-pvdz",
+            BasisSet::aug_cc_pvtz => s = "aug-cc-pvtz",
             BasisSet::def2_tzvp => s = "def2-tzvp",
             BasisSet::def2_svp => s = "def2-svp",
         }
@@ -50,6 +85,14 @@ impl FromStr for BasisSet {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let basis = match s.to_lowercase().as_str() {
             "sto-3g" => BasisSet::sto_3g,
+            "3-21g" => BasisSet::_3_21g,
+            "6-31g" => BasisSet::_6_31g,
+            "6-31g*" | "6-31g(d)" => BasisSet::_6_31g_star,
+            "6-311g**" | "6-311g(d,p)" => BasisSet::_6_311g_star_star,
+            "cc-pvdz" => BasisSet::cc_pvdz,
+            "cc-pvtz" => BasisSet::cc_pvtz,
+            "aug-cc-pvdz" => BasisSet::aug_cc_pvdz,
+            "aug-cc-pvtz" => BasisSet::aug_cc_pvtz,
             "def2-svp" => BasisSet::def2_svp,
             "def2-tzvp" => BasisSet::def2_tzvp,
             _ => panic!("Unknown basis set {}", s),
@@ -64,6 +107,14 @@ impl FromStr for BasisSet {
 pub fn load_basis_set(basis_set: &BasisSet, atoms: &[Atom]) -> Basis {
     match basis_set {
         BasisSet::sto_3g => load_sto_3g(atoms),
+        BasisSet::_3_21g => load_3_21g(atoms),
+        BasisSet::_6_31g => load_6_31g(atoms),
+        BasisSet::_6_31g_star => load_6_31g_star(atoms),
+        BasisSet::_6_311g_star_star => load_6_311g_star_star(atoms),
+        BasisSet::cc_pvdz => load_cc_pvdz(atoms),
+        BasisSet::cc_pvtz => load_cc_pvtz(atoms),
+        BasisSet::aug_cc_pvdz => load_aug_cc_pvdz(atoms),
+        BasisSet::aug_cc_pvtz => load_aug_cc_pvtz(atoms),
         BasisSet::def2_tzvp => load_def2_tzvp(atoms),
         BasisSet::def2_svp => load_def2_svp(atoms),
     }
@@ -71,9 +122,29 @@ pub fn load_basis_set(basis_set: &BasisSet, atoms: &[Atom]) -> Basis {
 
 #[cfg(test)]
 mod tests {
-    use crate::gto_basis_sets::BasisSet;
+    use crate::{
+        gto_basis_sets::{load_basis_set, BasisSet},
+        tests::water,
+    };
     use std::str::FromStr;
 
+    #[test]
+    fn pople_6_31g_star() {
+        // O: s, 2 x sp, d; H: 2 x s
+        let mut basis = load_basis_set(&BasisSet::_6_31g_star, &water());
+        assert_eq!(basis.shells().len(), 6 + 2 * 2);
+        assert_eq!(basis.dim(), 1 + 2 * 4 + 5 + 2 * 2);
+        basis.set_cartesian(true);
+        assert_eq!(basis.dim(), 1 + 2 * 4 + 6 + 2 * 2);
+
+        // the s and p halves of every sp shell share their exponents
+        let oxygen = &basis.shells()[..6];
+        for (s, p) in [(&oxygen[1], &oxygen[2]), (&oxygen[3], &oxygen[4])] {
+            assert_eq!((*s.l(), *p.l()), (0, 1));
+            assert_eq!(s.exps(), p.exps());
+        }
+    }
+
     #[test]
     fn from() {
         assert_eq!(BasisSet::from("sto-3g".to_string()), BasisSet::sto_3g);
@@ -86,6 +157,29 @@ mod tests {
         assert_eq!(BasisSet::from_str("sto-3g"), Ok(BasisSet::sto_3g));
         assert_eq!(BasisSet::from_str("sTO-3g"), Ok(BasisSet::sto_3g));
         assert_eq!(BasisSet::from_str("STO-3G"), Ok(BasisSet::sto_3g));
+        assert_eq!(BasisSet::from_str("3-21G"), Ok(BasisSet::_3_21g));
+        assert_eq!(BasisSet::from_str("6-31G"), Ok(BasisSet::_6_31g));
+        assert_eq!(BasisSet::from_str("6-31G*"), Ok(BasisSet::_6_31g_star));
+        assert_eq!(BasisSet::from_str("6-31G(d)"), Ok(BasisSet::_6_31g_star));
+        assert_eq!(
+            BasisSet::from_str("6-311G**"),
+            Ok(BasisSet::_6_311g_star_star)
+        );
+        assert_eq!(
+            BasisSet::from_str("6-311G(d,p)"),
+            Ok(BasisSet::_6_311g_star_star)
+        );
+        assert_eq!(BasisSet::from_str("cc-pVDZ"), Ok(BasisSet::cc_pvdz));
+        assert_eq!(BasisSet::from_str("cc-pVTZ"), Ok(BasisSet::cc_pvtz));
+        assert_eq!(BasisSet::from_str("aug-cc-pVDZ"), Ok(BasisSet::aug_cc_pvdz));
+        assert_eq!(BasisSet::from_str("aug-cc-pVTZ"), Ok(BasisSet::aug_cc_pvtz));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(BasisSet::_6_31g_star.to_string(), "6-31g*");
+        assert_eq!(BasisSet::_6_311g_star_star.to_string(), "6-311g**");
+        assert_eq!(BasisSet::aug_cc_pvtz.to_string(), "aug-cc-pvtz");
     }
 
     #[test]
@@ -0,0 +1,81 @@
+use crate::geometry::atom::Atom;
+use crate::gto_basis_sets::basis::{Basis, Shell};
+
+pub fn load_3_21g(atoms: &[Atom]) -> Basis {
+    println!("Loading 3-21G basis set");
+    const MAX_ATOMIC_NUMBER: usize = 8;
+
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); MAX_ATOMIC_NUMBER + 1];
+
+    // ----------------------------
+    // Element #1, Hydrogen
+    // ----------------------------
+    shells[1] = Vec::with_capacity(2);
+    // s
+    shells[1].push(Shell::new(
+        0,
+        vec![5.4471780, 0.8245470],
+        vec![0.1562850, 0.9046910],
+    ));
+    // s
+    shells[1].push(Shell::new(0, vec![0.1831920], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #6, Carbon
+    // ----------------------------
+    shells[6] = Vec::with_capacity(5);
+    // s
+    shells[6].push(Shell::new(
+        0,
+        vec![172.2560000, 25.9109000, 5.5333500],
+        vec![0.0617669, 0.3587940, 0.7007130],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(
+        vec![3.6649800, 0.7705450],
+        vec![-0.3958970, 1.2158400],
+        vec![0.2364600, 0.8606190],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(vec![0.1958570], vec![1.0000000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #7, Nitrogen
+    // ----------------------------
+    shells[7] = Vec::with_capacity(5);
+    // s
+    shells[7].push(Shell::new(
+        0,
+        vec![242.7660000, 36.4851000, 7.8144900],
+        vec![0.0598657, 0.3529550, 0.7065130],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(
+        vec![5.4252200, 1.1491500],
+        vec![-0.4133010, 1.2244200],
+        vec![0.2379720, 0.8589530],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(vec![0.2832050], vec![1.0000000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #8, Oxygen
+    // ----------------------------
+    shells[8] = Vec::with_capacity(5);
+    // s
+    shells[8].push(Shell::new(
+        0,
+        vec![322.0370000, 48.4308000, 10.4206000],
+        vec![0.0592394, 0.3515000, 0.7076580],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(
+        vec![7.4029400, 1.5762000],
+        vec![-0.4044530, 1.2215600],
+        vec![0.2445860, 0.8539550],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(vec![0.3736840], vec![1.0000000], vec![1.0000000]));
+
+    Basis::new(atoms, shells)
+}
@@ -0,0 +1,124 @@
+use crate::geometry::atom::Atom;
+use crate::gto_basis_sets::basis::{Basis, Shell};
+
+pub fn load_6_311g_star_star(atoms: &[Atom]) -> Basis {
+    println!("Loading 6-311G** basis set");
+    const MAX_ATOMIC_NUMBER: usize = 8;
+
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); MAX_ATOMIC_NUMBER + 1];
+
+    // ----------------------------
+    // Element #1, Hydrogen
+    // ----------------------------
+    shells[1] = Vec::with_capacity(4);
+    // s
+    shells[1].push(Shell::new(
+        0,
+        vec![33.8650000, 5.0947900, 1.1587900],
+        vec![0.0254938, 0.1903730, 0.8521610],
+    ));
+    // s
+    shells[1].push(Shell::new(0, vec![0.3258400], vec![1.0000000]));
+    // s
+    shells[1].push(Shell::new(0, vec![0.1027410], vec![1.0000000]));
+    // p
+    shells[1].push(Shell::new(1, vec![0.7500000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #6, Carbon
+    // ----------------------------
+    shells[6] = Vec::with_capacity(8);
+    // s
+    shells[6].push(Shell::new(
+        0,
+        vec![
+            4563.2400000,
+            682.0240000,
+            154.9730000,
+            44.4553000,
+            13.0290000,
+            1.8277300,
+        ],
+        vec![
+            0.0019667, 0.0152306, 0.0761269, 0.2608010, 0.6164620, 0.2210060,
+        ],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(
+        vec![20.9642000, 4.8033100, 1.4593300],
+        vec![0.1146600, 0.9199990, -0.0030307],
+        vec![0.0402487, 0.2375940, 0.8158540],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(vec![0.4834560], vec![1.0000000], vec![1.0000000]));
+    // sp
+    shells[6].extend(Shell::sp(vec![0.1455850], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.6260000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #7, Nitrogen
+    // ----------------------------
+    shells[7] = Vec::with_capacity(8);
+    // s
+    shells[7].push(Shell::new(
+        0,
+        vec![
+            6293.4800000,
+            949.0440000,
+            218.7760000,
+            63.6916000,
+            18.8282000,
+            2.7202300,
+        ],
+        vec![
+            0.0019698, 0.0149613, 0.0735006, 0.2489370, 0.6024600, 0.2562020,
+        ],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(
+        vec![30.6331000, 7.0261400, 2.1120500],
+        vec![0.1119060, 0.9216660, -0.0025692],
+        vec![0.0383119, 0.2374030, 0.8175920],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(vec![0.6840090], vec![1.0000000], vec![1.0000000]));
+    // sp
+    shells[7].extend(Shell::sp(vec![0.2008780], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.9130000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #8, Oxygen
+    // ----------------------------
+    shells[8] = Vec::with_capacity(8);
+    // s
+    shells[8].push(Shell::new(
+        0,
+        vec![
+            8588.5000000,
+            1297.2300000,
+            299.2960000,
+            87.3771000,
+            25.6789000,
+            3.7400400,
+        ],
+        vec![
+            0.0018952, 0.0143859, 0.0707320, 0.2400010, 0.5947970, 0.2808020,
+        ],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(
+        vec![42.1175000, 9.6283700, 2.8533200],
+        vec![0.1138890, 0.9208110, -0.0032745],
+        vec![0.0365114, 0.2371530, 0.8197020],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(vec![0.9056610], vec![1.0000000], vec![1.0000000]));
+    // sp
+    shells[8].extend(Shell::sp(vec![0.2556110], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![1.2920000], vec![1.0000000]));
+
+    Basis::new(atoms, shells)
+}
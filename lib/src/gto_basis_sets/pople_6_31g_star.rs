@@ -0,0 +1,114 @@
+use crate::geometry::atom::Atom;
+use crate::gto_basis_sets::basis::{Basis, Shell};
+
+pub fn load_6_31g_star(atoms: &[Atom]) -> Basis {
+    println!("Loading 6-31G* basis set");
+    const MAX_ATOMIC_NUMBER: usize = 8;
+
+    let mut shells: Vec<Vec<Shell>> = vec![Default::default(); MAX_ATOMIC_NUMBER + 1];
+
+    // ----------------------------
+    // Element #1, Hydrogen
+    // ----------------------------
+    shells[1] = Vec::with_capacity(2);
+    // s
+    shells[1].push(Shell::new(
+        0,
+        vec![18.7311370, 2.8253937, 0.6401217],
+        vec![0.0334946, 0.2347270, 0.8137573],
+    ));
+    // s
+    shells[1].push(Shell::new(0, vec![0.1612778], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #6, Carbon
+    // ----------------------------
+    shells[6] = Vec::with_capacity(6);
+    // s
+    shells[6].push(Shell::new(
+        0,
+        vec![
+            3047.5249000,
+            457.3695100,
+            103.9486900,
+            29.2101550,
+            9.2866630,
+            3.1639270,
+        ],
+        vec![
+            0.0018347, 0.0140373, 0.0688426, 0.2321844, 0.4679413, 0.3623120,
+        ],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(
+        vec![7.8682724, 1.8812885, 0.5442493],
+        vec![-0.1193324, -0.1608542, 1.1434564],
+        vec![0.0689991, 0.3164240, 0.7443083],
+    ));
+    // sp
+    shells[6].extend(Shell::sp(vec![0.1687144], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[6].push(Shell::new(2, vec![0.8000000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #7, Nitrogen
+    // ----------------------------
+    shells[7] = Vec::with_capacity(6);
+    // s
+    shells[7].push(Shell::new(
+        0,
+        vec![
+            4173.5110000,
+            627.4579000,
+            142.9021000,
+            40.2343300,
+            12.8202100,
+            4.3904370,
+        ],
+        vec![
+            0.0018348, 0.0139950, 0.0685870, 0.2322410, 0.4690700, 0.3604550,
+        ],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(
+        vec![11.6263580, 2.7162800, 0.7722180],
+        vec![-0.1149610, -0.1691180, 1.1458520],
+        vec![0.0675800, 0.3239070, 0.7408950],
+    ));
+    // sp
+    shells[7].extend(Shell::sp(vec![0.2120313], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[7].push(Shell::new(2, vec![0.8000000], vec![1.0000000]));
+
+    // ----------------------------
+    // Element #8, Oxygen
+    // ----------------------------
+    shells[8] = Vec::with_capacity(6);
+    // s
+    shells[8].push(Shell::new(
+        0,
+        vec![
+            5484.6717000,
+            825.2349500,
+            188.0469600,
+            52.9645000,
+            16.8975700,
+            5.7996353,
+        ],
+        vec![
+            0.0018311, 0.0139501, 0.0684451, 0.2327143, 0.4701930, 0.3585209,
+        ],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(
+        vec![15.5396160, 3.5999336, 1.0137618],
+        vec![-0.1107775, -0.1480263, 1.1307670],
+        vec![0.0708743, 0.3397528, 0.7271586],
+    ));
+    // sp
+    shells[8].extend(Shell::sp(vec![0.2700058], vec![1.0000000], vec![1.0000000]));
+    // d
+    shells[8].push(Shell::new(2, vec![0.8000000], vec![1.0000000]));
+
+    Basis::new(atoms, shells)
+}
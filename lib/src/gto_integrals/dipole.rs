@@ -0,0 +1,59 @@
+use crate::gto_integrals::e::e;
+use crate::linear_algebra::constants::PI;
+
+/// Component `k` of the dipole integral <a|r_k|b> about the origin. In the Hermite
+/// expansion the component k contributes E^{ij}_1 + P_k E^{ij}_0 with the Gaussian
+/// product center P, the other two components their overlap E^{ij}_0.
+pub fn dipole(
+    a: &f64,
+    ml_a: &[i16; 3],
+    a_origin: &[f64; 3],
+    b: &f64,
+    ml_b: &[i16; 3],
+    b_origin: &[f64; 3],
+    k: usize,
+) -> f64 {
+    let p = a + b;
+    let mut value = (PI / p).powf(1.5);
+    for x in 0..3 {
+        let center = a_origin[x] - b_origin[x];
+        let e0 = e(ml_a[x], ml_b[x], 0, center, a, b);
+        value *= match x == k {
+            true => {
+                let p_x = (a * a_origin[x] + b * b_origin[x]) / p;
+                e(ml_a[x], ml_b[x], 1, center, a, b) + p_x * e0
+            }
+            false => e0,
+        };
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gto_integrals::overlap::overlap;
+
+    const A: [f64; 3] = [0.0, 0.1, -0.3];
+    const B: [f64; 3] = [0.4, -0.2, 0.5];
+
+    #[test]
+    fn dipole() {
+        // x = (x - B_x) + B_x raises the angular momentum of b:
+        // <a|x|b> = <a|b + 1_x> + B_x <a|b>
+        for (ml_a, ml_b) in [
+            ([0, 0, 0], [0, 0, 0]),
+            ([1, 0, 0], [0, 1, 0]),
+            ([2, 1, 0], [1, 0, 2]),
+            ([0, 0, 3], [2, 2, 0]),
+        ] {
+            for k in 0..3 {
+                let mut raised = ml_b;
+                raised[k] += 1;
+                let reference = overlap(&1.3, &ml_a, &A, &0.8, &raised, &B)
+                    + B[k] * overlap(&1.3, &ml_a, &A, &0.8, &ml_b, &B);
+                let value = super::dipole(&1.3, &ml_a, &A, &0.8, &ml_b, &B, k);
+                assert!((value - reference).abs() < 1e-12, "{ml_a:?} {ml_b:?} {k}");
+            }
+        }
+    }
+}
@@ -69,3 +69,34 @@ pub fn eri(
 
     val * 2.0 * PI.powf(2.5) / (p * q * (p + q).sqrt())
 }
+
+#[cfg(test)]
+mod tests {
+    // reference values from an independent Obara-Saika implementation
+    const A: [f64; 3] = [0.0, 0.1, -0.3];
+    const B: [f64; 3] = [0.4, -0.2, 0.5];
+    const C: [f64; 3] = [-0.3, 0.6, 0.2];
+    const D: [f64; 3] = [0.2, 0.3, -0.4];
+
+    #[test]
+    fn eri() {
+        let reference = [
+            ([0, 0, 0], [0, 0, 0], [0, 0, 0], [0, 0, 0], 2.22959366626586),
+            ([1, 0, 0], [0, 1, 0], [0, 0, 1], [1, 0, 0], -0.0023180398912204677),
+            ([2, 1, 0], [0, 0, 1], [0, 2, 0], [1, 0, 0], -0.0020409089055138893),
+            ([4, 0, 1], [0, 1, 0], [1, 1, 0], [0, 0, 0], -0.00035457275809066874),
+            ([2, 2, 1], [0, 0, 0], [0, 0, 0], [0, 1, 0], 0.004359825146066785),
+            ([3, 1, 0], [0, 0, 0], [1, 2, 1], [0, 0, 0], 0.0006431873287892645),
+        ];
+
+        for (ml_a, ml_b, ml_c, ml_d, value) in reference {
+            let g = super::eri(
+                &1.3, &ml_a, &A, &0.8, &ml_b, &B, &1.1, &ml_c, &C, &0.6, &ml_d, &D,
+            );
+            assert!(
+                (g - value).abs() < 1e-12,
+                "({ml_a:?} {ml_b:?}|{ml_c:?} {ml_d:?}): {g} != {value}"
+            );
+        }
+    }
+}
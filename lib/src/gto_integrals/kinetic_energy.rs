@@ -31,11 +31,37 @@ pub fn kinetic_energy(
     // m2*(m2-1)*overlap( a, lmn1, A, b, [ l2, m2-2, n2 ], B ) +
     // n2*(n2-1)*overlap( a, lmn1, A, b, [ l2, m2, n2-2 ], B ));
     let term2 = -0.5
-        * (f64::from(l_b)
-            * f64::from(l_b - 1)
+        * (f64::from(l_b * (l_b - 1))
             * overlap(a, ml_a, a_origin, b, &[l_b - 2, m_b, n_b], b_origin)
-            + overlap(a, ml_a, a_origin, b, &[l_b, m_b - 2, n_b], b_origin)
-            + overlap(a, ml_a, a_origin, b, &[l_b, m_b, n_b - 2], b_origin));
+            + f64::from(m_b * (m_b - 1))
+                * overlap(a, ml_a, a_origin, b, &[l_b, m_b - 2, n_b], b_origin)
+            + f64::from(n_b * (n_b - 1))
+                * overlap(a, ml_a, a_origin, b, &[l_b, m_b, n_b - 2], b_origin));
 
     term0 + term1 + term2
 }
+
+#[cfg(test)]
+mod tests {
+    // reference values from an independent Obara-Saika implementation
+    const A: [f64; 3] = [0.0, 0.1, -0.3];
+    const B: [f64; 3] = [0.4, -0.2, 0.5];
+
+    #[test]
+    fn kinetic_energy() {
+        let reference = [
+            ([0, 0, 0], [0, 0, 0], 1.2354154712880658),
+            ([1, 0, 0], [1, 0, 0], 0.4372111404759227),
+            ([0, 1, 0], [0, 2, 0], 0.283100343705137),
+            ([1, 1, 0], [0, 0, 2], -0.032139255692356906),
+            ([3, 1, 1], [1, 0, 2], 0.01093429683796314),
+            ([5, 0, 0], [0, 2, 3], -0.0026361445287414534),
+            ([2, 2, 1], [0, 3, 2], -0.008987891891684637),
+        ];
+
+        for (ml_a, ml_b, value) in reference {
+            let t = super::kinetic_energy(&1.3, &ml_a, &A, &0.8, &ml_b, &B);
+            assert!((t - value).abs() < 1e-12, "{ml_a:?} {ml_b:?}: {t} != {value}");
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer};
+
+use std::{
+    fs::{remove_file, File},
+    io::{BufReader, Read, Seek, SeekFrom, Write},
+    ops::{Deref, DerefMut, Index, IndexMut, Range},
+};
+
+/// Storage of the half-transformed integrals (μν|rs) between the two halves of the
+/// transformation
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TransformBuffer {
+    #[default]
+    Memory,
+    /// binary scratch file, removed after the transformation
+    Disk(String),
+}
+
+/// MO integrals (pq|rs) in chemists' notation over four orbital spaces, stored as
+/// ((p * n_q + q) * n_r + r) * n_s + s
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MOIntegrals {
+    dims: [usize; 4],
+    data: Vec<f64>,
+}
+
+/// Getters
+impl MOIntegrals {
+    pub fn dims(&self) -> [usize; 4] {
+        self.dims
+    }
+}
+
+impl MOIntegrals {
+    pub fn zero(dims: [usize; 4]) -> Self {
+        Self {
+            dims,
+            data: vec![0.0; dims.iter().product()],
+        }
+    }
+
+    pub fn index_of(&self, p: usize, q: usize, r: usize, s: usize) -> usize {
+        let [_, n_q, n_r, n_s] = self.dims;
+        ((p * n_q + q) * n_r + r) * n_s + s
+    }
+}
+
+impl Deref for MOIntegrals {
+    type Target = [f64];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for MOIntegrals {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl Index<(usize, usize, usize, usize)> for MOIntegrals {
+    type Output = f64;
+
+    fn index(&self, (p, q, r, s): (usize, usize, usize, usize)) -> &Self::Output {
+        &self.data[self.index_of(p, q, r, s)]
+    }
+}
+
+impl IndexMut<(usize, usize, usize, usize)> for MOIntegrals {
+    fn index_mut(&mut self, (p, q, r, s): (usize, usize, usize, usize)) -> &mut Self::Output {
+        let index = self.index_of(p, q, r, s);
+        &mut self.data[index]
+    }
+}
+
+/// Columns of the MO coefficients for a range of orbitals
+pub fn orbital_space(c: &FMatrix, orbitals: Range<usize>) -> FMatrix {
+    if orbitals.is_empty() {
+        return FMatrix::zero(c.rows, 0);
+    }
+    c.slice(0, c.rows - 1, orbitals.start, orbitals.end - 1)
+}
+
+/// Half-transformed integrals (μν|rs), stored r-major as one block of n_s values for
+/// every AO pair μ >= ν per r, such that the second half reads every block exactly once
+enum HalfTransformed {
+    Memory(Vec<f64>),
+    Disk(String),
+}
+
+impl HalfTransformed {
+    /// Visit all blocks in the order of r
+    fn for_each(&self, block_len: usize, mut f: impl FnMut(usize, &[f64])) {
+        match self {
+            HalfTransformed::Memory(data) => {
+                for (r, block) in data.chunks_exact(block_len).enumerate() {
+                    f(r, block);
+                }
+            }
+            HalfTransformed::Disk(name) => {
+                let file = File::open(name).expect("Unable to open file for reading");
+                let mut reader = BufReader::new(file);
+                let mut bytes = vec![0u8; 8 * block_len];
+                let mut block = vec![0.0; block_len];
+                let mut r = 0;
+                while reader.read_exact(&mut bytes).is_ok() {
+                    for (x, b) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+                        *x = f64::from_le_bytes(b.try_into().unwrap());
+                    }
+                    f(r, &block);
+                    r += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Four-index transformation (pq|rs) = \sum_{μνλσ} Cμp Cνq (μν|λσ) Cλr Cσs of the lower
+/// triangle of AO integrals (μν|λσ) with four sets of MO coefficients (AO x MO), done as
+/// four quarter transformations
+pub fn mo_transform(
+    eri: &FMatrixContainer,
+    c: [&FMatrix; 4],
+    buffer: &TransformBuffer,
+) -> MOIntegrals {
+    let [c_p, c_q, c_r, c_s] = c;
+    let dim = c_p.rows;
+    let dims = c.map(|c| c.cols);
+    let [n_p, n_q, n_r, n_s] = dims;
+
+    let mut mo = MOIntegrals::zero(dims);
+    if mo.data.is_empty() {
+        return mo;
+    }
+
+    // first half: (μν|rs) = \sum_λ Cλr \sum_σ (μν|λσ) Cσs
+    let c_r_t = c_r.transposed();
+    let pairs: Vec<(usize, usize)> = (0..dim)
+        .flat_map(|mu| (0..=mu).map(move |nu| (mu, nu)))
+        .collect();
+    let records = pairs.iter().map(|pair| &c_r_t * &(&eri[*pair] * c_s));
+
+    // offset of the values (μν|rs) of a pair and r in the r-major storage
+    let block_len = pairs.len() * n_s;
+    let offset = |pair: usize, r: usize| r * block_len + pair * n_s;
+    let half = match buffer {
+        TransformBuffer::Memory => {
+            let mut data = vec![0.0; n_r * block_len];
+            for (pair, x) in records.enumerate() {
+                let x: &[f64] = &x;
+                for r in 0..n_r {
+                    let start = offset(pair, r);
+                    data[start..start + n_s].copy_from_slice(&x[r * n_s..(r + 1) * n_s]);
+                }
+            }
+            HalfTransformed::Memory(data)
+        }
+        TransformBuffer::Disk(name) => {
+            let mut file = File::create(name).expect("Unable to create file");
+            let mut bytes = Vec::with_capacity(8 * n_s);
+            for (pair, x) in records.enumerate() {
+                let x: &[f64] = &x;
+                for r in 0..n_r {
+                    bytes.clear();
+                    for value in &x[r * n_s..(r + 1) * n_s] {
+                        bytes.extend_from_slice(&value.to_le_bytes());
+                    }
+                    file.seek(SeekFrom::Start(8 * offset(pair, r) as u64))
+                        .expect("Unable to seek in file");
+                    file.write_all(&bytes).expect("Unable to write to file");
+                }
+            }
+            HalfTransformed::Disk(name.clone())
+        }
+    };
+
+    // second half, one r at a time: (pq|rs) = \sum_μ Cμp \sum_ν (μν|rs) Cνq
+    let c_p_t = c_p.transposed();
+    let mut ao = vec![FMatrix::zero(dim, dim); n_s];
+    half.for_each(block_len, |r, block| {
+        for (pair, values) in block.chunks_exact(n_s).enumerate() {
+            let (mu, nu) = pairs[pair];
+            for (s, value) in values.iter().enumerate() {
+                ao[s][(mu, nu)] = *value;
+                ao[s][(nu, mu)] = *value;
+            }
+        }
+
+        for (s, ao) in ao.iter().enumerate() {
+            let x = ao * c_q;
+            let x = &c_p_t * &x;
+            for p in 0..n_p {
+                for q in 0..n_q {
+                    let index = mo.index_of(p, q, r, s);
+                    mo.data[index] = x[(p, q)];
+                }
+            }
+        }
+    });
+
+    if let HalfTransformed::Disk(name) = half {
+        remove_file(name).expect("Unable to remove file");
+    }
+
+    mo
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mo_transform, orbital_space, TransformBuffer};
+    use crate::linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer};
+
+    /// (μν|λσ) of a model with three basis functions and the full permutational symmetry
+    fn eri() -> FMatrixContainer {
+        let pair = |i: usize, j: usize| (i.max(j) * (i.max(j) + 1) / 2 + i.min(j)) as f64;
+        let value = |i: usize, j: usize, k: usize, l: usize| {
+            let (ij, kl) = (pair(i, j), pair(k, l));
+            1.0 / (1.0 + ij + kl) + 0.1 * ij * kl
+        };
+
+        let mut eri = FMatrixContainer::new();
+        for i in 0..3 {
+            for j in 0..=i {
+                let mut mat = FMatrix::zero(3, 3);
+                for k in 0..3 {
+                    for l in 0..3 {
+                        mat[(k, l)] = value(i, j, k, l);
+                    }
+                }
+                eri.insert((i, j), &mat);
+            }
+        }
+        eri
+    }
+
+    fn coefficients() -> FMatrix {
+        FMatrix::new_from_vec(3, 3, &[0.7, 0.3, -0.2, 0.1, -0.8, 0.5, 0.4, 0.2, 0.9])
+    }
+
+    #[test]
+    fn transform() {
+        let eri = eri();
+        let c = coefficients();
+
+        // brute force reference
+        let ao = |i: usize, j: usize, k: usize, l: usize| eri[(i.max(j), i.min(j))][(k, l)];
+        let occ = orbital_space(&c, 0..1);
+        let virt = orbital_space(&c, 1..3);
+        let mo = mo_transform(&eri, [&occ, &virt, &c, &virt], &TransformBuffer::Memory);
+        assert_eq!(mo.dims(), [1, 2, 3, 2]);
+
+        for p in 0..1 {
+            for q in 0..2 {
+                for r in 0..3 {
+                    for s in 0..2 {
+                        let mut reference = 0.0;
+                        for i in 0..3 {
+                            for j in 0..3 {
+                                for k in 0..3 {
+                                    for l in 0..3 {
+                                        reference += occ[(i, p)]
+                                            * virt[(j, q)]
+                                            * c[(k, r)]
+                                            * virt[(l, s)]
+                                            * ao(i, j, k, l);
+                                    }
+                                }
+                            }
+                        }
+                        assert!((mo[(p, q, r, s)] - reference).abs() < 1e-12);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn disk_buffer() {
+        let eri = eri();
+        let c = coefficients();
+        let name = std::env::temp_dir().join("mo_transform_test.tmp");
+        let name = name.to_str().unwrap().to_string();
+
+        let memory = mo_transform(&eri, [&c; 4], &TransformBuffer::Memory);
+        let disk = mo_transform(&eri, [&c; 4], &TransformBuffer::Disk(name.clone()));
+        assert_eq!(memory, disk);
+        assert!(!std::path::Path::new(&name).exists());
+
+        let occ = orbital_space(&c, 0..1);
+        let virt = orbital_space(&c, 1..3);
+        let spaces = [&virt, &occ, &c, &virt];
+        let disk = mo_transform(&eri, spaces, &TransformBuffer::Disk(name.clone()));
+        assert_eq!(mo_transform(&eri, spaces, &TransformBuffer::Memory), disk);
+
+        // (pq|rs) = (qp|rs) = (rs|pq)
+        assert!((memory[(0, 1, 2, 0)] - memory[(1, 0, 2, 0)]).abs() < 1e-12);
+        assert!((memory[(0, 1, 2, 0)] - memory[(2, 0, 0, 1)]).abs() < 1e-12);
+    }
+}
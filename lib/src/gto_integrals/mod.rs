@@ -1,11 +1,14 @@
+mod dipole;
 mod e;
 mod eri;
 mod h_core;
 pub mod integral_interface;
 mod kinetic_energy;
-mod nuclear_electron_attraction;
+pub mod mo_transform;
+pub(crate) mod nuclear_electron_attraction;
 pub mod nuclear_repulsion;
 pub mod one_electron;
-mod overlap;
+pub(crate) mod overlap;
 mod r;
+pub mod ri;
 pub mod two_electron;
@@ -12,6 +12,25 @@ pub fn nuclear_electron_attraction(
     ml_b: &[i16; 3],
     b_origin: &[f64; 3],
     atoms: &[Atom],
+) -> f64 {
+    let charges: Vec<(f64, [f64; 3])> = atoms
+        .iter()
+        .map(|atom| (f64::from(atom.z()), atom.origin))
+        .collect();
+
+    -point_charge_potential(a, ml_a, a_origin, b, ml_b, b_origin, &charges)
+}
+
+/// Potential of point charges (q, C) over a product of primitives:
+/// \sum_C q_C ∫ a(r) b(r) / |r - C| dr
+pub fn point_charge_potential(
+    a: &f64,
+    ml_a: &[i16; 3],
+    a_origin: &[f64; 3],
+    b: &f64,
+    ml_b: &[i16; 3],
+    b_origin: &[f64; 3],
+    charges: &[(f64, [f64; 3])],
 ) -> f64 {
     let l_a = ml_a[0];
     let m_a = ml_a[1];
@@ -34,14 +53,14 @@ pub fn nuclear_electron_attraction(
                     * e(n_a, n_b, v, a_origin[2] - b_origin[2], a, b);
 
                 let mut rval = 0.0;
-                for atom in atoms {
+                for (q, origin) in charges {
                     let pc = [
-                        p_origin[0] - atom.origin[0],
-                        p_origin[1] - atom.origin[1],
-                        p_origin[2] - atom.origin[2],
+                        p_origin[0] - origin[0],
+                        p_origin[1] - origin[1],
+                        p_origin[2] - origin[2],
                     ];
-                    let distance = distance(&p_origin, &atom.origin);
-                    rval += f64::from(atom.z()) * r(t, u, v, 0, p, &pc, &distance);
+                    let distance = distance(&p_origin, origin);
+                    rval += q * r(t, u, v, 0, p, &pc, &distance);
                 }
 
                 val += eval * rval;
@@ -49,5 +68,39 @@ pub fn nuclear_electron_attraction(
         }
     }
 
-    -2.0 * PI / p * val
+    2.0 * PI / p * val
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::atom::Atom;
+    use crate::misc::elements::Element::{H, O};
+
+    // reference values from an independent Obara-Saika implementation
+    const A: [f64; 3] = [0.0, 0.1, -0.3];
+    const B: [f64; 3] = [0.4, -0.2, 0.5];
+
+    #[test]
+    fn nuclear_electron_attraction() {
+        let atoms = vec![
+            Atom::new(O, [-0.3, 0.6, 0.2]),
+            Atom::new(H, [0.2, 0.3, -0.4]),
+        ];
+
+        let reference = [
+            ([0, 0, 0], [0, 0, 0], -12.308883546577004),
+            ([1, 0, 0], [0, 1, 1], 0.06596031504638222),
+            ([3, 1, 1], [1, 0, 2], -0.007387449242060357),
+            ([5, 0, 0], [0, 2, 3], 0.09671877515833086),
+            ([2, 2, 1], [0, 3, 2], 0.009275007383747265),
+        ];
+
+        for (ml_a, ml_b, value) in reference {
+            let v = super::nuclear_electron_attraction(&1.3, &ml_a, &A, &0.8, &ml_b, &B, &atoms);
+            assert!(
+                (v - value).abs() < 1e-12,
+                "{ml_a:?} {ml_b:?}: {v} != {value}"
+            );
+        }
+    }
 }
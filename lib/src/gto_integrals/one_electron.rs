@@ -1,7 +1,8 @@
 use crate::{
     gto_basis_sets::basis::{BasisShell, CartesianBasisFunction},
     gto_integrals::{
-        h_core::h_core, integral_interface::IntegralInterface, kinetic_energy::kinetic_energy,
+        dipole::dipole, h_core::h_core, integral_interface::IntegralInterface,
+        kinetic_energy::kinetic_energy,
         nuclear_electron_attraction::nuclear_electron_attraction, overlap::overlap,
     },
     linear_algebra::matrix::FMatrix,
@@ -14,6 +15,10 @@ pub enum OneElectronKernel {
     Kinetic,
     NuclearAttraction,
     Overlap,
+    // components of the position operator about the origin
+    DipoleX,
+    DipoleY,
+    DipoleZ,
 }
 
 impl OneElectronKernel {
@@ -23,18 +28,34 @@ impl OneElectronKernel {
             OneElectronKernel::Kinetic => "t_ao.tmp",
             OneElectronKernel::NuclearAttraction => "v_ao.tmp",
             OneElectronKernel::Overlap => "s_ao.tmp",
+            OneElectronKernel::DipoleX => "dx_ao.tmp",
+            OneElectronKernel::DipoleY => "dy_ao.tmp",
+            OneElectronKernel::DipoleZ => "dz_ao.tmp",
         }
     }
 
     pub fn iter() -> Iter<'static, OneElectronKernel> {
-        static KERNEL: [OneElectronKernel; 4] = [
+        static KERNEL: [OneElectronKernel; 7] = [
             OneElectronKernel::HCore,
             OneElectronKernel::Kinetic,
             OneElectronKernel::NuclearAttraction,
             OneElectronKernel::Overlap,
+            OneElectronKernel::DipoleX,
+            OneElectronKernel::DipoleY,
+            OneElectronKernel::DipoleZ,
         ];
         KERNEL.iter()
     }
+
+    /// Cartesian component of the dipole kernels
+    fn component(&self) -> usize {
+        match self {
+            OneElectronKernel::DipoleX => 0,
+            OneElectronKernel::DipoleY => 1,
+            OneElectronKernel::DipoleZ => 2,
+            _ => panic!("{} is not a dipole integral", self),
+        }
+    }
 }
 
 impl Display for OneElectronKernel {
@@ -44,6 +65,9 @@ impl Display for OneElectronKernel {
             OneElectronKernel::Kinetic => write!(f, "T")?,
             OneElectronKernel::NuclearAttraction => write!(f, "V")?,
             OneElectronKernel::Overlap => write!(f, "S")?,
+            OneElectronKernel::DipoleX => write!(f, "Dx")?,
+            OneElectronKernel::DipoleY => write!(f, "Dy")?,
+            OneElectronKernel::DipoleZ => write!(f, "Dz")?,
         }
 
         Ok(())
@@ -53,24 +77,30 @@ impl Display for OneElectronKernel {
 impl IntegralInterface {
     fn cartesian_to_spherical_transformation_1e(
         &self,
-        l_a: &u8,
-        l_b: &u8,
+        a: &BasisShell,
+        b: &BasisShell,
         matrix_cartesian: FMatrix,
     ) -> FMatrix {
-        let ta = self.basis().trafo_matrix(l_a);
-        let tb = self.basis().trafo_matrix(l_b);
+        if self.basis().cartesian() {
+            return matrix_cartesian;
+        }
+
+        let ta = self.basis().shell_trafo(a);
+        let tb = self.basis().shell_trafo(b);
         ta * (matrix_cartesian * tb.transposed())
     }
 
+    /// Contracted integrals between two cartesian basis functions, for all pairs of
+    /// contracted functions. Every primitive integral is calculated only once.
     fn calc_one_electron_cbf_cbf(
         &self,
         kernel: &OneElectronKernel,
         a: &CartesianBasisFunction,
         b: &CartesianBasisFunction,
-    ) -> f64 {
-        let mut value = 0.0;
-        for (ia, ca) in a.coefs().iter().enumerate() {
-            for (ib, cb) in b.coefs().iter().enumerate() {
+    ) -> FMatrix {
+        let mut value = FMatrix::zero(a.coefs().len(), b.coefs().len());
+        for ia in 0..a.exps().len() {
+            for ib in 0..b.exps().len() {
                 let integral = {
                     match kernel {
                         OneElectronKernel::HCore => h_core(
@@ -107,10 +137,26 @@ impl IntegralInterface {
                             &b.ml_i16(),
                             b.origin(),
                         ),
+                        OneElectronKernel::DipoleX
+                        | OneElectronKernel::DipoleY
+                        | OneElectronKernel::DipoleZ => dipole(
+                            &a.exps()[ia],
+                            &a.ml_i16(),
+                            a.origin(),
+                            &b.exps()[ib],
+                            &b.ml_i16(),
+                            b.origin(),
+                            kernel.component(),
+                        ),
                     }
-                };
+                } * a.norm()[ia]
+                    * b.norm()[ib];
 
-                value += a.norm()[ia] * b.norm()[ib] * ca * cb * integral;
+                for (ca, coefs_a) in a.coefs().iter().enumerate() {
+                    for (cb, coefs_b) in b.coefs().iter().enumerate() {
+                        value[(ca, cb)] += coefs_a[ia] * coefs_b[ib] * integral;
+                    }
+                }
             }
         }
 
@@ -123,22 +169,37 @@ impl IntegralInterface {
         a: &BasisShell,
         b: &BasisShell,
     ) -> FMatrix {
-        let dim_a = a.cdim();
-        let dim_b = b.cdim();
+        // cartesian components per contracted function
+        let dim_a = a.cbf().len();
+        let dim_b = b.cbf().len();
 
-        let mut matrix_cartesian = FMatrix::zero(dim_a, dim_b);
+        let mut matrix_cartesian = FMatrix::zero(a.cdim(), b.cdim());
 
         for i in 0..dim_a {
             for j in 0..dim_b {
-                matrix_cartesian[(i, j)] =
-                    self.calc_one_electron_cbf_cbf(kernel, &a.cbf()[i], &b.cbf()[j]);
+                let contracted = self.calc_one_electron_cbf_cbf(kernel, &a.cbf()[i], &b.cbf()[j]);
+                for ca in 0..contracted.rows {
+                    for cb in 0..contracted.cols {
+                        matrix_cartesian[(ca * dim_a + i, cb * dim_b + j)] =
+                            contracted[(ca, cb)];
+                    }
+                }
             }
         }
 
-        self.cartesian_to_spherical_transformation_1e(a.l(), b.l(), matrix_cartesian)
+        self.cartesian_to_spherical_transformation_1e(a, b, matrix_cartesian)
     }
 
+    /// One-electron integral matrix, stored on disk for the subsequent modules
     pub fn calc_one_electron_integral(&self, kernel: OneElectronKernel) -> FMatrix {
+        let filename = kernel.to_filename().to_owned();
+        let one_electron_integral = self.compute_one_electron_integral(kernel);
+        one_electron_integral.store(&filename);
+        one_electron_integral
+    }
+
+    /// One-electron integral matrix, kept in memory only
+    pub fn compute_one_electron_integral(&self, kernel: OneElectronKernel) -> FMatrix {
         print!("Calculating one-electron integral: {} ... ", kernel);
         let t = Instant::now();
 
@@ -173,7 +234,6 @@ impl IntegralInterface {
 
         println!("done ({:?})", t.elapsed());
 
-        one_electron_integral.store(kernel.to_filename());
         one_electron_integral
     }
 }
@@ -19,3 +19,26 @@ pub fn overlap(
     // return S1*S2*S3*pow(PI/(a+b),1.5);
     s1 * s2 * s3 * (PI / (a + b)).powf(1.5)
 }
+
+#[cfg(test)]
+mod tests {
+    // reference values from an independent Obara-Saika implementation
+    const A: [f64; 3] = [0.0, 0.1, -0.3];
+    const B: [f64; 3] = [0.4, -0.2, 0.5];
+
+    #[test]
+    fn overlap() {
+        let reference = [
+            ([1, 0, 0], [0, 1, 0], 0.03332356063535513),
+            ([2, 0, 0], [0, 2, 0], 0.08387684218759063),
+            ([3, 1, 1], [1, 0, 2], 0.0018920203448170977),
+            ([5, 0, 0], [0, 2, 3], -0.02106179584426056),
+            ([2, 2, 1], [0, 3, 2], -0.001459687290240335),
+        ];
+
+        for (ml_a, ml_b, value) in reference {
+            let s = super::overlap(&1.3, &ml_a, &A, &0.8, &ml_b, &B);
+            assert!((s - value).abs() < 1e-12, "{ml_a:?} {ml_b:?}: {s} != {value}");
+        }
+    }
+}
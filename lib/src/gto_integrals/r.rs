@@ -1,4 +1,4 @@
-fn boys(n: i16, t: f64) -> f64 {
+pub(crate) fn boys(n: i16, t: f64) -> f64 {
     let mut f;
 
     // ----------------------------------------------
@@ -6,9 +6,11 @@ fn boys(n: i16, t: f64) -> f64 {
     // ----------------------------------------------
     if t > 30.0 {
         let pi_sqrt = 0.88622692545275801365;
+        // erf(sqrt(t)) = 1 to machine precision
         f = pi_sqrt / t.sqrt();
+        let exp_t = (-t).exp();
         for i in 1..=n {
-            f *= (f64::from(i) - 0.5) / t;
+            f = ((f64::from(i) - 0.5) * f - 0.5 * exp_t) / t;
         }
     }
     // ----------------------------------------------
@@ -18,7 +20,7 @@ fn boys(n: i16, t: f64) -> f64 {
         let pt5_pm = f64::from(n) + 0.5;
         let mut term = 0.5 / pt5_pm;
         let mut sum = term;
-        let accuracy = 1e-12;
+        let accuracy = 1e-16;
 
         let mut i = 1;
         loop {
@@ -26,7 +28,7 @@ fn boys(n: i16, t: f64) -> f64 {
             sum += term;
             i += 1;
 
-            if term < accuracy || i > 1000 {
+            if term < accuracy * sum || i > 1000 {
                 break;
             }
         }
@@ -65,3 +67,25 @@ pub fn r(t: i16, u: i16, v: i16, n: i16, p: f64, p_origin: &[f64; 3], distance:
 
     value
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn boys() {
+        // reference values from the series expansion in 60 digit arithmetic
+        let reference = [
+            (0, 0.0, 1.0),
+            (0, 1.0, 0.746824132812427),
+            (4, 12.5, 6.717797532407721e-05),
+            (10, 30.5, 1.4728359911281616e-10),
+            (20, 30.5, 9.855118827688411e-14),
+            (20, 45.0, 3.4753792295077497e-17),
+            (16, 80.0, 1.0307478731978099e-19),
+        ];
+
+        for (n, t, value) in reference {
+            let f = super::boys(n, t);
+            assert!(((f - value) / value).abs() < 1e-12, "F_{n}({t}): {f} != {value}");
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Two- and three-center electron repulsion integrals for the resolution of the identity,
+//! evaluated as four-center integrals with an unnormalized s function of exponent zero.
+//! All cartesian components of a shell triple are done at once with tabulated Hermite
+//! expansion coefficients and Hermite Coulomb integrals (McMurchie-Davidson).
+
+use crate::{
+    gto_basis_sets::basis::{Basis, BasisShell},
+    gto_integrals::r::boys,
+    linear_algebra::{
+        constants::PI,
+        functions::{distance, gaussian_product_center},
+        matrix::FMatrix,
+    },
+};
+
+/// Primitives of a shell with the contraction coefficients and normalization of every
+/// cartesian component folded into one weight
+struct ShellData {
+    origin: [f64; 3],
+    l: usize,
+    exps: Vec<f64>,
+    ml: Vec<[usize; 3]>,
+    // weights[component][contraction][primitive]
+    weights: Vec<Vec<Vec<f64>>>,
+}
+
+impl ShellData {
+    fn new(shell: &BasisShell) -> Self {
+        Self {
+            origin: *shell.origin(),
+            l: usize::from(*shell.l()),
+            exps: shell.exps().to_vec(),
+            ml: shell
+                .cbf()
+                .iter()
+                .map(|cbf| cbf.ml().map(usize::from))
+                .collect(),
+            weights: shell
+                .cbf()
+                .iter()
+                .map(|cbf| {
+                    cbf.coefs()
+                        .iter()
+                        .map(|c| c.iter().zip(cbf.norm()).map(|(c, n)| c * n).collect())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// s function with exponent zero, i.e. the constant 1
+    fn unit(origin: [f64; 3]) -> Self {
+        Self {
+            origin,
+            l: 0,
+            exps: vec![0.0],
+            ml: vec![[0, 0, 0]],
+            weights: vec![vec![vec![1.0]]],
+        }
+    }
+
+    /// number of cartesian functions (all contractions)
+    fn dim(&self) -> usize {
+        self.ml.len() * self.weights[0].len()
+    }
+}
+
+/// Hermite expansion coefficients E^{ij}_t of a one-dimensional Gaussian product for
+/// i <= i_max, j <= j_max, indexed as [i][j][t], with x = A - B
+fn hermite_e(i_max: usize, j_max: usize, x: f64, a: f64, b: f64) -> Vec<Vec<Vec<f64>>> {
+    let p = a + b;
+    let (x_pa, x_pb) = (-b / p * x, a / p * x);
+    let t_max = i_max + j_max;
+    let mut e = vec![vec![vec![0.0; t_max + 2]; j_max + 1]; i_max + 1];
+
+    e[0][0][0] = (-a * b / p * x * x).exp();
+    for i in 0..=i_max {
+        for j in 0..=j_max {
+            if i == 0 && j == 0 {
+                continue;
+            }
+            // raise i if possible, j otherwise
+            let (prev, x_p) = match i {
+                0 => (&e[i][j - 1], x_pb),
+                _ => (&e[i - 1][j], x_pa),
+            };
+            let mut next = vec![0.0; t_max + 2];
+            for t in 0..=i + j {
+                let lower = if t > 0 { prev[t - 1] } else { 0.0 };
+                next[t] = lower / (2.0 * p) + x_p * prev[t] + (t + 1) as f64 * prev[t + 1];
+            }
+            e[i][j] = next;
+        }
+    }
+    e
+}
+
+/// Hermite Coulomb integrals R_{tuv} for t + u + v <= l, indexed as [t][u][v]
+fn hermite_r(l: usize, alpha: f64, pq: &[f64; 3], r_pq: f64) -> Vec<Vec<Vec<f64>>> {
+    let t_arg = alpha * r_pq * r_pq;
+    let table = || vec![vec![vec![0.0; l + 1]; l + 1]; l + 1];
+
+    // R^n from R^(n + 1), starting at n = l
+    let mut r = table();
+    for n in (0..=l).rev() {
+        let mut next = table();
+        for t in 0..=l - n {
+            for u in 0..=l - n - t {
+                for v in 0..=l - n - t - u {
+                    next[t][u][v] = if t > 0 {
+                        let lower = if t > 1 {
+                            (t - 1) as f64 * r[t - 2][u][v]
+                        } else {
+                            0.0
+                        };
+                        lower + pq[0] * r[t - 1][u][v]
+                    } else if u > 0 {
+                        let lower = if u > 1 {
+                            (u - 1) as f64 * r[t][u - 2][v]
+                        } else {
+                            0.0
+                        };
+                        lower + pq[1] * r[t][u - 1][v]
+                    } else if v > 0 {
+                        let lower = if v > 1 {
+                            (v - 1) as f64 * r[t][u][v - 2]
+                        } else {
+                            0.0
+                        };
+                        lower + pq[2] * r[t][u][v - 1]
+                    } else {
+                        (-2.0 * alpha).powi(n as i32) * boys(n as i16, t_arg)
+                    };
+                }
+            }
+        }
+        r = next;
+    }
+    r
+}
+
+/// Contracted integrals (ab|c) over all cartesian functions of three shells, ordered as
+/// (a, b, c) with the function index (contraction * components + component)
+fn contracted(a: &ShellData, b: &ShellData, c: &ShellData) -> Vec<f64> {
+    let (dim_a, dim_b, dim_c) = (a.dim(), b.dim(), c.dim());
+    let (n_a, n_b, n_c) = (a.ml.len(), b.ml.len(), c.ml.len());
+    let l = a.l + b.l + c.l;
+    let mut value = vec![0.0; dim_a * dim_b * dim_c];
+
+    for (ia, exp_a) in a.exps.iter().enumerate() {
+        for (ib, exp_b) in b.exps.iter().enumerate() {
+            let p = exp_a + exp_b;
+            let p_origin = gaussian_product_center(exp_a, &a.origin, exp_b, &b.origin);
+            let e_ab: Vec<_> = (0..3)
+                .map(|k| hermite_e(a.l, b.l, a.origin[k] - b.origin[k], *exp_a, *exp_b))
+                .collect();
+
+            for (ic, exp_c) in c.exps.iter().enumerate() {
+                let q = *exp_c;
+                let e_c: Vec<_> = (0..3).map(|_| hermite_e(c.l, 0, 0.0, q, 0.0)).collect();
+                let alpha = p * q / (p + q);
+                let pq = [0, 1, 2].map(|k| p_origin[k] - c.origin[k]);
+                let r = hermite_r(l, alpha, &pq, distance(&p_origin, &c.origin));
+                let prefactor = 2.0 * PI.powf(2.5) / (p * q * (p + q).sqrt());
+
+                for (ka, ml_a) in a.ml.iter().enumerate() {
+                    for (kb, ml_b) in b.ml.iter().enumerate() {
+                        for (kc, ml_c) in c.ml.iter().enumerate() {
+                            let mut integral = 0.0;
+                            for t in 0..=ml_a[0] + ml_b[0] {
+                                let ex = e_ab[0][ml_a[0]][ml_b[0]][t];
+                                for u in 0..=ml_a[1] + ml_b[1] {
+                                    let exy = ex * e_ab[1][ml_a[1]][ml_b[1]][u];
+                                    for v in 0..=ml_a[2] + ml_b[2] {
+                                        let exyz = exy * e_ab[2][ml_a[2]][ml_b[2]][v];
+                                        for tau in 0..=ml_c[0] {
+                                            let fx = e_c[0][ml_c[0]][0][tau];
+                                            for nu in 0..=ml_c[1] {
+                                                let fxy = fx * e_c[1][ml_c[1]][0][nu];
+                                                for phi in 0..=ml_c[2] {
+                                                    let sign = match (tau + nu + phi) % 2 {
+                                                        0 => 1.0,
+                                                        _ => -1.0,
+                                                    };
+                                                    integral += exyz
+                                                        * fxy
+                                                        * e_c[2][ml_c[2]][0][phi]
+                                                        * sign
+                                                        * r[t + tau][u + nu][v + phi];
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            integral *= prefactor;
+
+                            // contract
+                            for (ca, wa) in a.weights[ka].iter().enumerate() {
+                                for (cb, wb) in b.weights[kb].iter().enumerate() {
+                                    let wab = wa[ia] * wb[ib] * integral;
+                                    for (cc, wc) in c.weights[kc].iter().enumerate() {
+                                        let index = ((ca * n_a + ka) * dim_b + cb * n_b + kb)
+                                            * dim_c
+                                            + cc * n_c
+                                            + kc;
+                                        value[index] += wab * wc[ic];
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    value
+}
+
+/// Cartesian to spherical transformation of a shell, the identity for cartesian basis
+/// functions
+fn trafo(basis: &Basis, shell: &BasisShell) -> FMatrix {
+    if basis.cartesian() {
+        let mut identity = FMatrix::zero(shell.cdim(), shell.cdim());
+        for i in 0..shell.cdim() {
+            identity[(i, i)] = 1.0;
+        }
+        return identity;
+    }
+    basis.shell_trafo(shell)
+}
+
+/// Coulomb metric (P|Q) of the auxiliary basis
+pub fn two_center(aux: &Basis) -> FMatrix {
+    let dim = aux.dim();
+    let mut metric = FMatrix::zero(dim, dim);
+    let shells: Vec<ShellData> = aux.shells().iter().map(ShellData::new).collect();
+
+    for (sp, p) in aux.shells().iter().enumerate() {
+        // the unit function is placed on the centre of P
+        let unit = ShellData::unit(*p.origin());
+        for (sq, q) in aux.shells().iter().enumerate().take(sp + 1) {
+            let cartesian = FMatrix::new_from_vec(
+                p.cdim(),
+                q.cdim(),
+                &contracted(&shells[sp], &unit, &shells[sq]),
+            );
+
+            let block = trafo(aux, p) * (&cartesian * trafo(aux, q).transposed());
+            let (offset_p, offset_q) = (aux.offset(sp), aux.offset(sq));
+            for i in 0..block.rows {
+                for j in 0..block.cols {
+                    metric[(offset_p + i, offset_q + j)] = block[(i, j)];
+                    metric[(offset_q + j, offset_p + i)] = block[(i, j)];
+                }
+            }
+        }
+    }
+
+    metric
+}
+
+/// Three-center integrals (P|μν), one matrix over the orbital basis per auxiliary
+/// function
+pub fn three_center(basis: &Basis, aux: &Basis) -> Vec<FMatrix> {
+    let dim = basis.dim();
+    let mut integrals = vec![FMatrix::zero(dim, dim); aux.dim()];
+    let shells: Vec<ShellData> = basis.shells().iter().map(ShellData::new).collect();
+    let aux_shells: Vec<ShellData> = aux.shells().iter().map(ShellData::new).collect();
+
+    for (sa, a) in basis.shells().iter().enumerate() {
+        let t_a = trafo(basis, a);
+        for (sb, b) in basis.shells().iter().enumerate().take(sa + 1) {
+            let t_b = trafo(basis, b).transposed();
+
+            for (sp, p) in aux.shells().iter().enumerate() {
+                // (ab|p) with one cartesian (a, b) block per auxiliary component
+                let value = contracted(&shells[sa], &shells[sb], &aux_shells[sp]);
+                let cartesian: Vec<FMatrix> = (0..p.cdim())
+                    .map(|k| {
+                        let mut block = FMatrix::zero(a.cdim(), b.cdim());
+                        for i in 0..a.cdim() {
+                            for j in 0..b.cdim() {
+                                block[(i, j)] = value[(i * b.cdim() + j) * p.cdim() + k];
+                            }
+                        }
+                        // spherical (a, b) block
+                        &t_a * &(&block * &t_b)
+                    })
+                    .collect();
+
+                // spherical auxiliary components
+                let t_p = trafo(aux, p);
+                let (offset_a, offset_b, offset_p) =
+                    (basis.offset(sa), basis.offset(sb), aux.offset(sp));
+                for (ip, integral) in integrals[offset_p..offset_p + t_p.rows]
+                    .iter_mut()
+                    .enumerate()
+                {
+                    for i in 0..t_a.rows {
+                        for j in 0..t_b.cols {
+                            let value: f64 = cartesian
+                                .iter()
+                                .enumerate()
+                                .map(|(kp, block)| t_p[(ip, kp)] * block[(i, j)])
+                                .sum();
+                            integral[(offset_a + i, offset_b + j)] = value;
+                            integral[(offset_b + j, offset_a + i)] = value;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    integrals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contracted, three_center, two_center, ShellData};
+    use crate::geometry::atom::Atom;
+    use crate::gto_basis_sets::auxiliary::AuxiliaryBasis;
+    use crate::gto_basis_sets::basis::gaussian_layout;
+    use crate::gto_basis_sets::basis::{Basis, Shell};
+    use crate::gto_basis_sets::{load_basis_set, BasisSet};
+    use crate::gto_integrals::eri::eri;
+    use crate::linear_algebra::constants::PI;
+    use crate::misc::elements::Element::H;
+    use crate::tests::water_xz;
+
+    #[test]
+    fn s_functions() {
+        // normalized s functions on one centre:
+        // (ab|c) = N_a N_b N_c 2 π^2.5 / ((a + b) c sqrt(a + b + c))
+        let atoms = vec![Atom::new(H, [0.1, -0.2, 0.3])];
+        let shells = |exps: [f64; 2]| {
+            vec![
+                vec![],
+                exps.iter()
+                    .map(|e| Shell::new(0, vec![*e], vec![1.0]))
+                    .collect(),
+            ]
+        };
+        let (orbital_exps, aux_exps) = ([0.5, 1.3], [0.8, 2.1]);
+        let basis = Basis::new(&atoms, shells(orbital_exps));
+        let aux = Basis::new(&atoms, shells(aux_exps));
+        let norm = |e: f64| (2.0 * e / PI).powf(0.75);
+        let coulomb = |p: f64, q: f64| 2.0 * PI.powf(2.5) / (p * q * (p + q).sqrt());
+
+        let metric = two_center(&aux);
+        let three = three_center(&basis, &aux);
+        for (p, e_p) in aux_exps.iter().enumerate() {
+            for (q, e_q) in aux_exps.iter().enumerate() {
+                let reference = norm(*e_p) * norm(*e_q) * coulomb(*e_p, *e_q);
+                assert!((metric[(p, q)] - reference).abs() < 1e-12);
+            }
+            for (i, e_i) in orbital_exps.iter().enumerate() {
+                for (j, e_j) in orbital_exps.iter().enumerate() {
+                    let reference = norm(*e_i) * norm(*e_j) * norm(*e_p) * coulomb(e_i + e_j, *e_p);
+                    assert!((three[p][(i, j)] - reference).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kernel() {
+        // unnormalized primitives against the general four-center integral
+        let primitive = |l: u8, exp: f64, origin: [f64; 3]| {
+            let ml: Vec<[usize; 3]> = gaussian_layout(&l)
+                .iter()
+                .map(|ml| ml.map(usize::from))
+                .collect();
+            ShellData {
+                origin,
+                l: usize::from(l),
+                exps: vec![exp],
+                weights: vec![vec![vec![1.0]]; ml.len()],
+                ml,
+            }
+        };
+        let a = primitive(2, 1.3, [0.0, 0.1, -0.3]);
+        let b = primitive(1, 0.8, [0.4, -0.2, 0.5]);
+        let c = primitive(3, 1.1, [-0.3, 0.6, 0.2]);
+
+        let value = contracted(&a, &b, &c);
+        let ml = |shell: &ShellData, k: usize| shell.ml[k].map(|x| x as i16);
+        for ka in 0..a.ml.len() {
+            for kb in 0..b.ml.len() {
+                for kc in 0..c.ml.len() {
+                    let reference = eri(
+                        &1.3,
+                        &ml(&a, ka),
+                        &a.origin,
+                        &0.8,
+                        &ml(&b, kb),
+                        &b.origin,
+                        &1.1,
+                        &ml(&c, kc),
+                        &c.origin,
+                        &0.0,
+                        &[0, 0, 0],
+                        &c.origin,
+                    );
+                    let index = (ka * b.ml.len() + kb) * c.ml.len() + kc;
+                    assert!((value[index] - reference).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn symmetry() {
+        let atoms = water_xz();
+        let basis = load_basis_set(&BasisSet::sto_3g, &atoms);
+        let aux = AuxiliaryBasis::EvenTempered.load(&basis, &atoms);
+
+        let metric = two_center(&aux);
+        let three = three_center(&basis, &aux);
+        assert_eq!(three.len(), aux.dim());
+        for p in 0..aux.dim() {
+            assert!(metric[(p, p)] > 0.0);
+            for q in 0..p {
+                assert_eq!(metric[(p, q)], metric[(q, p)]);
+            }
+            for mu in 0..basis.dim() {
+                for nu in 0..mu {
+                    assert_eq!(three[p][(mu, nu)], three[p][(nu, mu)]);
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    gto_basis_sets::basis::{cdim, dim, BasisShell, CartesianBasisFunction},
+    gto_basis_sets::basis::{BasisShell, CartesianBasisFunction},
     gto_integrals::{eri::eri, integral_interface::IntegralInterface},
     linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer},
 };
@@ -34,7 +34,16 @@ impl Display for TwoElectronKernel {
 }
 
 impl IntegralInterface {
+    /// Two-electron integrals, stored on disk for the subsequent modules
     pub fn calc_two_electron_integral(&self, kernel: TwoElectronKernel) -> FMatrixContainer {
+        let filename = kernel.to_filename().to_owned();
+        let two_electron_integral = self.compute_two_electron_integral(kernel);
+        two_electron_integral.store(&filename);
+        two_electron_integral
+    }
+
+    /// Two-electron integrals, kept in memory only
+    pub fn compute_two_electron_integral(&self, kernel: TwoElectronKernel) -> FMatrixContainer {
         print!("Calculating two-electron integral: {} ... ", kernel);
         let t = Instant::now();
 
@@ -94,7 +103,6 @@ impl IntegralInterface {
 
         println!("done ({:?})", t.elapsed());
 
-        two_electron_integral.store(kernel.to_filename());
         two_electron_integral
     }
 
@@ -106,40 +114,60 @@ impl IntegralInterface {
         c: &BasisShell,
         d: &BasisShell,
     ) -> FMatrixContainer {
-        let dim_a = a.cdim();
-        let dim_b = b.cdim();
-        let dim_c = c.cdim();
-        let dim_d = d.cdim();
+        // cartesian components per contracted function
+        let dim_a = a.cbf().len();
+        let dim_b = b.cbf().len();
+        let dim_c = c.cbf().len();
+        let dim_d = d.cbf().len();
+
+        let nc_a = a.n_contractions();
+        let nc_b = b.n_contractions();
+        let nc_c = c.n_contractions();
+        let nc_d = d.n_contractions();
 
         let mut integral_cartesian = FMatrixContainer::new();
+        for ab in 0..a.cdim() {
+            for bb in 0..b.cdim() {
+                integral_cartesian.insert((ab, bb), &FMatrix::zero(c.cdim(), d.cdim()));
+            }
+        }
+
         for i in 0..dim_a {
             for j in 0..dim_b {
-                let mut mat = FMatrix::zero(dim_c, dim_d);
                 for k in 0..dim_c {
                     for l in 0..dim_d {
-                        mat[(k, l)] = self.calc_two_electron_cbf(
+                        let contracted = self.calc_two_electron_cbf(
                             kernel,
                             &a.cbf()[i],
                             &b.cbf()[j],
                             &c.cbf()[k],
                             &d.cbf()[l],
                         );
+
+                        // contracted is ordered as (ca, cb, cc, cd)
+                        for ca in 0..nc_a {
+                            for cb in 0..nc_b {
+                                let mat =
+                                    &mut integral_cartesian[(ca * dim_a + i, cb * dim_b + j)];
+                                for cc in 0..nc_c {
+                                    for cd in 0..nc_d {
+                                        mat[(cc * dim_c + k, cd * dim_d + l)] = contracted
+                                            [((ca * nc_b + cb) * nc_c + cc) * nc_d + cd];
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
-                integral_cartesian.insert((i, j), &mat);
             }
         }
 
         // transform to spherical
-        self.cartesian_to_spherical_transformation_2e(
-            a.l(),
-            b.l(),
-            c.l(),
-            d.l(),
-            integral_cartesian,
-        )
+        self.cartesian_to_spherical_transformation_2e(a, b, c, d, integral_cartesian)
     }
 
+    /// Contracted integrals for all combinations of contracted functions, ordered as
+    /// (ca, cb, cc, cd). Every primitive integral is calculated only once.
     fn calc_two_electron_cbf(
         &self,
         kernel: &TwoElectronKernel,
@@ -147,12 +175,13 @@ impl IntegralInterface {
         b: &CartesianBasisFunction,
         c: &CartesianBasisFunction,
         d: &CartesianBasisFunction,
-    ) -> f64 {
-        let mut value = 0.0;
-        for (ia, ca) in a.coefs().iter().enumerate() {
-            for (ib, cb) in b.coefs().iter().enumerate() {
-                for (ic, cc) in c.coefs().iter().enumerate() {
-                    for (id, cd) in d.coefs().iter().enumerate() {
+    ) -> Vec<f64> {
+        let mut value =
+            vec![0.0; a.coefs().len() * b.coefs().len() * c.coefs().len() * d.coefs().len()];
+        for ia in 0..a.exps().len() {
+            for ib in 0..b.exps().len() {
+                for ic in 0..c.exps().len() {
+                    for id in 0..d.exps().len() {
                         let integral = match kernel {
                             TwoElectronKernel::ERI => eri(
                                 &a.exps()[ia],
@@ -168,17 +197,23 @@ impl IntegralInterface {
                                 &d.ml_i16(),
                                 d.origin(),
                             ),
-                        };
-
-                        value += a.norm()[ia]
+                        } * a.norm()[ia]
                             * b.norm()[ib]
                             * c.norm()[ic]
-                            * d.norm()[id]
-                            * ca
-                            * cb
-                            * cc
-                            * cd
-                            * integral;
+                            * d.norm()[id];
+
+                        let mut n = 0;
+                        for ca in a.coefs() {
+                            for cb in b.coefs() {
+                                let cab = ca[ia] * cb[ib];
+                                for cc in c.coefs() {
+                                    for cd in d.coefs() {
+                                        value[n] += cab * cc[ic] * cd[id] * integral;
+                                        n += 1;
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -189,16 +224,20 @@ impl IntegralInterface {
 
     fn cartesian_to_spherical_transformation_2e(
         &self,
-        l_a: &u8,
-        l_b: &u8,
-        l_c: &u8,
-        l_d: &u8,
+        a: &BasisShell,
+        b: &BasisShell,
+        c: &BasisShell,
+        d: &BasisShell,
         cartesian: FMatrixContainer,
     ) -> FMatrixContainer {
-        let ta = self.basis().trafo_matrix(l_a);
-        let tb = self.basis().trafo_matrix(l_b);
-        let tc = self.basis().trafo_matrix(l_c);
-        let td = self.basis().trafo_matrix(l_d);
+        if self.basis().cartesian() {
+            return cartesian;
+        }
+
+        let ta = self.basis().shell_trafo(a);
+        let tb = self.basis().shell_trafo(b);
+        let tc = self.basis().shell_trafo(c);
+        let td = self.basis().shell_trafo(d);
 
         let mut spherical = FMatrixContainer::new();
 
@@ -209,35 +248,35 @@ impl IntegralInterface {
 
         // resort (ab|cd) -> (cd|ab)
         let mut cartesian_resorted = FMatrixContainer::new();
-        for c in 0..cdim(l_c) {
-            for d in 0..cdim(l_d) {
-                let mut cartesian_cd = FMatrix::zero(cdim(l_a), cdim(l_b));
-                for a in 0..cdim(l_a) {
-                    for b in 0..cdim(l_b) {
-                        cartesian_cd[(a, b)] = cartesian[(a, b)][(c, d)];
+        for ic in 0..c.cdim() {
+            for id in 0..d.cdim() {
+                let mut cartesian_cd = FMatrix::zero(a.cdim(), b.cdim());
+                for ia in 0..a.cdim() {
+                    for ib in 0..b.cdim() {
+                        cartesian_cd[(ia, ib)] = cartesian[(ia, ib)][(ic, id)];
                     }
                 }
-                cartesian_resorted.insert((c, d), &cartesian_cd);
+                cartesian_resorted.insert((ic, id), &cartesian_cd);
             }
         }
 
         // half trafo (cd|ab) -> (cd|ij)
         let mut half = FMatrixContainer::new();
-        for c in 0..cdim(l_c) {
-            for d in 0..cdim(l_d) {
-                let half_cd = ta * &cartesian_resorted[(c, d)] * tb.transposed();
-                half.insert((c, d), &half_cd);
+        for ic in 0..c.cdim() {
+            for id in 0..d.cdim() {
+                let half_cd = &ta * &cartesian_resorted[(ic, id)] * tb.transposed();
+                half.insert((ic, id), &half_cd);
             }
         }
 
         // resort (cd|ij) -> (ij|cd)
         let mut half_resorted = FMatrixContainer::new();
-        for i in 0..dim(l_a) {
-            for j in 0..dim(l_b) {
-                let mut half_ij = FMatrix::zero(cdim(l_c), cdim(l_d));
-                for c in 0..cdim(l_c) {
-                    for d in 0..cdim(l_d) {
-                        half_ij[(c, d)] = half[(c, d)][(i, j)]
+        for i in 0..a.dim() {
+            for j in 0..b.dim() {
+                let mut half_ij = FMatrix::zero(c.cdim(), d.cdim());
+                for ic in 0..c.cdim() {
+                    for id in 0..d.cdim() {
+                        half_ij[(ic, id)] = half[(ic, id)][(i, j)]
                     }
                 }
                 half_resorted.insert((i, j), &half_ij);
@@ -245,9 +284,9 @@ impl IntegralInterface {
         }
 
         // second half trafo (ij|cd) -> (ij|kl)
-        for i in 0..dim(l_a) {
-            for j in 0..dim(l_b) {
-                let full_ij = tc * &half_resorted[(i, j)] * td.transposed();
+        for i in 0..a.dim() {
+            for j in 0..b.dim() {
+                let full_ij = &tc * &half_resorted[(i, j)] * td.transposed();
                 spherical.insert((i, j), &full_ij);
             }
         }
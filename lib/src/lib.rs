@@ -1,9 +1,20 @@
+// the Greek AO indices μ, ν, ρ, σ follow the equations and are no typos of the
+// single-letter Latin locals v, p, o elsewhere in the crate; rustc only honours this
+// lint at the crate level, not per file
+#![allow(confusable_idents)]
+
+pub mod correlation;
 pub mod data;
+pub mod dft;
+pub mod export;
 pub mod geometry;
+pub mod grid;
 pub mod gto_basis_sets;
 pub mod gto_integrals;
 pub mod linear_algebra;
 pub mod misc;
+pub mod response;
+pub mod scf;
 
 extern crate blas_src;
 extern crate cblas;
@@ -59,8 +70,30 @@ impl Display for HFType {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::HFType;
+    use crate::{
+        geometry::atom::Atom,
+        misc::elements::Element::{H, O},
+    };
+
+    /// Water at the geometry of T. D. Crawford's programming projects (bohr)
+    pub(crate) fn water() -> Vec<Atom> {
+        vec![
+            Atom::new(O, [0.0, -0.143225816552, 0.0]),
+            Atom::new(H, [1.638036840407, 1.136548822547, 0.0]),
+            Atom::new(H, [-1.638036840407, 1.136548822547, 0.0]),
+        ]
+    }
+
+    /// Water in the xz plane with the C2 axis along z (bohr)
+    pub(crate) fn water_xz() -> Vec<Atom> {
+        vec![
+            Atom::new(O, [0.0, 0.0, -0.2249058930]),
+            Atom::new(H, [1.4523499293, 0.0, 0.8996235720]),
+            Atom::new(H, [-1.4523499293, 0.0, 0.8996235720]),
+        ]
+    }
 
     #[test]
     fn serialize() {
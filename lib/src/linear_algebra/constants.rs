@@ -4,6 +4,9 @@
 pub const AU_EV: f64 = 27.2113834;
 pub const EV_KCAL: f64 = 23.0605;
 
+// temperature: Boltzmann constant in Hartree / K
+pub const K_B: f64 = 3.166811563e-6;
+
 // geometry
 pub const AU_ANG: f64 = 0.5291772083;
 pub const AU_BOHR: f64 = 1.0; // todo!()
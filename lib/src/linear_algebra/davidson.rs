@@ -0,0 +1,262 @@
+use crate::linear_algebra::{
+    diagonalize::DiagonalizeSym,
+    matrix::FMatrix,
+    traits::{Dot, Norm},
+    vector::FVector,
+};
+
+/// Number of roots and convergence settings of the Davidson solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct DavidsonOptions {
+    pub n_roots: usize,
+    pub max_iter: usize,
+    /// largest residual norm |A x - θ x|
+    pub threshold: f64,
+    /// subspace dimension before collapsing to the current Ritz vectors, by default
+    /// max(20 n_roots, 50)
+    pub max_subspace: Option<usize>,
+}
+
+impl Default for DavidsonOptions {
+    fn default() -> Self {
+        Self {
+            n_roots: 1,
+            max_iter: 100,
+            threshold: 1e-6,
+            max_subspace: None,
+        }
+    }
+}
+
+/// Lowest eigenpairs of a symmetric matrix given by its diagonal and a function computing
+/// the product with a vector, E. R. Davidson, J. Comput. Phys. 17, 87 (1975). The
+/// corrections are preconditioned with (θ - A_kk)^-1 and the unit vectors of the lowest
+/// diagonal elements serve as initial guess.
+pub fn davidson(
+    diagonal: &FVector,
+    sigma: impl Fn(&FVector) -> FVector,
+    options: &DavidsonOptions,
+) -> (FVector, FMatrix) {
+    let n = diagonal.len();
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|a, b| diagonal[*a].total_cmp(&diagonal[*b]));
+    let guess: Vec<FVector> = order
+        .iter()
+        .take((2 * options.n_roots).min(n))
+        .map(|k| {
+            let mut v = FVector::zero(n);
+            v[*k] = 1.0;
+            v
+        })
+        .collect();
+
+    let preconditioner = |residual: &FVector, theta: f64| -> FVector {
+        let mut correction = residual.clone();
+        for (x, d) in correction.iter_mut().zip(diagonal.iter()) {
+            let denominator = theta - d;
+            *x /= match denominator.abs() < 1e-8 {
+                true => 1e-8_f64.copysign(denominator),
+                false => denominator,
+            };
+        }
+        correction
+    };
+
+    davidson_preconditioned(guess, sigma, preconditioner, options)
+}
+
+/// Davidson solver with initial guess vectors and a preconditioner that turns the
+/// residual of a root with the Ritz value θ into a correction vector. Returns the
+/// eigenvalues in ascending order and the eigenvectors as columns once all residual
+/// norms are below the threshold (or after `max_iter` iterations).
+pub fn davidson_preconditioned(
+    guess: Vec<FVector>,
+    sigma: impl Fn(&FVector) -> FVector,
+    preconditioner: impl Fn(&FVector, f64) -> FVector,
+    options: &DavidsonOptions,
+) -> (FVector, FMatrix) {
+    let n = guess
+        .first()
+        .expect("Davidson requires a guess vector")
+        .len();
+    let n_roots = options.n_roots.min(n);
+    let max_dim = n.min(
+        options
+            .max_subspace
+            .unwrap_or((20 * n_roots).max(50))
+            .max(2 * n_roots),
+    );
+
+    let combine = |vectors: &[FVector], y: &FMatrix, root: usize| -> FVector {
+        let mut x = FVector::zero(n);
+        for (k, v) in vectors.iter().enumerate() {
+            x.axpy(&y[(k, root)], v);
+        }
+        x
+    };
+
+    let mut basis: Vec<FVector> = Vec::new();
+    let mut sigmas: Vec<FVector> = Vec::new();
+    let mut new = guess;
+    let mut values = FVector::zero(n_roots);
+    let mut vectors = FMatrix::zero(n, n_roots);
+    for iter in 0..options.max_iter {
+        // orthonormalize the new vectors against the subspace
+        for mut v in new.drain(..) {
+            for _ in 0..2 {
+                for b in &basis {
+                    let overlap = -b.dot(&v);
+                    v.axpy(&overlap, b);
+                }
+            }
+            let norm = v.norm();
+            if norm < 1e-8 {
+                continue;
+            }
+            v *= 1.0 / norm;
+            sigmas.push(sigma(&v));
+            basis.push(v);
+        }
+        assert!(
+            basis.len() >= n_roots,
+            "Davidson subspace is smaller than the number of roots"
+        );
+
+        // Rayleigh-Ritz in the subspace
+        let dim = basis.len();
+        let mut g = FMatrix::zero(dim, dim);
+        for i in 0..dim {
+            for j in 0..=i {
+                let x = basis[i].dot(&sigmas[j]);
+                g[(i, j)] = x;
+                g[(j, i)] = x;
+            }
+        }
+        let (theta, y) = g.diagonalize_sym();
+
+        // residuals r = (A - θ) x
+        let mut norm_max: f64 = 0.0;
+        let mut ritz = Vec::new();
+        for root in 0..n_roots {
+            let x = combine(&basis, &y, root);
+            let mut residual = combine(&sigmas, &y, root);
+            residual.axpy(&-theta[root], &x);
+            let norm = residual.norm();
+            norm_max = norm_max.max(norm);
+            if norm > options.threshold {
+                new.push(preconditioner(&residual, theta[root]));
+            }
+
+            values[root] = theta[root];
+            for (k, x) in x.iter().enumerate() {
+                vectors[(k, root)] = *x;
+            }
+            ritz.push(x);
+        }
+
+        if new.is_empty() {
+            println!("Davidson converged in {} iterations", iter + 1);
+            return (values, vectors);
+        }
+        if dim == n {
+            println!(
+                "Warning: Davidson subspace exhausted, residual norm {:.2e}",
+                norm_max
+            );
+            return (values, vectors);
+        }
+
+        // collapse to the current Ritz vectors if the subspace becomes too large
+        if dim + new.len() > max_dim {
+            sigmas = (0..n_roots)
+                .map(|root| combine(&sigmas, &y, root))
+                .collect();
+            basis = ritz;
+        }
+    }
+
+    println!(
+        "Warning: Davidson not converged within {} iterations",
+        options.max_iter
+    );
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{davidson, davidson_preconditioned, DavidsonOptions};
+    use crate::linear_algebra::{
+        diagonalize::DiagonalizeSym, matrix::FMatrix, power::PowerSym, vector::FVector,
+    };
+
+    /// Diagonally dominant symmetric matrix
+    fn matrix(n: usize) -> FMatrix {
+        let mut a = FMatrix::zero(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                a[(i, j)] = match i == j {
+                    true => 1.0 + i as f64,
+                    false => 0.05 / (1.0 + (i as f64 - j as f64).abs()),
+                };
+            }
+        }
+        a
+    }
+
+    fn product(a: &FMatrix, x: &FVector) -> FVector {
+        let mut ax = FVector::zero(a.rows);
+        ax.dgemv(false, 1.0, a, x, 0.0);
+        ax
+    }
+
+    #[test]
+    fn lowest_roots() {
+        let n = 60;
+        let a = matrix(n);
+        let diagonal = FVector::new_from_vec(&a.diagonal().copied().collect::<Vec<f64>>());
+        let options = DavidsonOptions {
+            n_roots: 3,
+            threshold: 1e-8,
+            max_subspace: Some(10),
+            ..Default::default()
+        };
+
+        let (values, vectors) = davidson(&diagonal, |x| product(&a, x), &options);
+        let (reference, _) = a.diagonalize_sym();
+        for root in 0..3 {
+            assert!((values[root] - reference[root]).abs() < 1e-10);
+
+            // A x = λ x
+            let x = FVector::new_from_vec(&vectors.col(root).copied().collect::<Vec<f64>>());
+            let ax = product(&a, &x);
+            for i in 0..n {
+                assert!((ax[i] - values[root] * x[i]).abs() < 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn preconditioner() {
+        // (A - θ)^-1 r from the exact inverse of A converges in a few iterations
+        let n = 40;
+        let a = matrix(n);
+        let inverse = a.powf_sym(-1.0);
+        let mut guess = FVector::zero(n);
+        guess[0] = 1.0;
+        let options = DavidsonOptions {
+            threshold: 1e-10,
+            max_iter: 8,
+            ..Default::default()
+        };
+
+        let (values, _) = davidson_preconditioned(
+            vec![guess],
+            |x| product(&a, x),
+            |r, _| product(&inverse, r),
+            &options,
+        );
+        let (reference, _) = a.diagonalize_sym();
+        assert!((values[0] - reference[0]).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix};
+
+/// Direct inversion in the iterative subspace for any set of parameters stored as a flat
+/// vector (e.g. coupled-cluster amplitudes or Fock matrices), P. Pulay, Chem. Phys. Lett.
+/// 73, 393 (1980). The last `dim_max` parameter and error vectors are kept and the
+/// extrapolated parameters are the linear combination which minimizes the norm of the
+/// combined error vectors.
+pub struct DIIS {
+    dim_max: usize,
+
+    // history, oldest first
+    parameters: Vec<Vec<f64>>,
+    error: Vec<Vec<f64>>,
+}
+
+/// Getters
+impl DIIS {
+    /// Number of stored vectors
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+
+    /// Stored parameters, oldest first
+    pub fn parameters(&self) -> &[Vec<f64>] {
+        &self.parameters
+    }
+}
+
+impl DIIS {
+    pub fn new(dim_max: usize) -> Self {
+        Self {
+            dim_max,
+            parameters: Vec::new(),
+            error: Vec::new(),
+        }
+    }
+
+    /// Store a parameter vector and its error vector, discarding the oldest ones beyond
+    /// `dim_max`
+    pub fn push(&mut self, parameters: &[f64], error: &[f64]) {
+        if self.dim_max == 0 {
+            return;
+        }
+        if self.len() == self.dim_max {
+            self.parameters.remove(0);
+            self.error.remove(0);
+        }
+        self.parameters.push(parameters.to_vec());
+        self.error.push(error.to_vec());
+    }
+
+    /// Coefficients minimizing |\sum_i c_i e_i| with \sum_i c_i = 1. The oldest vectors
+    /// are discarded while the B matrix is singular.
+    pub fn coefficients(&self) -> Vec<f64> {
+        let n = self.len();
+        let mut b = FMatrix::zero(n.max(1), n.max(1));
+        for i in 0..n {
+            for j in 0..=i {
+                let bij: f64 = self.error[i]
+                    .iter()
+                    .zip(self.error[j].iter())
+                    .map(|(x, y)| x * y)
+                    .sum();
+                b[(i, j)] = bij;
+                b[(j, i)] = bij;
+            }
+        }
+
+        let mut coefficients = vec![0.0; n];
+        for start in 0..n {
+            let dim = n - start;
+            // scaling B leaves the coefficients unchanged
+            let scale = (start..n).map(|i| b[(i, i)]).fold(0.0, f64::max);
+            if scale < 1e-30 {
+                break;
+            }
+            let mut a = FMatrix::new_with_value(dim + 1, dim + 1, -1.0);
+            a[(dim, dim)] = 0.0;
+            for i in 0..dim {
+                for j in 0..dim {
+                    a[(i, j)] = b[(start + i, start + j)] / scale;
+                }
+            }
+            let mut rhs = vec![0.0; dim + 1];
+            rhs[dim] = -1.0;
+            if let Some(x) = solve_symmetric(&a, &rhs) {
+                coefficients[start..].copy_from_slice(&x[..dim]);
+                return coefficients;
+            }
+        }
+
+        // nothing to extrapolate, keep the last parameters
+        if let Some(last) = coefficients.last_mut() {
+            *last = 1.0;
+        }
+        coefficients
+    }
+
+    /// Linear combination p' = \sum_i c_i p_i of the stored parameters
+    pub fn combine(&self, coefficients: &[f64], parameters: &mut [f64]) {
+        parameters.fill(0.0);
+        for (c, p) in coefficients.iter().zip(&self.parameters) {
+            for (x, y) in parameters.iter_mut().zip(p) {
+                *x += c * y;
+            }
+        }
+    }
+
+    /// Store a parameter vector and its error vector and replace the parameters by the
+    /// extrapolation over the subspace
+    pub fn extrapolate(&mut self, parameters: &mut [f64], error: &[f64]) {
+        self.push(parameters, error);
+
+        // extrapolation needs at least two vectors
+        if self.len() < 2 {
+            return;
+        }
+        let coefficients = self.coefficients();
+        self.combine(&coefficients, parameters);
+    }
+}
+
+/// Solution of the symmetric system A x = b, None if A is (nearly) singular
+pub fn solve_symmetric(a: &FMatrix, b: &[f64]) -> Option<Vec<f64>> {
+    let (eigenvalues, u) = a.diagonalize_sym();
+    let largest = eigenvalues.iter().fold(0.0, |max: f64, e| max.max(e.abs()));
+    if eigenvalues.iter().any(|e| e.abs() < 1e-12 * largest) {
+        return None;
+    }
+
+    let n = b.len();
+    let mut x = vec![0.0; n];
+    for (k, e) in eigenvalues.iter().enumerate() {
+        let ub: f64 = (0..n).map(|i| u[(i, k)] * b[i]).sum();
+        for (i, x) in x.iter_mut().enumerate() {
+            *x += u[(i, k)] * ub / e;
+        }
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DIIS;
+
+    #[test]
+    fn linear_iteration() {
+        // fixed point x = A x + b of a slowly converging linear iteration, solution (1, 2)
+        let a = [[0.9, 0.05], [0.05, 0.8]];
+        let b = [1.0 - 0.9 - 0.1, 2.0 - 0.05 - 1.6];
+        let step = |x: &[f64]| -> Vec<f64> {
+            (0..2)
+                .map(|i| a[i][0] * x[0] + a[i][1] * x[1] + b[i])
+                .collect()
+        };
+
+        let mut diis = DIIS::new(4);
+        let mut x = vec![0.0, 0.0];
+        for _ in 0..4 {
+            let mut x_new = step(&x);
+            let error: Vec<f64> = x_new.iter().zip(x.iter()).map(|(n, o)| n - o).collect();
+            diis.extrapolate(&mut x_new, &error);
+            x = x_new;
+        }
+
+        // exact after three vectors, plain iterations would still be far off
+        assert!((x[0] - 1.0).abs() < 1e-8, "{:?}", x);
+        assert!((x[1] - 2.0).abs() < 1e-8, "{:?}", x);
+    }
+
+    #[test]
+    fn singular_history() {
+        // identical error vectors make B singular, the older copy is discarded
+        let mut diis = DIIS::new(3);
+        diis.push(&[1.0], &[0.5, 0.5]);
+        diis.push(&[2.0], &[0.5, 0.5]);
+        assert_eq!(diis.coefficients(), vec![0.0, 1.0]);
+
+        // vanishing errors keep the last parameters
+        let mut diis = DIIS::new(3);
+        diis.push(&[1.0], &[0.0]);
+        diis.push(&[2.0], &[0.0]);
+        let mut p = [0.0];
+        diis.combine(&diis.coefficients(), &mut p);
+        assert_eq!(p, [2.0]);
+
+        // only the last dim_max vectors are kept
+        let mut diis = DIIS::new(2);
+        for i in 0..3 {
+            diis.push(&[i as f64], &[1.0]);
+        }
+        assert_eq!(diis.parameters(), &[vec![1.0], vec![2.0]]);
+    }
+}
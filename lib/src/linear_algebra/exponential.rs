@@ -0,0 +1,37 @@
+use crate::linear_algebra::{matrix::FMatrix, power::PowerSym};
+
+/// exp(κ) of an antisymmetric matrix, e.g. of orbital rotation parameters, from its
+/// Taylor series, orthonormalized by U (U^T U)^-1/2
+pub fn exp_antisymmetric(kappa: &FMatrix) -> FMatrix {
+    let n = kappa.rows;
+    let mut u = FMatrix::zero(n, n);
+    let mut term = FMatrix::zero(n, n);
+    for p in 0..n {
+        u[(p, p)] = 1.0;
+        term[(p, p)] = 1.0;
+    }
+    for k in 1..=12 {
+        term = &term * kappa * (1.0 / k as f64);
+        u += &term;
+    }
+    let overlap = &u.transposed() * &u;
+    &u * &overlap.powf_sym(-0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::exp_antisymmetric;
+    use crate::linear_algebra::matrix::FMatrix;
+
+    #[test]
+    fn rotation() {
+        // exp([[0, -θ], [θ, 0]]) is the rotation by θ
+        let theta: f64 = 0.7;
+        let kappa = FMatrix::new_from_vec(2, 2, &[0.0, -theta, theta, 0.0]);
+        let u = exp_antisymmetric(&kappa);
+        let reference = [theta.cos(), -theta.sin(), theta.sin(), theta.cos()];
+        for (u, reference) in u.iter().zip(reference) {
+            assert!((u - reference).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::linear_algebra::{
+    diagonalize::DiagonalizeSym,
+    matrix::FMatrix,
+    traits::{Dot, Norm},
+    vector::FVector,
+};
+
+/// Number of roots and convergence settings of the Lanczos solver
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanczosOptions {
+    pub n_roots: usize,
+    /// largest dimension of the Krylov subspace
+    pub max_iter: usize,
+    /// largest residual norm |A x - θ x|
+    pub threshold: f64,
+}
+
+impl Default for LanczosOptions {
+    fn default() -> Self {
+        Self {
+            n_roots: 1,
+            max_iter: 200,
+            threshold: 1e-6,
+        }
+    }
+}
+
+/// Lowest eigenpairs of a symmetric matrix from the Krylov subspace of a start vector,
+/// C. Lanczos, J. Res. Natl. Bur. Stand. 45, 255 (1950). The Lanczos vectors are fully
+/// reorthogonalized, so no spurious copies of converged roots appear. Eigenvalues with
+/// eigenvectors orthogonal to the start vector (e.g. of other symmetry) are not found and
+/// degenerate roots only once. Returns the eigenvalues in ascending order and the
+/// eigenvectors as columns, no roots without iterations (max_iter = 0 or n = 0).
+pub fn lanczos(
+    start: &FVector,
+    sigma: impl Fn(&FVector) -> FVector,
+    options: &LanczosOptions,
+) -> (FVector, FMatrix) {
+    let n = start.len();
+
+    let mut q = start.clone();
+    q *= 1.0 / q.norm();
+    let mut krylov: Vec<FVector> = vec![q];
+    let mut alpha: Vec<f64> = Vec::new();
+    let mut beta: Vec<f64> = Vec::new();
+
+    for iter in 0..options.max_iter.min(n) {
+        // w = A q_j - α_j q_j - β_j-1 q_j-1, orthogonalized against all Lanczos vectors
+        let mut w = sigma(&krylov[iter]);
+        alpha.push(krylov[iter].dot(&w));
+        for _ in 0..2 {
+            for v in &krylov {
+                let overlap = -v.dot(&w);
+                w.axpy(&overlap, v);
+            }
+        }
+        let b = w.norm();
+
+        // tridiagonal matrix T = Q^T A Q, the residual of a Ritz pair is β_j |s_jk|
+        let dim = alpha.len();
+        let mut t = FMatrix::zero(dim, dim);
+        for k in 0..dim {
+            t[(k, k)] = alpha[k];
+            if k + 1 < dim {
+                t[(k, k + 1)] = beta[k];
+                t[(k + 1, k)] = beta[k];
+            }
+        }
+        let (theta, s) = t.diagonalize_sym();
+
+        let n_roots = options.n_roots.min(dim);
+        let norm_max = (0..n_roots)
+            .map(|root| (b * s[(dim - 1, root)]).abs())
+            .fold(0.0, f64::max);
+        let invariant = b < 1e-12 || dim == n;
+        let last = iter + 1 == options.max_iter.min(n);
+        if (n_roots == options.n_roots && norm_max < options.threshold) || invariant || last {
+            match (invariant, norm_max < options.threshold, last) {
+                (false, false, true) => println!(
+                    "Warning: Lanczos not converged within {} iterations, residual norm {:.2e}",
+                    options.max_iter, norm_max
+                ),
+                _ => println!("Lanczos converged in {} iterations", iter + 1),
+            }
+
+            let mut values = FVector::zero(n_roots);
+            let mut vectors = FMatrix::zero(n, n_roots);
+            for root in 0..n_roots {
+                values[root] = theta[root];
+                for (k, v) in krylov.iter().enumerate() {
+                    for i in 0..n {
+                        vectors[(i, root)] += s[(k, root)] * v[i];
+                    }
+                }
+            }
+            return (values, vectors);
+        }
+
+        beta.push(b);
+        w *= 1.0 / b;
+        krylov.push(w);
+    }
+
+    (FVector::new_from_vec(&[]), FMatrix::new_from_vec(n, 0, &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lanczos, LanczosOptions};
+    use crate::linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix, vector::FVector};
+
+    #[test]
+    fn lowest_roots() {
+        // tridiagonal matrix of a particle in a box with a linear potential
+        let n = 200;
+        let mut a = FMatrix::zero(n, n);
+        for i in 0..n {
+            a[(i, i)] = 2.0 + 0.01 * i as f64;
+            if i + 1 < n {
+                a[(i, i + 1)] = -1.0;
+                a[(i + 1, i)] = -1.0;
+            }
+        }
+        let sigma = |x: &FVector| -> FVector {
+            let mut ax = FVector::zero(n);
+            ax.dgemv(false, 1.0, &a, x, 0.0);
+            ax
+        };
+        let start = FVector::new_with_value(n, 1.0);
+        let options = LanczosOptions {
+            n_roots: 3,
+            threshold: 1e-8,
+            ..Default::default()
+        };
+
+        let (values, vectors) = lanczos(&start, sigma, &options);
+        let (reference, _) = a.diagonalize_sym();
+        for root in 0..3 {
+            assert!((values[root] - reference[root]).abs() < 1e-10);
+
+            // A x = λ x with |x| = 1
+            let x = FVector::new_from_vec(&vectors.col(root).copied().collect::<Vec<f64>>());
+            let ax = sigma(&x);
+            let norm: f64 = x.iter().map(|x| x * x).sum();
+            assert!((norm - 1.0).abs() < 1e-10);
+            for i in 0..n {
+                assert!((ax[i] - values[root] * x[i]).abs() < 1e-7);
+            }
+        }
+
+        let options = LanczosOptions {
+            max_iter: 0,
+            ..Default::default()
+        };
+        let (values, vectors) = lanczos(&start, sigma, &options);
+        assert_eq!((values.len(), vectors.rows, vectors.cols), (0, n, 0));
+    }
+}
@@ -3,11 +3,16 @@ pub mod blas_level_1;
 pub mod blas_level_2;
 pub mod blas_level_3;
 pub mod constants;
+pub mod davidson;
 pub mod diagonalize;
+pub mod diis;
+pub mod exponential;
 pub mod functions;
+pub mod lanczos;
 pub mod linear_solve;
 pub mod matrix;
 pub mod matrix_container;
+pub mod orthogonalization;
 pub mod power;
 mod scalar;
 pub mod traits;
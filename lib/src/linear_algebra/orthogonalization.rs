@@ -0,0 +1,69 @@
+use crate::linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix, power::PowerSym};
+
+/// Orthogonalization X^T S X = 1 of a (nearly) linearly dependent basis. Symmetric
+/// orthogonalization X = S^-1/2 if all eigenvalues of S reach the threshold, canonical
+/// orthogonalization X = U λ^-1/2 over the eigenvectors above it otherwise, such that X
+/// has fewer columns than rows. Returns X and the eigenvalues of the removed functions.
+pub fn orthogonalizer(s: &FMatrix, threshold: f64) -> (FMatrix, Vec<f64>) {
+    let (eigenvalues, u) = s.diagonalize_sym();
+    let kept: Vec<usize> = (0..eigenvalues.len())
+        .filter(|&k| eigenvalues[k] >= threshold)
+        .collect();
+    if kept.len() == eigenvalues.len() {
+        // symmetric to machine precision, such that X^T F X stays symmetric
+        let x = s.powf_sym(-0.5);
+        return ((&x + &x.transposed()) * 0.5, Vec::new());
+    }
+
+    let removed = (0..eigenvalues.len())
+        .filter(|&k| eigenvalues[k] < threshold)
+        .map(|k| eigenvalues[k])
+        .collect();
+    let mut x = FMatrix::zero(s.rows, kept.len());
+    for (j, &k) in kept.iter().enumerate() {
+        let norm = 1.0 / eigenvalues[k].sqrt();
+        for mu in 0..s.rows {
+            x[(mu, j)] = u[(mu, k)] * norm;
+        }
+    }
+    (x, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::orthogonalizer;
+    use crate::linear_algebra::{matrix::FMatrix, power::PowerSym};
+
+    fn assert_orthonormal(x: &FMatrix, s: &FMatrix) {
+        let metric = x.transposed() * s * x;
+        for i in 0..metric.rows {
+            for j in 0..metric.cols {
+                let reference = if i == j { 1.0 } else { 0.0 };
+                assert!((metric[(i, j)] - reference).abs() < 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric() {
+        let s = FMatrix::new_from_vec(2, 2, &[1.0, 0.4, 0.4, 1.0]);
+        let (x, removed) = orthogonalizer(&s, 1e-7);
+        assert!(removed.is_empty());
+        for (x, reference) in x.iter().zip(s.powf_sym(-0.5).iter()) {
+            assert!((x - reference).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn canonical() {
+        // the third function is almost the normalized sum of the first two
+        let a: f64 = 0.2;
+        let b = (1.0 - 1e-9) * (1.0 + a) / (2.0 * (1.0 + a)).sqrt();
+        let s = FMatrix::new_from_vec(3, 3, &[1.0, a, b, a, 1.0, b, b, b, 1.0]);
+        let (x, removed) = orthogonalizer(&s, 1e-7);
+        assert_eq!(removed.len(), 1);
+        assert!(removed[0] < 1e-7);
+        assert_eq!((x.rows, x.cols), (3, 2));
+        assert_orthonormal(&x, &s);
+    }
+}
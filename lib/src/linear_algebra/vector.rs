@@ -2,10 +2,12 @@ use crate::linear_algebra::scalar::Scalar;
 use std::fmt::{Display, Formatter};
 use std::ops::{Deref, DerefMut, Index, IndexMut};
 
+use serde::{Deserialize, Serialize};
+
 pub type FVector = Vector<f64>;
 pub type IVector = Vector<i64>;
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
 pub struct Vector<T: Scalar> {
     pub n: usize,
     data: Vec<T>,
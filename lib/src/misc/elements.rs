@@ -388,16 +388,65 @@ impl Element {
         }
     }
 
-    // fn core_electrons(&self) -> u8 {
-    //     //     1:2    => 0,
-    //     //     3:10   => 2,
-    //     //     11:18  => 10,
-    //     //     19:36  => 18,
-    //     //     37:54  => 36,
-    //     //     55:86  => 54,
-    //     //     87:118 => 86
-    //     0
-    // }
+    /// Bragg-Slater radius in Ångström (J. C. Slater, J. Chem. Phys. 41, 3199 (1964)),
+    /// noble gases as used in common DFT codes
+    pub fn bragg_radius(&self) -> f64 {
+        match self {
+            Element::H => 0.35,
+            Element::He => 1.40,
+            Element::Li => 1.45,
+            Element::Be => 1.05,
+            Element::B => 0.85,
+            Element::C => 0.70,
+            Element::N => 0.65,
+            Element::O => 0.60,
+            Element::F => 0.50,
+            Element::Ne => 1.50,
+            Element::Na => 1.80,
+            Element::Mg => 1.50,
+            Element::Al => 1.25,
+            Element::Si => 1.10,
+            Element::P => 1.00,
+            Element::S => 1.00,
+            Element::Cl => 1.00,
+            Element::Ar => 1.80,
+            Element::K => 2.20,
+            Element::Ca => 1.80,
+            Element::Sc => 1.60,
+            Element::Ti => 1.40,
+            Element::V => 1.35,
+            Element::Cr => 1.40,
+            Element::Mn => 1.40,
+            Element::Fe => 1.40,
+            Element::Co => 1.35,
+            Element::Ni => 1.35,
+            Element::Cu => 1.35,
+            Element::Zn => 1.35,
+            Element::Ga => 1.30,
+            Element::Ge => 1.25,
+            Element::As => 1.15,
+            Element::Se => 1.15,
+            Element::Br => 1.15,
+            Element::Kr => 1.90,
+            _ => panic!(
+                "No Bragg-Slater radius for {}, integration grids are available up to Kr",
+                self.name()
+            ),
+        }
+    }
+
+    /// Electrons in the closed shells of the preceding noble gas
+    pub fn core_electrons(&self) -> u8 {
+        match self.atomic_number() {
+            1..=2 => 0,
+            3..=10 => 2,
+            11..=18 => 10,
+            19..=36 => 18,
+            37..=54 => 36,
+            55..=86 => 54,
+            _ => 86,
+        }
+    }
 }
 
 impl Display for Element {
@@ -681,6 +730,12 @@ mod tests {
         assert_eq!(Element::Sn.to_string(), "Sn");
     }
 
+    #[test]
+    #[should_panic(expected = "No Bragg-Slater radius for Xenon")]
+    fn bragg_radius_unknown() {
+        Element::Xe.bragg_radius();
+    }
+
     #[test]
     fn from_str() {
         use std::str::FromStr;
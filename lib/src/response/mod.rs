@@ -0,0 +1,4 @@
+//! Linear response of converged SCF wave functions
+
+pub mod stability;
+pub mod tdhf;
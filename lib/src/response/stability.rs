@@ -0,0 +1,256 @@
+use crate::{
+    data::orbitals::Orbitals,
+    linear_algebra::{
+        davidson::{davidson, DavidsonOptions},
+        exponential::exp_antisymmetric,
+        matrix::FMatrix,
+        matrix_container::FMatrixContainer,
+        traits::Dot,
+        vector::FVector,
+    },
+    response::tdhf::{Sigma, Spin, TDHF},
+    scf::fock::fock,
+};
+
+use std::{
+    f64::consts::PI,
+    fmt::{Display, Formatter},
+};
+
+/// Lowest eigenvalue of the stability matrix below which a wave function is unstable
+pub const INSTABILITY_THRESHOLD: f64 = -1e-5;
+
+/// Class of real orbital rotations tested for instabilities
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StabilityType {
+    /// internal instability of a restricted wave function (singlet rotations)
+    RHFToRHF,
+    /// external instability towards broken spin symmetry (triplet rotations)
+    RHFToUHF,
+    /// internal instability of an unrestricted wave function
+    UHFToUHF,
+}
+
+impl Display for StabilityType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StabilityType::RHFToRHF => write!(f, "RHF -> RHF"),
+            StabilityType::RHFToUHF => write!(f, "RHF -> UHF"),
+            StabilityType::UHFToUHF => write!(f, "UHF -> UHF"),
+        }
+    }
+}
+
+/// Number of roots and convergence settings of the eigensolver
+#[derive(Clone, Debug, PartialEq)]
+pub struct StabilityOptions {
+    pub n_roots: usize,
+    pub max_iter: usize,
+    pub threshold: f64,
+}
+
+impl Default for StabilityOptions {
+    fn default() -> Self {
+        Self {
+            n_roots: 3,
+            max_iter: 100,
+            threshold: 1e-6,
+        }
+    }
+}
+
+/// Eigenpair of the stability matrix A + B, the orbital Hessian of real rotations
+#[derive(Clone, Debug, PartialEq)]
+pub struct StabilityRoot {
+    pub kind: StabilityType,
+    pub eigenvalue: f64,
+    /// rotation parameters of all occupied-virtual pairs, normalized
+    pub vector: Vec<f64>,
+}
+
+impl StabilityRoot {
+    pub fn unstable(&self) -> bool {
+        self.eigenvalue < INSTABILITY_THRESHOLD
+    }
+}
+
+/// Lowest eigenvalues of the stability matrices of a converged SCF wave function,
+/// R. Seeger and J. A. Pople, J. Chem. Phys. 66, 3045 (1977). Restricted references are
+/// tested for RHF -> RHF and RHF -> UHF, unrestricted ones for UHF -> UHF instabilities.
+pub fn stability(
+    orbitals: &Orbitals,
+    eri: &FMatrixContainer,
+    options: &StabilityOptions,
+) -> Vec<StabilityRoot> {
+    let tdhf = TDHF::new(orbitals, eri);
+    let kinds = match orbitals.restricted() {
+        true => vec![
+            (StabilityType::RHFToRHF, Spin::Singlet),
+            (StabilityType::RHFToUHF, Spin::Triplet),
+        ],
+        false => vec![(StabilityType::UHFToUHF, Spin::Singlet)],
+    };
+    let davidson_options = DavidsonOptions {
+        n_roots: options.n_roots,
+        max_iter: options.max_iter,
+        threshold: options.threshold,
+        ..Default::default()
+    };
+
+    let mut roots = Vec::new();
+    for (kind, spin) in kinds {
+        let (eigenvalues, vectors) = davidson(
+            &FVector::new_from_vec(&tdhf.delta),
+            |v| FVector::new_from_vec(&tdhf.sigma(v, Sigma::APlusB, spin)),
+            &davidson_options,
+        );
+        for (root, eigenvalue) in eigenvalues.iter().enumerate() {
+            roots.push(StabilityRoot {
+                kind,
+                eigenvalue: *eigenvalue,
+                vector: vectors.col(root).copied().collect(),
+            });
+        }
+    }
+    roots
+}
+
+/// Orbitals rotated along an unstable mode, C' = C exp(θ κ) with the rotation angle θ
+/// of lowest energy on a grid up to π/4. RHF -> UHF modes rotate alpha and beta
+/// orbitals in opposite directions and return unrestricted orbitals. Returns the new
+/// orbitals and their electronic energy.
+pub fn follow(
+    orbitals: &Orbitals,
+    root: &StabilityRoot,
+    h: &FMatrix,
+    eri: &FMatrixContainer,
+) -> (Orbitals, f64) {
+    let n_op = match root.kind {
+        StabilityType::RHFToRHF => 1,
+        StabilityType::RHFToUHF | StabilityType::UHFToUHF => 2,
+    };
+    let homo: Vec<usize> = (0..orbitals.num_op()).map(|op| orbitals.homo(op)).collect();
+
+    // rotation generators of every spin, the triplet mode with opposite signs
+    let n_mo = orbitals.c(0).cols;
+    let mut kappa = vec![FMatrix::zero(n_mo, n_mo); n_op];
+    let mut index = 0;
+    for (op, &n_occ) in homo.iter().enumerate() {
+        for i in 0..n_occ {
+            for a in n_occ..n_mo {
+                let x = root.vector[index];
+                kappa[op][(a, i)] = x;
+                kappa[op][(i, a)] = -x;
+                if root.kind == StabilityType::RHFToUHF {
+                    kappa[1][(a, i)] = -x;
+                    kappa[1][(i, a)] = x;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    let rotated = |theta: f64| -> Orbitals {
+        let c: Vec<FMatrix> = (0..n_op)
+            .map(|op| {
+                let c = orbitals.c(op.min(orbitals.num_op() - 1));
+                c * &exp_antisymmetric(&(&kappa[op] * theta))
+            })
+            .collect();
+        match root.kind {
+            StabilityType::RHFToRHF => {
+                Orbitals::new_restricted(c[0].clone(), orbitals.e(0).clone(), 2 * homo[0])
+            }
+            StabilityType::RHFToUHF => Orbitals::new_unrestricted(
+                [c[0].clone(), c[1].clone()],
+                [orbitals.e(0).clone(), orbitals.e(0).clone()],
+                [homo[0], homo[0]],
+            ),
+            StabilityType::UHFToUHF => Orbitals::new_unrestricted(
+                [c[0].clone(), c[1].clone()],
+                [orbitals.e(0).clone(), orbitals.e(1).clone()],
+                [homo[0], homo[1]],
+            ),
+        }
+    };
+
+    let e_initial = energy(orbitals, h, eri);
+    let (theta, e_rotated) = (1..=8)
+        .map(|k| k as f64 * PI / 32.0)
+        .map(|theta| (theta, energy(&rotated(theta), h, eri)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    println!(
+        "Following {} instability: θ = {:5.3}, ΔE = {:.9}",
+        root.kind,
+        theta,
+        e_rotated - e_initial
+    );
+
+    (rotated(theta), e_rotated)
+}
+
+/// Electronic energy of a single determinant, E = 1/2 \sum_σ Dσ (H + Fσ)
+fn energy(orbitals: &Orbitals, h: &FMatrix, eri: &FMatrixContainer) -> f64 {
+    match orbitals.restricted() {
+        true => {
+            let d = orbitals.density(0);
+            0.5 * d.dot(&(h + fock(&d, h, eri, 1.0, 0.5)))
+        }
+        false => {
+            let d = [orbitals.density(0), orbitals.density(1)];
+            let j = fock(&(&d[0] + &d[1]), h, eri, 1.0, 0.0);
+            d.iter()
+                .map(|d| 0.5 * d.dot(&(h + fock(d, &j, eri, 0.0, 1.0))))
+                .sum()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{follow, stability, StabilityOptions, StabilityType};
+    use crate::{
+        correlation::tests::rhf, data::orbitals::Orbitals, geometry::atom::Atom,
+        gto_basis_sets::BasisSet, gto_integrals::nuclear_repulsion::nuclear_repulsion,
+        misc::elements::Element::H, tests::water,
+    };
+
+    #[test]
+    fn water_stable() {
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let roots = stability(&rhf.orbitals, &rhf.eri, &StabilityOptions::default());
+        assert_eq!(roots.len(), 6);
+        assert!(roots.iter().all(|root| !root.unstable()));
+    }
+
+    #[test]
+    fn stretched_h2() {
+        // the RHF solution of H2 at 3 bohr is unstable towards UHF
+        let atoms = vec![Atom::new(H, [0.0, 0.0, 0.0]), Atom::new(H, [0.0, 0.0, 3.0])];
+        let rhf = rhf(&atoms, &BasisSet::sto_3g);
+        let options = StabilityOptions {
+            n_roots: 1,
+            threshold: 1e-8,
+            ..Default::default()
+        };
+        let roots = stability(&rhf.orbitals, &rhf.eri, &options);
+        let singlet = &roots[0];
+        let triplet = &roots[1];
+        assert_eq!(triplet.kind, StabilityType::RHFToUHF);
+        assert!(!singlet.unstable());
+        assert!(triplet.unstable());
+
+        // the same mode appears among the UHF -> UHF rotations of the RHF orbitals
+        let (c, e) = (rhf.orbitals.c(0), rhf.orbitals.e(0));
+        let uhf =
+            Orbitals::new_unrestricted([c.clone(), c.clone()], [e.clone(), e.clone()], [1, 1]);
+        let roots = stability(&uhf, &rhf.eri, &options);
+        assert!((roots[0].eigenvalue - triplet.eigenvalue).abs() < 1e-8);
+
+        // following the mode breaks the spin symmetry and lowers the energy
+        let (orbitals, e) = follow(&rhf.orbitals, triplet, &rhf.h, &rhf.eri);
+        assert!(!orbitals.restricted());
+        assert!(e + nuclear_repulsion(&atoms) < rhf.e_scf - 1e-3);
+    }
+}
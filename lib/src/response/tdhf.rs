@@ -0,0 +1,634 @@
+use crate::{
+    data::orbitals::Orbitals,
+    gto_integrals::mo_transform::orbital_space,
+    linear_algebra::{
+        davidson::{davidson, DavidsonOptions},
+        diagonalize::DiagonalizeSym,
+        matrix::FMatrix,
+        matrix_container::FMatrixContainer,
+        power::PowerSym,
+        vector::FVector,
+    },
+    scf::fock::fock,
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// Approximation to the linear response equations
+/// [[A, B], [B, A]] (X, Y) = ω [[1, 0], [0, -1]] (X, Y)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResponseType {
+    /// Tamm-Dancoff approximation B = 0, identical to CIS for Hartree-Fock references
+    #[default]
+    TDA,
+    /// full time-dependent Hartree-Fock, the random phase approximation
+    RPA,
+}
+
+impl FromStr for ResponseType {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let response = match s.to_uppercase().as_str() {
+            "TDA" | "CIS" => ResponseType::TDA,
+            "RPA" | "TDHF" => ResponseType::RPA,
+            _ => return Err("Unknown response type"),
+        };
+
+        Ok(response)
+    }
+}
+
+impl Display for ResponseType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResponseType::TDA => write!(f, "TDA"),
+            ResponseType::RPA => write!(f, "RPA"),
+        }
+    }
+}
+
+/// Spin of the excited states of a restricted reference
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Spin {
+    Singlet,
+    Triplet,
+}
+
+impl Display for Spin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Spin::Singlet => write!(f, "Singlet"),
+            Spin::Triplet => write!(f, "Triplet"),
+        }
+    }
+}
+
+/// Number of states and convergence settings of the eigensolver
+#[derive(Clone, Debug, PartialEq)]
+pub struct TDHFOptions {
+    pub n_states: usize,
+    pub max_iter: usize,
+    pub threshold: f64,
+}
+
+impl Default for TDHFOptions {
+    fn default() -> Self {
+        Self {
+            n_states: 5,
+            max_iter: 100,
+            threshold: 1e-6,
+        }
+    }
+}
+
+/// Excited state from the response equations
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExcitedState {
+    /// excitation energy ω
+    pub energy: f64,
+    /// spin of a restricted reference, None for unrestricted ones
+    pub spin: Option<Spin>,
+    /// excitation and de-excitation amplitudes of all occupied-virtual pairs, normalized
+    /// to X X - Y Y = 1
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    /// <0|r|n> about the origin
+    pub transition_dipole: [f64; 3],
+    /// 2/3 ω |<0|r|n>|^2
+    pub oscillator_strength: f64,
+}
+
+/// Products of the response matrices with a trial vector
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Sigma {
+    A,
+    APlusB,
+    AMinusB,
+}
+
+/// Excitation energies of RHF and UHF references. The products of the response matrices
+/// with trial vectors are built directly in the AO basis from Coulomb and exchange
+/// matrices of the transition densities Dμν = \sum_ia Cμi X_ia Cνa.
+pub struct TDHF<'a> {
+    eri: &'a FMatrixContainer,
+    restricted: bool,
+
+    // occupied and virtual orbitals of every spin
+    c_occ: Vec<FMatrix>,
+    c_virt: Vec<FMatrix>,
+
+    // ε_a - ε_i of all occupied-virtual pairs, alpha before beta
+    pub(crate) delta: Vec<f64>,
+}
+
+impl<'a> TDHF<'a> {
+    pub fn new(orbitals: &Orbitals, eri: &'a FMatrixContainer) -> Self {
+        let mut c_occ = Vec::new();
+        let mut c_virt = Vec::new();
+        let mut delta = Vec::new();
+        for op in 0..orbitals.num_op() {
+            let (c, e) = (orbitals.c(op), orbitals.e(op));
+            let n_occ = orbitals.homo(op);
+            assert!(
+                n_occ > 0 && n_occ < c.cols,
+                "Response calculations need occupied and virtual orbitals"
+            );
+            for i in 0..n_occ {
+                for a in n_occ..c.cols {
+                    delta.push(e[a] - e[i]);
+                }
+            }
+            c_occ.push(orbital_space(c, 0..n_occ));
+            c_virt.push(orbital_space(c, n_occ..c.cols));
+        }
+
+        Self {
+            eri,
+            restricted: orbitals.restricted(),
+            c_occ,
+            c_virt,
+            delta,
+        }
+    }
+
+    /// number of occupied-virtual pairs
+    pub fn dim(&self) -> usize {
+        self.delta.len()
+    }
+
+    /// Spin, occupied and virtual orbital of an occupied-virtual pair
+    pub fn pair(&self, mut index: usize) -> (usize, usize, usize) {
+        for op in 0..self.c_occ.len() {
+            let (n_occ, n_virt) = (self.c_occ[op].cols, self.c_virt[op].cols);
+            if index < n_occ * n_virt {
+                return (op, index / n_virt, n_occ + index % n_virt);
+            }
+            index -= n_occ * n_virt;
+        }
+        panic!("Pair index out of range")
+    }
+
+    /// Lowest excited states of the given spin (ignored for unrestricted references) with
+    /// their transition dipoles from the AO dipole integrals
+    pub fn solve(
+        &self,
+        response: ResponseType,
+        spin: Spin,
+        dipole: &[FMatrix; 3],
+        options: &TDHFOptions,
+    ) -> Vec<ExcitedState> {
+        let (energies, x, y) = match response {
+            ResponseType::TDA => {
+                let davidson_options = DavidsonOptions {
+                    n_roots: options.n_states,
+                    max_iter: options.max_iter,
+                    threshold: options.threshold,
+                    ..Default::default()
+                };
+                let (energies, vectors) = davidson(
+                    &FVector::new_from_vec(&self.delta),
+                    |v| FVector::new_from_vec(&self.sigma(v, Sigma::A, spin)),
+                    &davidson_options,
+                );
+                let x: Vec<Vec<f64>> = (0..energies.len())
+                    .map(|root| vectors.col(root).copied().collect())
+                    .collect();
+                let y = vec![vec![0.0; self.dim()]; x.len()];
+                (energies.to_vec(), x, y)
+            }
+            ResponseType::RPA => self.rpa(spin, options),
+        };
+
+        let spin = self.restricted.then_some(spin);
+        energies
+            .into_iter()
+            .zip(x.into_iter().zip(y))
+            .map(|(energy, (x, y))| {
+                let transition_dipole = self.transition_dipole(&x, &y, spin, dipole);
+                let oscillator_strength =
+                    2.0 / 3.0 * energy * transition_dipole.iter().map(|d| d * d).sum::<f64>();
+                ExcitedState {
+                    energy,
+                    spin,
+                    x,
+                    y,
+                    transition_dipole,
+                    oscillator_strength,
+                }
+            })
+            .collect()
+    }
+
+    /// (A X), ((A + B) X) or ((A - B) X) from the Fock-like matrices
+    /// G[T] = c_J J[T] - K[T] of the transition densities T = D, D + D^T or D - D^T,
+    /// with c_J = 2 for singlets, 0 for triplets and J of both spins for UHF
+    pub(crate) fn sigma(&self, x: &[f64], kind: Sigma, spin: Spin) -> Vec<f64> {
+        let n_ao = self.c_occ[0].rows;
+        let zero = FMatrix::zero(n_ao, n_ao);
+
+        let mut offset = 0;
+        let mut densities = Vec::new();
+        for (c_occ, c_virt) in self.c_occ.iter().zip(&self.c_virt) {
+            let n = c_occ.cols * c_virt.cols;
+            let x = FMatrix::new_from_vec(c_occ.cols, c_virt.cols, &x[offset..offset + n]);
+            let d = c_occ * &(&x * &c_virt.transposed());
+            densities.push(match kind {
+                Sigma::A => d,
+                Sigma::APlusB => &d + &d.transposed(),
+                Sigma::AMinusB => &d - &d.transposed(),
+            });
+            offset += n;
+        }
+
+        // J[D - D^T] vanishes
+        let g: Vec<FMatrix> = match self.restricted {
+            true => {
+                let facj = match (kind, spin) {
+                    (Sigma::AMinusB, _) | (_, Spin::Triplet) => 0.0,
+                    _ => 2.0,
+                };
+                vec![fock(&densities[0], &zero, self.eri, facj, 1.0)]
+            }
+            false => {
+                let j = match kind {
+                    Sigma::AMinusB => zero,
+                    _ => fock(&(&densities[0] + &densities[1]), &zero, self.eri, 1.0, 0.0),
+                };
+                densities
+                    .iter()
+                    .map(|d| fock(d, &j, self.eri, 0.0, 1.0))
+                    .collect()
+            }
+        };
+
+        let mut sigma: Vec<f64> = x.iter().zip(&self.delta).map(|(x, d)| x * d).collect();
+        let mut offset = 0;
+        for ((c_occ, c_virt), g) in self.c_occ.iter().zip(&self.c_virt).zip(&g) {
+            let g_ov = &c_occ.transposed() * &(g * c_virt);
+            for (s, g) in sigma[offset..].iter_mut().zip(g_ov.iter()) {
+                *s += g;
+            }
+            offset += g_ov.rows * g_ov.cols;
+        }
+        sigma
+    }
+
+    /// RPA in the symmetric form (A - B)^1/2 (A + B) (A - B)^1/2 Z = ω^2 Z, solved in a
+    /// common subspace for X + Y and X - Y, R. E. Stratmann, G. E. Scuseria and M. J.
+    /// Frisch, J. Chem. Phys. 109, 8218 (1998)
+    fn rpa(&self, spin: Spin, options: &TDHFOptions) -> (Vec<f64>, Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let n = self.dim();
+        let n_states = options.n_states.min(n);
+        let max_dim = n.min((20 * n_states).max(50));
+
+        let dot = |x: &[f64], y: &[f64]| -> f64 { x.iter().zip(y).map(|(a, b)| a * b).sum() };
+        let combine = |vectors: &[Vec<f64>], t: &[f64]| -> Vec<f64> {
+            let mut x = vec![0.0; n];
+            for (v, t) in vectors.iter().zip(t) {
+                for (x, v) in x.iter_mut().zip(v) {
+                    *x += t * v;
+                }
+            }
+            x
+        };
+
+        // unit vectors of the lowest orbital energy differences as initial guess
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| self.delta[*a].total_cmp(&self.delta[*b]));
+        let mut basis: Vec<Vec<f64>> = Vec::new();
+        let mut new: Vec<Vec<f64>> = order
+            .iter()
+            .take((2 * n_states).min(n))
+            .map(|k| {
+                let mut v = vec![0.0; n];
+                v[*k] = 1.0;
+                v
+            })
+            .collect();
+        let mut plus: Vec<Vec<f64>> = Vec::new();
+        let mut minus: Vec<Vec<f64>> = Vec::new();
+
+        let mut energies = Vec::new();
+        let mut xpy = Vec::new();
+        let mut xmy = Vec::new();
+        for iter in 0..options.max_iter {
+            // orthonormalize the new vectors against the subspace
+            for mut v in new.drain(..) {
+                for _ in 0..2 {
+                    for b in &basis {
+                        let overlap = dot(b, &v);
+                        v.iter_mut().zip(b).for_each(|(v, b)| *v -= overlap * b);
+                    }
+                }
+                let norm = dot(&v, &v).sqrt();
+                if norm < 1e-8 {
+                    continue;
+                }
+                v.iter_mut().for_each(|x| *x /= norm);
+                plus.push(self.sigma(&v, Sigma::APlusB, spin));
+                minus.push(self.sigma(&v, Sigma::AMinusB, spin));
+                basis.push(v);
+            }
+
+            // reduced matrices and the symmetric eigenvalue problem
+            let dim = basis.len();
+            let mut m_plus = FMatrix::zero(dim, dim);
+            let mut m_minus = FMatrix::zero(dim, dim);
+            for i in 0..dim {
+                for j in 0..dim {
+                    m_plus[(i, j)] = 0.5 * (dot(&basis[i], &plus[j]) + dot(&basis[j], &plus[i]));
+                    m_minus[(i, j)] = 0.5 * (dot(&basis[i], &minus[j]) + dot(&basis[j], &minus[i]));
+                }
+            }
+            let s = m_minus.powf_sym(0.5);
+            let (omega2, z) = (&s * &(&m_plus * &s)).diagonalize_sym();
+
+            energies.clear();
+            xpy.clear();
+            xmy.clear();
+            let mut norm_max: f64 = 0.0;
+            for root in 0..n_states {
+                let omega = omega2[root].sqrt();
+                let z_root: Vec<f64> = (0..dim).map(|k| z[(k, root)]).collect();
+
+                // X + Y = b S z, X - Y = (A + B)(X + Y) / ω, (X + Y)(X - Y) = 1
+                let mut t_plus: Vec<f64> = (0..dim)
+                    .map(|i| (0..dim).map(|k| s[(i, k)] * z_root[k]).sum())
+                    .collect();
+                let mut t_minus: Vec<f64> = (0..dim)
+                    .map(|i| (0..dim).map(|k| m_plus[(i, k)] * t_plus[k]).sum::<f64>() / omega)
+                    .collect();
+                let scale = dot(&t_plus, &t_minus).sqrt();
+                t_plus.iter_mut().for_each(|t| *t /= scale);
+                t_minus.iter_mut().for_each(|t| *t /= scale);
+
+                let v_plus = combine(&basis, &t_plus);
+                let v_minus = combine(&basis, &t_minus);
+
+                // residuals (A + B)(X + Y) - ω (X - Y) and (A - B)(X - Y) - ω (X + Y)
+                let r_plus: Vec<f64> = combine(&plus, &t_plus)
+                    .iter()
+                    .zip(&v_minus)
+                    .map(|(s, v)| s - omega * v)
+                    .collect();
+                let r_minus: Vec<f64> = combine(&minus, &t_minus)
+                    .iter()
+                    .zip(&v_plus)
+                    .map(|(s, v)| s - omega * v)
+                    .collect();
+                let norm = (dot(&r_plus, &r_plus) + dot(&r_minus, &r_minus)).sqrt();
+                norm_max = norm_max.max(norm);
+
+                if norm > options.threshold {
+                    for r in [r_plus, r_minus] {
+                        new.push(
+                            r.iter()
+                                .zip(&self.delta)
+                                .map(|(r, d)| {
+                                    let denominator = omega - d;
+                                    match denominator.abs() < 1e-8 {
+                                        true => r / 1e-8_f64.copysign(denominator),
+                                        false => r / denominator,
+                                    }
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+
+                energies.push(omega);
+                xpy.push(v_plus);
+                xmy.push(v_minus);
+            }
+
+            if new.is_empty() {
+                println!("Davidson converged in {} iterations", iter + 1);
+                break;
+            }
+            if iter + 1 == options.max_iter {
+                println!(
+                    "Warning: Davidson not converged within {} iterations, residual norm {:.2e}",
+                    options.max_iter, norm_max
+                );
+                break;
+            }
+
+            // collapse to the current solutions if the subspace becomes too large
+            if basis.len() + new.len() > max_dim {
+                basis.clear();
+                plus.clear();
+                minus.clear();
+                new.splice(0..0, xpy.iter().chain(&xmy).cloned());
+            }
+        }
+
+        let x = xpy
+            .iter()
+            .zip(&xmy)
+            .map(|(p, m)| p.iter().zip(m).map(|(p, m)| 0.5 * (p + m)).collect())
+            .collect();
+        let y = xpy
+            .iter()
+            .zip(&xmy)
+            .map(|(p, m)| p.iter().zip(m).map(|(p, m)| 0.5 * (p - m)).collect())
+            .collect();
+        (energies, x, y)
+    }
+
+    /// <0|r|n> = \sum_ia (X + Y)_ia <i|r|a>, with a factor √2 for singlets of a restricted
+    /// reference and zero for triplets
+    fn transition_dipole(
+        &self,
+        x: &[f64],
+        y: &[f64],
+        spin: Option<Spin>,
+        dipole: &[FMatrix; 3],
+    ) -> [f64; 3] {
+        let factor = match spin {
+            Some(Spin::Singlet) => 2.0_f64.sqrt(),
+            Some(Spin::Triplet) => return [0.0; 3],
+            None => 1.0,
+        };
+
+        dipole.each_ref().map(|d| {
+            let mut offset = 0;
+            let mut value = 0.0;
+            for (c_occ, c_virt) in self.c_occ.iter().zip(&self.c_virt) {
+                let d_ov = &c_occ.transposed() * &(d * c_virt);
+                for (k, d) in d_ov.iter().enumerate() {
+                    value += (x[offset + k] + y[offset + k]) * d;
+                }
+                offset += d_ov.rows * d_ov.cols;
+            }
+            factor * value
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResponseType, Spin, TDHFOptions, TDHF};
+    use crate::{
+        correlation::tests::rhf,
+        data::orbitals::Orbitals,
+        gto_basis_sets::BasisSet,
+        gto_integrals::{integral_interface::IntegralInterface, one_electron::OneElectronKernel},
+        linear_algebra::matrix::FMatrix,
+        tests::water,
+    };
+
+    /// Excitation energies of water/STO-3G at the geometry of T. D. Crawford's programming
+    /// project #12, singlets and triplets combined
+    const CIS: [f64; 20] = [
+        0.2872554996,
+        0.3444249963,
+        0.3564617587,
+        0.3659889948,
+        0.3945137992,
+        0.4160717386,
+        0.5056282877,
+        0.5142899971,
+        0.5551918860,
+        0.5630557635,
+        0.6553184485,
+        0.9101216891,
+        1.1087709658,
+        1.2000961331,
+        1.3007851948,
+        1.3257620652,
+        19.9585264123,
+        20.0109794203,
+        20.0113420895,
+        20.0505319444,
+    ];
+    const RPA: [f64; 20] = [
+        0.2851637170,
+        0.2997434467,
+        0.3526266606,
+        0.3547782530,
+        0.3651313107,
+        0.4153174946,
+        0.5001011401,
+        0.5106610509,
+        0.5460719086,
+        0.5513718846,
+        0.6502707118,
+        0.8734253708,
+        1.1038187957,
+        1.1957870714,
+        1.2832053178,
+        1.3237421886,
+        19.9585040647,
+        20.0109471551,
+        20.0113074586,
+        20.0504919449,
+    ];
+
+    fn dipole(rhf: &crate::correlation::tests::Reference) -> [FMatrix; 3] {
+        let integrals = IntegralInterface::new(&rhf.basis, &water());
+        let dipole = [
+            OneElectronKernel::DipoleX,
+            OneElectronKernel::DipoleY,
+            OneElectronKernel::DipoleZ,
+        ]
+        .map(|kernel| integrals.compute_one_electron_integral(kernel));
+        dipole
+    }
+
+    #[test]
+    fn water_sto_3g() {
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let dipole = dipole(&rhf);
+        let tdhf = TDHF::new(&rhf.orbitals, &rhf.eri);
+        let options = TDHFOptions {
+            n_states: 10,
+            threshold: 1e-9,
+            ..Default::default()
+        };
+
+        for (response, reference) in [(ResponseType::TDA, CIS), (ResponseType::RPA, RPA)] {
+            let singlets = tdhf.solve(response, Spin::Singlet, &dipole, &options);
+            let triplets = tdhf.solve(response, Spin::Triplet, &dipole, &options);
+            let mut energies: Vec<f64> = singlets
+                .iter()
+                .chain(&triplets)
+                .map(|state| state.energy)
+                .collect();
+            energies.sort_by(f64::total_cmp);
+            for (e, reference) in energies.iter().zip(reference) {
+                assert!(
+                    (e - reference).abs() < 1e-8,
+                    "{response}: {e} != {reference}"
+                );
+            }
+
+            // triplets are dark, X X - Y Y = 1
+            assert!(triplets
+                .iter()
+                .all(|state| state.oscillator_strength == 0.0));
+            assert!(singlets
+                .iter()
+                .any(|state| state.oscillator_strength > 0.01));
+            for state in &singlets {
+                let norm: f64 = state
+                    .x
+                    .iter()
+                    .zip(&state.y)
+                    .map(|(x, y)| x * x - y * y)
+                    .sum();
+                assert!((norm - 1.0).abs() < 1e-8);
+            }
+        }
+
+        // only a few roots from a smaller subspace
+        let options = TDHFOptions {
+            n_states: 2,
+            threshold: 1e-8,
+            ..Default::default()
+        };
+        let states = tdhf.solve(ResponseType::RPA, Spin::Triplet, &dipole, &options);
+        assert!((states[0].energy - RPA[0]).abs() < 1e-8);
+    }
+
+    #[test]
+    fn unrestricted() {
+        // closed-shell orbitals as UHF reference: singlets and the M_S = 0 triplets
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let dipole = dipole(&rhf);
+        let (c, e) = (rhf.orbitals.c(0), rhf.orbitals.e(0));
+        let uhf =
+            Orbitals::new_unrestricted([c.clone(), c.clone()], [e.clone(), e.clone()], [5, 5]);
+        let restricted = TDHF::new(&rhf.orbitals, &rhf.eri);
+        let unrestricted = TDHF::new(&uhf, &rhf.eri);
+        assert_eq!(unrestricted.dim(), 2 * restricted.dim());
+        assert_eq!(unrestricted.pair(12), (1, 1, 5));
+
+        let options = TDHFOptions {
+            n_states: 6,
+            threshold: 1e-9,
+            ..Default::default()
+        };
+        for response in [ResponseType::TDA, ResponseType::RPA] {
+            let states = unrestricted.solve(response, Spin::Singlet, &dipole, &options);
+            let singlets = restricted.solve(response, Spin::Singlet, &dipole, &options);
+            let triplets = restricted.solve(response, Spin::Triplet, &dipole, &options);
+            for state in &states {
+                let reference = singlets
+                    .iter()
+                    .chain(&triplets)
+                    .min_by(|a, b| {
+                        (a.energy - state.energy)
+                            .abs()
+                            .total_cmp(&(b.energy - state.energy).abs())
+                    })
+                    .unwrap();
+                assert!((state.energy - reference.energy).abs() < 1e-8);
+                assert!((state.oscillator_strength - reference.oscillator_strength).abs() < 1e-8);
+            }
+        }
+    }
+}
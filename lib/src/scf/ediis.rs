@@ -0,0 +1,245 @@
+use crate::linear_algebra::matrix::FMatrix;
+
+/// errors above which the energy-based coefficients are used alone, and below which the
+/// commutator DIIS coefficients take over
+const BLEND_START: f64 = 1e-1;
+const BLEND_END: f64 = 1e-4;
+
+/// EDIIS coefficients of the stored densities, Fock matrices (all spins flattened into
+/// one vector per iteration) and energies: minimum of the interpolated energy
+/// E(c) = \sum_i c_i E_i - 1/4 \sum_ij c_i c_j (D_i - D_j)(F_i - F_j) over c_i >= 0,
+/// \sum_i c_i = 1, K. N. Kudin, G. E. Scuseria and E. Cancès, J. Chem. Phys. 116, 8255
+/// (2002)
+pub fn ediis(energy: &[f64], density: &[Vec<f64>], fock: &[Vec<f64>]) -> Vec<f64> {
+    let n = energy.len();
+    let mut m = FMatrix::zero(n, n);
+    for i in 0..n {
+        for j in 0..i {
+            let x = dot_difference(&density[i], &density[j], &fock[i], &fock[j]);
+            m[(i, j)] = -0.5 * x;
+            m[(j, i)] = -0.5 * x;
+        }
+    }
+    minimize_simplex(energy, &m)
+}
+
+/// ADIIS coefficients: minimum of the second-order expansion around the last density n
+/// E(c) = E_n + \sum_i c_i (D_i - D_n) F_n + 1/2 \sum_ij c_i c_j (D_i - D_n)(F_j - F_n),
+/// X. Hu and W. Yang, J. Chem. Phys. 132, 054109 (2010)
+pub fn adiis(density: &[Vec<f64>], fock: &[Vec<f64>]) -> Vec<f64> {
+    let n = density.len();
+    let last = n - 1;
+    let zero = vec![0.0; fock[last].len()];
+    let (d, f) = (density, fock);
+
+    let a: Vec<f64> = (0..n)
+        .map(|i| dot_difference(&d[i], &d[last], &f[last], &zero))
+        .collect();
+    let mut m = FMatrix::zero(n, n);
+    for i in 0..n {
+        for j in 0..n {
+            m[(i, j)] = 0.5
+                * (dot_difference(&d[i], &d[last], &f[j], &f[last])
+                    + dot_difference(&d[j], &d[last], &f[i], &f[last]));
+        }
+    }
+    minimize_simplex(&a, &m)
+}
+
+/// Energy-based coefficients far from convergence, commutator DIIS coefficients close to
+/// it and a linear blend in between, A. J. Garza and G. E. Scuseria, J. Chem. Phys. 137,
+/// 054110 (2012)
+pub fn blend(energy: &[f64], cdiis: &[f64], error_max: f64) -> Vec<f64> {
+    if error_max > BLEND_START {
+        energy.to_vec()
+    } else if error_max < BLEND_END {
+        cdiis.to_vec()
+    } else {
+        let w = error_max / BLEND_START;
+        energy
+            .iter()
+            .zip(cdiis)
+            .map(|(e, c)| w * e + (1.0 - w) * c)
+            .collect()
+    }
+}
+
+/// (a1 - a2) · (b1 - b2)
+fn dot_difference(a1: &[f64], a2: &[f64], b1: &[f64], b2: &[f64]) -> f64 {
+    (0..a1.len())
+        .map(|k| (a1[k] - a2[k]) * (b1[k] - b2[k]))
+        .sum()
+}
+
+/// a c + 1/2 c M c
+fn quadratic(a: &[f64], m: &FMatrix, c: &[f64]) -> f64 {
+    let n = a.len();
+    (0..n)
+        .map(|i| c[i] * (a[i] + 0.5 * (0..n).map(|j| m[(i, j)] * c[j]).sum::<f64>()))
+        .sum()
+}
+
+/// Minimum of a c + 1/2 c M c over c_i >= 0, \sum_i c_i = 1 by projected gradient
+/// descent. M is not necessarily positive definite, so the descent is started from every
+/// vertex of the simplex and the lowest of the local minima is kept.
+fn minimize_simplex(a: &[f64], m: &FMatrix) -> Vec<f64> {
+    let n = a.len();
+    let max_iter = 1000;
+
+    // step 1/L with the Frobenius norm as bound of the Lipschitz constant of the gradient
+    let lipschitz = m.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let step = 1.0 / lipschitz.max(1e-12);
+
+    let mut best = (f64::MAX, vec![0.0; n]);
+    for vertex in 0..n {
+        let mut c = vec![0.0; n];
+        c[vertex] = 1.0;
+        for _ in 0..max_iter {
+            let moved: Vec<f64> = (0..n)
+                .map(|i| c[i] - step * (a[i] + (0..n).map(|j| m[(i, j)] * c[j]).sum::<f64>()))
+                .collect();
+            let next = project_simplex(&moved);
+            let change = next
+                .iter()
+                .zip(&c)
+                .fold(0.0, |max: f64, (x, y)| max.max((x - y).abs()));
+            c = next;
+            if change < 1e-14 {
+                break;
+            }
+        }
+
+        let value = quadratic(a, m, &c);
+        if value < best.0 {
+            best = (value, c);
+        }
+    }
+    best.1
+}
+
+/// Euclidean projection onto the simplex c_i >= 0, \sum_i c_i = 1, J. Duchi, S.
+/// Shalev-Shwartz, Y. Singer and T. Chandra, Proc. ICML 2008, 272
+fn project_simplex(v: &[f64]) -> Vec<f64> {
+    let mut sorted = v.to_vec();
+    sorted.sort_by(|x, y| y.total_cmp(x));
+
+    // largest k with sorted_k > (\sum_{j<=k} sorted_j - 1) / k
+    let mut sum = 0.0;
+    let mut shift = 0.0;
+    for (k, x) in sorted.iter().enumerate() {
+        sum += x;
+        let candidate = (sum - 1.0) / (k + 1) as f64;
+        if *x > candidate {
+            shift = candidate;
+        }
+    }
+    v.iter().map(|x| (x - shift).max(0.0)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adiis, blend, ediis, minimize_simplex, project_simplex, quadratic};
+    use crate::linear_algebra::{diis::solve_symmetric, matrix::FMatrix};
+
+    /// Minimum over all faces of the simplex from the stationarity conditions
+    fn brute_force(a: &[f64], m: &FMatrix) -> f64 {
+        let n = a.len();
+        let mut best = f64::MAX;
+        for subset in 1..(1usize << n) {
+            let face: Vec<usize> = (0..n).filter(|i| subset & (1 << i) != 0).collect();
+            let dim = face.len();
+            let mut kkt = FMatrix::new_with_value(dim + 1, dim + 1, 1.0);
+            kkt[(dim, dim)] = 0.0;
+            let mut rhs = vec![1.0; dim + 1];
+            for (k, &i) in face.iter().enumerate() {
+                rhs[k] = -a[i];
+                for (l, &j) in face.iter().enumerate() {
+                    kkt[(k, l)] = m[(i, j)];
+                }
+            }
+            let Some(x) = solve_symmetric(&kkt, &rhs) else {
+                continue;
+            };
+            if x[..dim].iter().any(|x| *x < -1e-12) {
+                continue;
+            }
+            let mut c = vec![0.0; n];
+            for (k, &i) in face.iter().enumerate() {
+                c[i] = x[k];
+            }
+            best = best.min(quadratic(a, m, &c));
+        }
+        best
+    }
+
+    #[test]
+    fn simplex() {
+        // the closest point of the simplex shifts the two largest components equally
+        let projected = project_simplex(&[0.5, 1.2, -0.3]);
+        for (x, reference) in projected.iter().zip([0.15, 0.85, 0.0]) {
+            assert!((x - reference).abs() < 1e-14);
+        }
+
+        // positive definite, indefinite and linear models
+        let a = [0.3, -0.2, 0.1, 0.05];
+        let matrices = [
+            [
+                2.0, 0.5, 0.1, 0.0, 0.5, 1.0, 0.2, 0.1, 0.1, 0.2, 1.5, 0.3, 0.0, 0.1, 0.3, 0.8,
+            ],
+            [
+                0.0, -1.0, 0.4, 0.2, -1.0, 0.0, 0.3, -0.5, 0.4, 0.3, 0.0, 0.6, 0.2, -0.5, 0.6, 0.0,
+            ],
+            [0.0; 16],
+        ];
+        for values in matrices {
+            let m = FMatrix::new_from_vec(4, 4, &values);
+            let c = minimize_simplex(&a, &m);
+            assert!(c.iter().all(|c| *c >= 0.0));
+            assert!((c.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+            assert!((quadratic(&a, &m, &c) - brute_force(&a, &m)).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn quadratic_model() {
+        // E(d) = g d + 1/2 d H d with F = g + H d: both interpolations are exact and the
+        // minimum -H^-1 g lies inside the triangle of the three densities
+        let h = [[2.0, 0.3], [0.3, 1.0]];
+        let g = [-0.7, -0.4];
+        let density = vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, 1.0]];
+        let fock: Vec<Vec<f64>> = density
+            .iter()
+            .map(|d| {
+                (0..2)
+                    .map(|k| g[k] + h[k][0] * d[0] + h[k][1] * d[1])
+                    .collect()
+            })
+            .collect();
+        let energy: Vec<f64> = density
+            .iter()
+            .zip(&fock)
+            .map(|(d, f)| (0..2).map(|k| 0.5 * d[k] * (g[k] + f[k])).sum())
+            .collect();
+        let det = h[0][0] * h[1][1] - h[0][1] * h[1][0];
+        let minimum = [
+            -(h[1][1] * g[0] - h[0][1] * g[1]) / det,
+            -(h[0][0] * g[1] - h[1][0] * g[0]) / det,
+        ];
+
+        for c in [ediis(&energy, &density, &fock), adiis(&density, &fock)] {
+            for k in 0..2 {
+                let d: f64 = (0..3).map(|i| c[i] * density[i][k]).sum();
+                assert!((d - minimum[k]).abs() < 1e-8, "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn blending() {
+        let (energy, cdiis) = ([1.0, 0.0], [0.0, 1.0]);
+        assert_eq!(blend(&energy, &cdiis, 0.5), vec![1.0, 0.0]);
+        assert_eq!(blend(&energy, &cdiis, 1e-5), vec![0.0, 1.0]);
+        let c = blend(&energy, &cdiis, 1e-2);
+        assert!((c[0] - 0.1).abs() < 1e-12 && (c[1] - 0.9).abs() < 1e-12);
+    }
+}
@@ -1,8 +1,9 @@
-use libferric::linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer, traits::Dot};
+use crate::linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer, traits::Dot};
 
 /// build Fock matrix as
 ///     RHF: Fμν = Hμν + \sum_{ρσ} Dρσ [ (μν|ρσ) - 0.5 (μρ|νσ) ]
 ///     UHF: Fμν = Hμν + \sum_{ρσ} Dρσ [ 2 (μν|ρσ) - (μρ|νσ) ]
+/// The exchange is scaled by `facx` for hybrid functionals and skipped if it vanishes.
 pub fn fock(d: &FMatrix, h: &FMatrix, eri: &FMatrixContainer, facj: f64, facx: f64) -> FMatrix {
     let dim = h.rows;
 
@@ -16,6 +17,10 @@ pub fn fock(d: &FMatrix, h: &FMatrix, eri: &FMatrixContainer, facj: f64, facx: f
         }
     }
 
+    if facx == 0.0 {
+        return h.clone() + facj * j;
+    }
+
     let mut k = FMatrix::zero(dim, dim);
     for μ in 0..dim {
         for ν in 0..dim {
@@ -0,0 +1,7 @@
+//! Building blocks of self-consistent field calculations shared by the SCF solvers and
+//! the methods built on top of them
+
+pub mod ediis;
+pub mod fock;
+pub mod occupation;
+pub mod trah;
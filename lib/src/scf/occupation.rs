@@ -0,0 +1,288 @@
+use crate::linear_algebra::{constants::K_B, matrix::FMatrix, vector::FVector};
+
+use std::fmt::{Display, Formatter};
+
+/// Occupation numbers `max_occ` for the `n_occ` lowest orbitals
+pub fn aufbau(n_mo: usize, n_occ: usize, max_occ: f64) -> FVector {
+    let mut on = FVector::zero(n_mo);
+    for i in 0..n_occ {
+        on[i] = max_occ;
+    }
+    on
+}
+
+/// Fermi-Dirac occupations n_i = max_occ / (1 + exp((ε_i - μ) / kT)) at the electronic
+/// temperature (K), with the Fermi level μ from bisection such that \sum_i n_i = n_electrons
+pub fn fermi_dirac(eps: &FVector, n_electrons: f64, temperature: f64, max_occ: f64) -> FVector {
+    assert!(
+        n_electrons <= max_occ * eps.len() as f64,
+        "{} electrons do not fit into {} orbitals",
+        n_electrons,
+        eps.len()
+    );
+
+    let kt = K_B * temperature;
+    let occupations = |mu: f64| -> FVector {
+        let on: Vec<f64> = eps
+            .iter()
+            .map(|e| max_occ / (1.0 + ((e - mu) / kt).clamp(-700.0, 700.0).exp()))
+            .collect();
+        FVector::new_from_vec(&on)
+    };
+    let count = |mu: f64| occupations(mu).iter().sum::<f64>();
+
+    // widen the bracket around the orbital energies until it contains the electron count
+    let eps_min = eps.iter().copied().fold(f64::MAX, f64::min);
+    let eps_max = eps.iter().copied().fold(f64::MIN, f64::max);
+    let mut width = 1.0;
+    while count(eps_min - width) > n_electrons && width < 1e12 {
+        width *= 2.0;
+    }
+    let mut lo = eps_min - width;
+    let mut width = 1.0;
+    while count(eps_max + width) < n_electrons && width < 1e12 {
+        width *= 2.0;
+    }
+    let mut hi = eps_max + width;
+
+    for _ in 0..200 {
+        let mu = 0.5 * (lo + hi);
+        match count(mu) > n_electrons {
+            true => hi = mu,
+            false => lo = mu,
+        }
+    }
+    occupations(0.5 * (lo + hi))
+}
+
+/// Density matrix of occupied orbitals: Dμν = \sum_i n_i Cμi Cνi
+pub fn density(c: &FMatrix, on: &FVector) -> FMatrix {
+    let mut weighted = c.clone();
+    for mu in 0..c.rows {
+        for i in 0..c.cols {
+            weighted[(mu, i)] *= on[i];
+        }
+    }
+    weighted * c.transposed()
+}
+
+/// Orbital named in an occupation change
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orbital {
+    /// orbital number, counting from 1
+    Number(usize),
+    HOMO,
+    LUMO,
+}
+
+impl Orbital {
+    /// Orbital index for `n_occ` occupied orbitals, None for the HOMO without occupied
+    /// orbitals
+    pub fn index(&self, n_occ: usize) -> Option<usize> {
+        match self {
+            Orbital::Number(n) => n.checked_sub(1),
+            Orbital::HOMO => n_occ.checked_sub(1),
+            Orbital::LUMO => Some(n_occ),
+        }
+    }
+}
+
+/// Occupation change for ΔSCF: one electron of the given spin (0: alpha, 1: beta, None:
+/// both) moves from an occupied to an unoccupied orbital of the ground state
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Excitation {
+    pub spin: Option<usize>,
+    pub from: Orbital,
+    pub to: Orbital,
+}
+
+impl Display for Excitation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let spin = match self.spin {
+            None => "",
+            Some(0) => "alpha ",
+            Some(_) => "beta ",
+        };
+        let name = |orbital: &Orbital| match orbital {
+            Orbital::Number(n) => n.to_string(),
+            Orbital::HOMO => "HOMO".to_string(),
+            Orbital::LUMO => "LUMO".to_string(),
+        };
+        write!(f, "{}{} -> {}", spin, name(&self.from), name(&self.to))
+    }
+}
+
+/// Maximum overlap method: orders the orbitals such that the `n_occ` orbitals with the
+/// largest projection p_j = (S C)_j^T P (S C)_j onto the occupied space P of the previous
+/// iteration come first, P. A. B. Gilbert, N. A. Besley and P. M. W. Gill, J. Phys. Chem.
+/// A 112, 13164 (2008)
+pub fn max_overlap(
+    c: &FMatrix,
+    eps: &FVector,
+    s: &FMatrix,
+    p: &FMatrix,
+    n_occ: usize,
+) -> (FMatrix, FVector) {
+    let sc = s * c;
+    let psc = p * &sc;
+    let projection: Vec<f64> = (0..c.cols)
+        .map(|j| (0..c.rows).map(|mu| sc[(mu, j)] * psc[(mu, j)]).sum())
+        .collect();
+
+    let mut occupied: Vec<usize> = (0..c.cols).collect();
+    occupied.sort_by(|&a, &b| projection[b].total_cmp(&projection[a]));
+    occupied.truncate(n_occ);
+    occupied.sort();
+    let order: Vec<usize> = occupied
+        .iter()
+        .copied()
+        .chain((0..c.cols).filter(|j| !occupied.contains(j)))
+        .collect();
+
+    let mut c_new = FMatrix::zero(c.rows, c.cols);
+    let mut eps_new = FVector::zero(c.cols);
+    for (k, &j) in order.iter().enumerate() {
+        eps_new[k] = eps[j];
+        for mu in 0..c.rows {
+            c_new[(mu, k)] = c[(mu, j)];
+        }
+    }
+    (c_new, eps_new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{aufbau, density, fermi_dirac, max_overlap, Orbital};
+    use crate::{
+        correlation::tests::rhf,
+        gto_basis_sets::BasisSet,
+        gto_integrals::nuclear_repulsion::nuclear_repulsion,
+        linear_algebra::{
+            diagonalize::DiagonalizeSym, matrix::FMatrix, power::PowerSym, traits::Dot,
+            vector::FVector,
+        },
+        scf::fock::fock,
+        tests::water,
+    };
+
+    #[test]
+    fn electron_count() {
+        let eps = FVector::new_from_vec(&[-1.2, -0.5, -0.45, 0.1, 0.3]);
+        // kT = 3.2 Eh at 1e6 K lies far outside the spread of the orbital energies
+        for temperature in [100.0, 5000.0, 50000.0, 1e6] {
+            for (n_electrons, max_occ) in [(1.0, 2.0), (4.0, 2.0), (3.0, 1.0), (9.0, 2.0)] {
+                let on = fermi_dirac(&eps, n_electrons, temperature, max_occ);
+                let total: f64 = on.iter().sum();
+                assert!(
+                    (total - n_electrons).abs() < 1e-10,
+                    "{} K: {}",
+                    temperature,
+                    total
+                );
+                assert!(on.iter().all(|n| (0.0..=max_occ).contains(n)));
+            }
+        }
+    }
+
+    #[test]
+    fn zero_temperature() {
+        // the occupations approach the aufbau occupations, a degenerate pair shares
+        // its electrons evenly
+        let eps = FVector::new_from_vec(&[-1.2, -0.5, -0.45, 0.1, 0.3]);
+        let on = fermi_dirac(&eps, 6.0, 1.0, 2.0);
+        let reference = aufbau(5, 3, 2.0);
+        for (n, r) in on.iter().zip(reference.iter()) {
+            assert!((n - r).abs() < 1e-10);
+        }
+
+        let eps = FVector::new_from_vec(&[-1.2, -0.5, -0.5, 0.1]);
+        let on = fermi_dirac(&eps, 4.0, 1.0, 2.0);
+        assert!((on[1] - 1.0).abs() < 1e-10 && (on[2] - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    #[should_panic(expected = "5 electrons do not fit into 2 orbitals")]
+    fn too_many_electrons() {
+        fermi_dirac(&FVector::new_from_vec(&[-1.0, 0.5]), 5.0, 300.0, 2.0);
+    }
+
+    #[test]
+    fn orbital_index() {
+        assert_eq!(Orbital::Number(3).index(5), Some(2));
+        assert_eq!(Orbital::HOMO.index(5), Some(4));
+        assert_eq!(Orbital::LUMO.index(5), Some(5));
+        assert_eq!(Orbital::HOMO.index(0), None);
+        assert_eq!(Orbital::LUMO.index(0), Some(0));
+    }
+
+    #[test]
+    fn maximum_overlap() {
+        // the occupied space of orbitals 0 and 2 stays occupied although orbital 1 is lower
+        let c = FMatrix::new_from_vec(3, 3, &[0.0, 1.0, 0.0, 0.6, 0.0, 0.8, 0.8, 0.0, -0.6]);
+        let eps = FVector::new_from_vec(&[-1.0, -0.8, 0.2]);
+        let s = FMatrix::new_from_vec(3, 3, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        let p = density(&c, &FVector::new_from_vec(&[1.0, 0.0, 1.0]));
+        let (c_new, eps_new) = max_overlap(&c, &eps, &s, &p, 2);
+        assert_eq!(
+            eps_new.iter().copied().collect::<Vec<f64>>(),
+            [-1.0, 0.2, -0.8]
+        );
+        for (k, j) in [0, 2, 1].into_iter().enumerate() {
+            for mu in 0..3 {
+                assert_eq!(c_new[(mu, k)], c[(mu, j)]);
+            }
+        }
+    }
+
+    #[test]
+    fn delta_scf() {
+        // UHF with the beta HOMO -> LUMO excitation of water kept by the maximum overlap
+        // method: stationary and well above the ground state instead of collapsing
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let (h, eri, s) = (&rhf.h, &rhf.eri, &rhf.s);
+        let x = s.powf_sym(-0.5);
+        let n = s.rows;
+        let n_occ = 5;
+
+        let mut c = [rhf.orbitals.c(0).clone(), rhf.orbitals.c(0).clone()];
+        for mu in 0..n {
+            c[1][(mu, n_occ - 1)] = c[0][(mu, n_occ)];
+            c[1][(mu, n_occ)] = c[0][(mu, n_occ - 1)];
+        }
+        let on = aufbau(n, n_occ, 1.0);
+
+        let mut energy = 0.0;
+        let mut converged = false;
+        let mut commutator = f64::MAX;
+        for _ in 0..100 {
+            let d = [density(&c[0], &on), density(&c[1], &on)];
+            let zero = FMatrix::zero(n, n);
+            let j = fock(&(&d[0] + &d[1]), &zero, eri, 1.0, 0.0);
+            let f = [
+                fock(&d[0], &(h + &j), eri, 0.0, 1.0),
+                fock(&d[1], &(h + &j), eri, 0.0, 1.0),
+            ];
+
+            let e_old = energy;
+            energy = (0..2).map(|op| 0.5 * d[op].dot(&(h + &f[op]))).sum();
+            commutator = (0..2)
+                .flat_map(|op| (&f[op] * &d[op] * s - s * &d[op] * &f[op]).to_vec())
+                .fold(0.0, |max: f64, e| max.max(e.abs()));
+            if (energy - e_old).abs() < 1e-11 && commutator < 1e-7 {
+                converged = true;
+                break;
+            }
+
+            for op in 0..2 {
+                let (eps, c_prime) = (x.transposed() * &f[op] * &x).diagonalize_sym();
+                c[op] = max_overlap(&(&x * c_prime), &eps, s, &d[op], n_occ).0;
+            }
+        }
+        assert!(converged, "{}", commutator);
+
+        // ΔSCF of the program for the same molecule
+        let excitation = energy + nuclear_repulsion(&water()) - rhf.e_scf;
+        assert!((excitation - 0.274263118).abs() < 1e-8, "{}", excitation);
+    }
+}
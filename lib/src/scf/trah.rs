@@ -0,0 +1,503 @@
+use crate::{
+    gto_integrals::mo_transform::orbital_space,
+    linear_algebra::{
+        diagonalize::DiagonalizeSym,
+        exponential::exp_antisymmetric,
+        matrix::FMatrix,
+        matrix_container::FMatrixContainer,
+        traits::{Dot, Norm},
+        vector::FVector,
+    },
+    scf::fock::fock,
+};
+
+/// Orbital gradient and Hessian of RHF (one set of orbitals) or UHF (alpha and beta)
+/// wave functions with respect to real occupied-virtual rotations C' = C exp(κ),
+/// κ_ai = -κ_ia = x_ia. The energy changes as
+/// E(x) = E + s (2 g x + x H x) with s = 2 for RHF and s = 1 for UHF.
+/// Hessian products are built in the AO basis like the response matrices A + B, but
+/// with the full occupied and virtual Fock blocks, so they hold away from convergence.
+/// For Kohn-Sham the exchange-correlation kernel is neglected (quasi-Newton).
+pub struct OrbitalHessian<'a> {
+    eri: &'a FMatrixContainer,
+    facx: f64,
+
+    c_occ: Vec<FMatrix>,
+    c_virt: Vec<FMatrix>,
+    f_oo: Vec<FMatrix>,
+    f_vv: Vec<FMatrix>,
+
+    gradient: FVector,
+    diagonal: FVector,
+}
+
+impl<'a> OrbitalHessian<'a> {
+    /// Orbitals, number of occupied orbitals and AO Fock matrix of every spin, fraction
+    /// of exact exchange
+    pub fn new(
+        c: &[FMatrix],
+        n_occ: &[usize],
+        f: &[FMatrix],
+        eri: &'a FMatrixContainer,
+        facx: f64,
+    ) -> Self {
+        let mut hessian = Self {
+            eri,
+            facx,
+            c_occ: Vec::new(),
+            c_virt: Vec::new(),
+            f_oo: Vec::new(),
+            f_vv: Vec::new(),
+            gradient: FVector::default(),
+            diagonal: FVector::default(),
+        };
+
+        let mut gradient = Vec::new();
+        let mut diagonal = Vec::new();
+        for ((c, &n_occ), f) in c.iter().zip(n_occ).zip(f) {
+            let c_occ = orbital_space(c, 0..n_occ);
+            let c_virt = orbital_space(c, n_occ..c.cols);
+            let f_oo = &c_occ.transposed() * &(f * &c_occ);
+            let f_ov = &c_occ.transposed() * &(f * &c_virt);
+            let f_vv = &c_virt.transposed() * &(f * &c_virt);
+            for i in 0..c_occ.cols {
+                for a in 0..c_virt.cols {
+                    gradient.push(f_ov[(i, a)]);
+                    diagonal.push(f_vv[(a, a)] - f_oo[(i, i)]);
+                }
+            }
+            hessian.c_occ.push(c_occ);
+            hessian.c_virt.push(c_virt);
+            hessian.f_oo.push(f_oo);
+            hessian.f_vv.push(f_vv);
+        }
+        hessian.gradient = FVector::new_from_vec(&gradient);
+        hessian.diagonal = FVector::new_from_vec(&diagonal);
+        hessian
+    }
+
+    pub fn restricted(&self) -> bool {
+        self.c_occ.len() == 1
+    }
+
+    /// s in E(x) = E + s (2 g x + x H x)
+    pub fn scale(&self) -> f64 {
+        if self.restricted() {
+            2.0
+        } else {
+            1.0
+        }
+    }
+
+    pub fn gradient(&self) -> &FVector {
+        &self.gradient
+    }
+
+    /// F_aa - F_ii, the diagonal of the Hessian without two-electron terms
+    pub fn diagonal(&self) -> &FVector {
+        &self.diagonal
+    }
+
+    /// H x = X F_vv - F_oo X + C_o^T G[D + D^T] C_v with Dμν = \sum_ia Cμi X_ia Cνa and
+    /// G = 2 J - c_x K (RHF) or G_σ = J[D_α + D_β] - c_x K[D_σ] (UHF)
+    pub fn product(&self, x: &FVector) -> FVector {
+        let n_ao = self.c_occ[0].rows;
+        let zero = FMatrix::zero(n_ao, n_ao);
+
+        let mut offset = 0;
+        let mut blocks = Vec::new();
+        let mut densities = Vec::new();
+        for (c_occ, c_virt) in self.c_occ.iter().zip(&self.c_virt) {
+            let n = c_occ.cols * c_virt.cols;
+            let x = FMatrix::new_from_vec(c_occ.cols, c_virt.cols, &(**x)[offset..offset + n]);
+            let d = c_occ * &(&x * &c_virt.transposed());
+            densities.push(&d + &d.transposed());
+            blocks.push(x);
+            offset += n;
+        }
+
+        let g: Vec<FMatrix> = match self.restricted() {
+            true => vec![fock(&densities[0], &zero, self.eri, 2.0, self.facx)],
+            false => {
+                let j = fock(&(&densities[0] + &densities[1]), &zero, self.eri, 1.0, 0.0);
+                densities
+                    .iter()
+                    .map(|d| fock(d, &j, self.eri, 0.0, self.facx))
+                    .collect()
+            }
+        };
+
+        let mut sigma = Vec::with_capacity(x.len());
+        for op in 0..self.c_occ.len() {
+            let (c_occ, c_virt) = (&self.c_occ[op], &self.c_virt[op]);
+            let hx = &blocks[op] * &self.f_vv[op] - &self.f_oo[op] * &blocks[op]
+                + &c_occ.transposed() * &(&g[op] * c_virt);
+            sigma.extend(hx.iter());
+        }
+        FVector::new_from_vec(&sigma)
+    }
+}
+
+/// Trust-region augmented Hessian (TRAH) steps, the level-shifted Newton step
+/// x = -(H - μ)^-1 g with μ <= min(0, λ_min) such that |x| stays within the trust
+/// radius, solved in a Davidson subspace of Hessian products. Follows
+/// B. Helmich-Paris, J. Chem. Phys. 154, 164104 (2021).
+pub struct TrustRegion {
+    radius: f64,
+    // energy change predicted by the quadratic model for the last step
+    predicted: f64,
+    step_norm: f64,
+}
+
+impl TrustRegion {
+    const MIN_RADIUS: f64 = 1e-4;
+    const MAX_RADIUS: f64 = 1.0;
+    const MAX_MICRO: usize = 30;
+
+    pub fn new(radius: f64) -> Self {
+        Self {
+            radius,
+            predicted: 0.0,
+            step_norm: 0.0,
+        }
+    }
+
+    /// Step within the trust region
+    pub fn step(&mut self, hessian: &OrbitalHessian) -> FVector {
+        let g = hessian.gradient();
+        let n = g.len();
+        let g_norm = g.norm();
+        if g_norm < 1e-14 {
+            self.predicted = 0.0;
+            self.step_norm = 0.0;
+            return FVector::zero(n);
+        }
+        // superlinear convergence from increasingly accurate steps
+        let threshold = g_norm.min(0.1) * g_norm;
+
+        let precondition = |r: &FVector, mu: f64| -> FVector {
+            let mut v = r.clone();
+            for (v, d) in v.iter_mut().zip(hessian.diagonal().iter()) {
+                *v /= (d - mu).max(1e-2);
+            }
+            v
+        };
+
+        let mut basis: Vec<FVector> = Vec::new();
+        let mut sigmas: Vec<FVector> = Vec::new();
+        let mut new = vec![g.clone(), precondition(g, 0.0)];
+        let mut x = FVector::zero(n);
+        for _ in 0..Self::MAX_MICRO {
+            for mut v in new.drain(..) {
+                v *= 1.0 / v.norm();
+                for _ in 0..2 {
+                    for b in &basis {
+                        let overlap = -b.dot(&v);
+                        v.axpy(&overlap, b);
+                    }
+                }
+                let norm = v.norm();
+                if norm < 1e-8 {
+                    continue;
+                }
+                v *= 1.0 / norm;
+                sigmas.push(hessian.product(&v));
+                basis.push(v);
+            }
+            if basis.is_empty() {
+                break;
+            }
+
+            // Hessian and gradient in the subspace
+            let dim = basis.len();
+            let mut h = FMatrix::zero(dim, dim);
+            for i in 0..dim {
+                for j in 0..=i {
+                    let x = 0.5 * (basis[i].dot(&sigmas[j]) + basis[j].dot(&sigmas[i]));
+                    h[(i, j)] = x;
+                    h[(j, i)] = x;
+                }
+            }
+            let (lambda, u) = h.diagonalize_sym();
+            let gu: Vec<f64> = (0..dim)
+                .map(|k| (0..dim).map(|i| u[(i, k)] * basis[i].dot(g)).sum())
+                .collect();
+
+            // |x(μ)|^2 = \sum_k (u_k g)^2 / (λ_k - μ)^2 increases monotonically with μ
+            let length = |mu: f64| -> f64 {
+                gu.iter()
+                    .zip(lambda.iter())
+                    .map(|(gu, l)| (gu / (l - mu)).powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            };
+            let mut mu = 0.0;
+            if lambda[0] <= 0.0 || length(0.0) > self.radius {
+                let upper = lambda[0].min(0.0);
+                let (mut lo, mut hi) = (upper - g_norm / self.radius - 1e-8, upper);
+                for _ in 0..100 {
+                    let mid = 0.5 * (lo + hi);
+                    match length(mid) > self.radius {
+                        true => hi = mid,
+                        false => lo = mid,
+                    }
+                }
+                mu = lo;
+            }
+
+            // x = -\sum_k u_k (u_k g) / (λ_k - μ) and the residual H x + g - μ x
+            let mut y = vec![0.0; dim];
+            for k in 0..dim {
+                let coefficient = -gu[k] / (lambda[k] - mu);
+                for i in 0..dim {
+                    y[i] += u[(i, k)] * coefficient;
+                }
+            }
+            x = FVector::zero(n);
+            let mut residual = g.clone();
+            for i in 0..dim {
+                x.axpy(&y[i], &basis[i]);
+                residual.axpy(&y[i], &sigmas[i]);
+            }
+            residual.axpy(&-mu, &x);
+
+            let residual_norm = residual.norm();
+            if residual_norm < threshold || residual_norm < 1e-14 || dim == n {
+                break;
+            }
+            new.push(precondition(&residual, mu));
+        }
+
+        let hx = hessian.product(&x);
+        self.predicted = hessian.scale() * (2.0 * g.dot(&x) + x.dot(&hx));
+        self.step_norm = x.norm();
+        x
+    }
+
+    /// Accepts or rejects the last step from the actual energy change and adapts the trust
+    /// radius to the agreement with the quadratic model
+    pub fn update(&mut self, actual: f64) -> bool {
+        // changes at the level of numerical noise
+        if self.predicted.abs() < 1e-10 && actual < 1e-10 {
+            return true;
+        }
+
+        let ratio = actual / self.predicted;
+        if ratio < 0.0 {
+            self.radius = (0.5 * self.step_norm.min(self.radius)).max(Self::MIN_RADIUS);
+            return false;
+        }
+        if ratio < 0.25 {
+            self.radius = (0.7 * self.radius).max(Self::MIN_RADIUS);
+        } else if ratio > 0.75 && self.step_norm > 0.8 * self.radius {
+            self.radius = (2.0 * self.radius).min(Self::MAX_RADIUS);
+        }
+        true
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+/// Orbitals of every spin rotated by C' = C exp(κ) with κ_ai = -κ_ia = x_ia
+pub fn rotate(c: &[FMatrix], n_occ: &[usize], x: &FVector) -> Vec<FMatrix> {
+    let mut offset = 0;
+    c.iter()
+        .zip(n_occ)
+        .map(|(c, &n_occ)| {
+            let n_mo = c.cols;
+            let mut kappa = FMatrix::zero(n_mo, n_mo);
+            for i in 0..n_occ {
+                for a in n_occ..n_mo {
+                    kappa[(a, i)] = x[offset];
+                    kappa[(i, a)] = -x[offset];
+                    offset += 1;
+                }
+            }
+            c * &exp_antisymmetric(&kappa)
+        })
+        .collect()
+}
+
+/// Canonical orbitals that diagonalize the occupied and virtual blocks of the Fock
+/// matrix separately, leaving the occupied space unchanged
+pub fn canonicalize(c: &FMatrix, f: &FMatrix, n_occ: usize) -> (FMatrix, FVector) {
+    let mut c_new = FMatrix::zero(c.rows, c.cols);
+    let mut e = FVector::zero(c.cols);
+    for range in [0..n_occ, n_occ..c.cols] {
+        if range.is_empty() {
+            continue;
+        }
+        let offset = range.start;
+        let space = orbital_space(c, range);
+        let (eps, u) = (&space.transposed() * &(f * &space)).diagonalize_sym();
+        let rotated = &space * &u;
+        for k in 0..space.cols {
+            e[offset + k] = eps[k];
+            for mu in 0..c.rows {
+                c_new[(mu, offset + k)] = rotated[(mu, k)];
+            }
+        }
+    }
+    (c_new, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{canonicalize, rotate, OrbitalHessian, TrustRegion};
+    use crate::{
+        correlation::tests::rhf,
+        gto_basis_sets::BasisSet,
+        gto_integrals::mo_transform::orbital_space,
+        linear_algebra::{
+            matrix::FMatrix, matrix_container::FMatrixContainer, traits::Dot, vector::FVector,
+        },
+        scf::fock::fock,
+        tests::water,
+    };
+
+    /// Energy and Fock matrix of RHF orbitals
+    fn energy(c: &FMatrix, h: &FMatrix, eri: &FMatrixContainer) -> (f64, FMatrix) {
+        let c_occ = orbital_space(c, 0..5);
+        let d = &c_occ * &c_occ.transposed() * 2.0;
+        let f = fock(&d, h, eri, 1.0, 0.5);
+        (0.5 * d.dot(&(h + &f)), f)
+    }
+
+    /// Energy and Fock matrices of UHF orbitals with 5 alpha and 4 beta electrons
+    fn energy_uhf(c: &[FMatrix], h: &FMatrix, eri: &FMatrixContainer) -> (f64, Vec<FMatrix>) {
+        let d: Vec<FMatrix> = c
+            .iter()
+            .zip([5, 4])
+            .map(|(c, n_occ)| {
+                let c_occ = orbital_space(c, 0..n_occ);
+                &c_occ * &c_occ.transposed()
+            })
+            .collect();
+        let zero = FMatrix::zero(h.rows, h.cols);
+        let j = fock(&(&d[0] + &d[1]), &zero, eri, 1.0, 0.0);
+        let f: Vec<FMatrix> = d
+            .iter()
+            .map(|d| fock(d, &(h + &j), eri, 0.0, 1.0))
+            .collect();
+        let e = (0..2).map(|op| 0.5 * d[op].dot(&(h + &f[op]))).sum();
+        (e, f)
+    }
+
+    #[test]
+    fn gradient_and_hessian() {
+        // quadratic model against energies along a rotation of perturbed orbitals
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let n = 10;
+        let direction: Vec<f64> = (0..n).map(|k| 0.1 * ((k * 7 % 5) as f64 - 2.0)).collect();
+        let c = &rotate(
+            &[rhf.orbitals.c(0).clone()],
+            &[5],
+            &FVector::new_from_vec(&direction),
+        )[0];
+        let (e, f) = energy(c, &rhf.h, &rhf.eri);
+        let hessian = OrbitalHessian::new(&[c.clone()], &[5], &[f], &rhf.eri, 1.0);
+
+        let mut x = FVector::zero(n);
+        x[3] = 1.0;
+        x[6] = -0.5;
+        let t = 1e-3;
+        let e_at = |t: f64| {
+            let mut step = x.clone();
+            step *= t;
+            energy(&rotate(&[c.clone()], &[5], &step)[0], &rhf.h, &rhf.eri).0
+        };
+        let (e_plus, e_minus) = (e_at(t), e_at(-t));
+        let first = (e_plus - e_minus) / (2.0 * t);
+        let second = (e_plus - 2.0 * e + e_minus) / (t * t);
+
+        let s = hessian.scale();
+        let hx = hessian.product(&x);
+        assert!((first - s * 2.0 * hessian.gradient().dot(&x)).abs() < 1e-6);
+        assert!((second - s * 2.0 * x.dot(&hx)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn unrestricted_gradient_and_hessian() {
+        // the same quadratic model for the water cation with different alpha and beta
+        // orbitals
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let n_occ = [5, 4];
+        let n = 5 * 2 + 4 * 3;
+        let direction: Vec<f64> = (0..n).map(|k| 0.1 * ((k * 7 % 5) as f64 - 2.0)).collect();
+        let c0 = rhf.orbitals.c(0).clone();
+        let c = rotate(
+            &[c0.clone(), c0],
+            &n_occ,
+            &FVector::new_from_vec(&direction),
+        );
+        let (e, f) = energy_uhf(&c, &rhf.h, &rhf.eri);
+        let hessian = OrbitalHessian::new(&c, &n_occ, &f, &rhf.eri, 1.0);
+        assert!(!hessian.restricted());
+
+        // one alpha and two beta rotations
+        let mut x = FVector::zero(n);
+        x[3] = 1.0;
+        x[12] = -0.5;
+        x[20] = 0.7;
+        let t = 1e-3;
+        let e_at = |t: f64| {
+            let mut step = x.clone();
+            step *= t;
+            energy_uhf(&rotate(&c, &n_occ, &step), &rhf.h, &rhf.eri).0
+        };
+        let (e_plus, e_minus) = (e_at(t), e_at(-t));
+        let first = (e_plus - e_minus) / (2.0 * t);
+        let second = (e_plus - 2.0 * e + e_minus) / (t * t);
+
+        let s = hessian.scale();
+        let hx = hessian.product(&x);
+        assert!((first - s * 2.0 * hessian.gradient().dot(&x)).abs() < 1e-6);
+        assert!((second - s * 2.0 * x.dot(&hx)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn convergence() {
+        // second-order steps from perturbed orbitals back to the RHF solution
+        let rhf = rhf(&water(), &BasisSet::sto_3g);
+        let nuclear_repulsion = rhf.e_scf - energy(rhf.orbitals.c(0), &rhf.h, &rhf.eri).0;
+        let perturbation: Vec<f64> = (0..10).map(|k| 0.3 * (k as f64).sin()).collect();
+        let mut c = rotate(
+            &[rhf.orbitals.c(0).clone()],
+            &[5],
+            &FVector::new_from_vec(&perturbation),
+        )[0]
+        .clone();
+
+        let mut trust = TrustRegion::new(0.5);
+        let (mut e, mut f) = energy(&c, &rhf.h, &rhf.eri);
+        let mut converged = false;
+        for _ in 0..20 {
+            let hessian = OrbitalHessian::new(&[c.clone()], &[5], &[f.clone()], &rhf.eri, 1.0);
+            if hessian
+                .gradient()
+                .iter()
+                .fold(0.0, |m: f64, g| m.max(g.abs()))
+                < 1e-9
+            {
+                converged = true;
+                break;
+            }
+            let x = trust.step(&hessian);
+            let c_new = rotate(&[c.clone()], &[5], &x)[0].clone();
+            let (e_new, f_new) = energy(&c_new, &rhf.h, &rhf.eri);
+            if trust.update(e_new - e) {
+                (c, e, f) = (c_new, e_new, f_new);
+            }
+        }
+        assert!(converged);
+        assert!((e + nuclear_repulsion - rhf.e_scf).abs() < 1e-10);
+
+        // canonical orbitals reproduce the orbital energies
+        let (_, eps) = canonicalize(&c, &f, 5);
+        for (e, reference) in eps.iter().zip(rhf.orbitals.e(0).iter()) {
+            assert!((e - reference).abs() < 1e-7);
+        }
+    }
+}
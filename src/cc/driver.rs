@@ -0,0 +1,131 @@
+use super::input::CCInput;
+
+use libferric::{
+    correlation::ccsd::CCSD,
+    data::orbitals::{OrbitalType, Orbitals},
+    geometry::Geometry,
+    gto_integrals::{mo_transform::TransformBuffer, two_electron::TwoElectronKernel},
+    linear_algebra::{constants::AU_EV, matrix_container::FMatrixContainer},
+};
+
+use std::{error, time::Instant};
+
+pub fn driver(basename: &str, cc_input: &CCInput, e_scf: f64) -> Result<(), Box<dyn error::Error>> {
+    println!(
+        r#"
+
+▐▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▌
+▐                              ▌
+▐     ____ ____ ____  ____     ▌
+▐    / ___/ ___/ ___||  _ \    ▌
+▐   | |  | |   \___ \| | | |   ▌
+▐   | |__| |___ ___) | |_| |   ▌
+▐    \____\____|____/|____/    ▌
+▐                              ▌
+▐▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▌
+"#
+    );
+
+    // read data
+    let geometry = Geometry::retrieve(basename);
+    let orbitals = Orbitals::retrieve(basename);
+    if orbitals.kind() != OrbitalType::HartreeFock {
+        return Err(format!(
+            "Coupled cluster requires Hartree-Fock orbitals, not {} orbitals",
+            orbitals.kind()
+        )
+        .into());
+    }
+    let eri = FMatrixContainer::retrieve(TwoElectronKernel::ERI.to_filename());
+
+    let n_frozen = match cc_input.frozen_core {
+        true => geometry
+            .molecule
+            .atoms()
+            .iter()
+            .map(|atom| usize::from(atom.el.core_electrons()) / 2)
+            .sum(),
+        false => 0,
+    };
+
+    println!(
+        "Reference:                        {}",
+        if orbitals.restricted() { "RHF" } else { "UHF" }
+    );
+    println!(
+        "Method:                           {}",
+        if cc_input.triples { "CCSD(T)" } else { "CCSD" }
+    );
+    println!("Frozen Core Orbitals:             {}", n_frozen);
+    println!("Maximum Iterations:               {}", cc_input.max_iter);
+    println!(
+        "Integral Buffer:                  {}",
+        if cc_input.disk_buffer {
+            "disk"
+        } else {
+            "memory"
+        }
+    );
+    println!(
+        "DIIS Dimension:                   {}",
+        cc_input.diis_dim_max
+    );
+    println!(
+        "Thresholds (E, RMS):              {:.1e} {:.1e}\n",
+        cc_input.e_threshold, cc_input.rms_threshold
+    );
+
+    // half-transformed integrals in memory or in a scratch file
+    let buffer = match cc_input.disk_buffer {
+        true => TransformBuffer::Disk(format!("{basename}.mo.tmp")),
+        false => TransformBuffer::Memory,
+    };
+    let t = Instant::now();
+    let mut ccsd = CCSD::new(&orbitals, &eri, n_frozen, &buffer);
+    ccsd.solve(&cc_input.options());
+    println!("CCSD done ({:?})", t.elapsed());
+
+    let e_t = match cc_input.triples {
+        true => {
+            print!("Calculating triples correction (T) ... ");
+            let t = Instant::now();
+            let e_t = ccsd.triples();
+            println!("done ({:?})", t.elapsed());
+            Some(e_t)
+        }
+        false => None,
+    };
+
+    println!(
+        "\n                          {:^20}  {:^20}",
+        "Hartree", "eV"
+    );
+    println!(
+        "MP2 Correlation:     {:20.9}  {:20.5}",
+        ccsd.e_mp2(),
+        ccsd.e_mp2() * AU_EV
+    );
+    println!(
+        "CCSD Correlation:    {:20.9}  {:20.5}",
+        ccsd.e_ccsd(),
+        ccsd.e_ccsd() * AU_EV
+    );
+    if let Some(e_t) = e_t {
+        println!("(T) Correction:      {:20.9}  {:20.5}", e_t, e_t * AU_EV);
+    }
+    println!(
+        "\nTotal CCSD Energy:   {:20.9}  {:20.5}",
+        e_scf + ccsd.e_ccsd(),
+        (e_scf + ccsd.e_ccsd()) * AU_EV
+    );
+    if let Some(e_t) = e_t {
+        let e_tot = e_scf + ccsd.e_ccsd() + e_t;
+        println!(
+            "Total CCSD(T) Energy:{:20.9}  {:20.5}",
+            e_tot,
+            e_tot * AU_EV
+        );
+    }
+
+    Ok(())
+}
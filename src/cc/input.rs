@@ -0,0 +1,76 @@
+use libferric::correlation::ccsd::CCSDOptions;
+use serde_yaml::Value;
+
+#[derive(Clone)]
+pub struct CCInput {
+    // keep core orbitals uncorrelated
+    pub frozen_core: bool,
+
+    // perturbative triples correction
+    pub triples: bool,
+
+    // amplitude iterations
+    pub max_iter: usize,
+    pub diis_dim_max: usize,
+    pub e_threshold: f64,
+    pub rms_threshold: f64,
+
+    // half-transformed integrals on disk instead of in memory
+    pub disk_buffer: bool,
+}
+
+impl Default for CCInput {
+    fn default() -> Self {
+        let options = CCSDOptions::default();
+        Self {
+            frozen_core: false,
+
+            triples: true,
+
+            max_iter: options.max_iter,
+            diis_dim_max: options.diis_dim_max,
+            e_threshold: options.e_threshold,
+            rms_threshold: options.rms_threshold,
+
+            disk_buffer: false,
+        }
+    }
+}
+
+impl CCInput {
+    pub fn parse(input: &Value) -> CCInput {
+        // initialise CCInput with default values
+        let mut cc = CCInput::default();
+
+        // an empty block uses the defaults
+        if input.is_null() {
+            return cc;
+        }
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "frozencore" => cc.frozen_core = value.as_bool().unwrap(),
+                "triples" => cc.triples = value.as_bool().unwrap(),
+                "maxiter" => cc.max_iter = value.as_u64().unwrap() as usize,
+                "diisdimmax" => cc.diis_dim_max = value.as_u64().unwrap() as usize,
+                "thresholde" => cc.e_threshold = value.as_f64().unwrap(),
+                "thresholdrms" => cc.rms_threshold = value.as_f64().unwrap(),
+                "diskbuffer" => cc.disk_buffer = value.as_bool().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        // return CCInput
+        cc
+    }
+
+    pub fn options(&self) -> CCSDOptions {
+        CCSDOptions {
+            max_iter: self.max_iter,
+            e_threshold: self.e_threshold,
+            rms_threshold: self.rms_threshold,
+            diis_dim_max: self.diis_dim_max,
+        }
+    }
+}
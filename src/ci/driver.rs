@@ -0,0 +1,164 @@
+use super::input::CIInput;
+
+use libferric::{
+    correlation::ci::{
+        ci,
+        determinants::{DeterminantSpace, Occupation, MAX_ORBITALS},
+        hamiltonian::ActiveSpace,
+    },
+    data::orbitals::{OrbitalType, Orbitals},
+    geometry::Geometry,
+    gto_integrals::{
+        mo_transform::TransformBuffer, nuclear_repulsion::nuclear_repulsion,
+        one_electron::OneElectronKernel, two_electron::TwoElectronKernel,
+    },
+    linear_algebra::{constants::AU_EV, matrix::FMatrix, matrix_container::FMatrixContainer},
+};
+
+use std::{error, time::Instant};
+
+/// coefficients of the determinants printed for every state
+const PRINT_THRESHOLD: f64 = 0.1;
+
+pub fn driver(basename: &str, ci_input: &CIInput, e_scf: f64) -> Result<(), Box<dyn error::Error>> {
+    println!(
+        r#"
+
+▐▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▌
+▐                              ▌
+▐          ____ ___            ▌
+▐         / ___|_ _|           ▌
+▐        | |    | |            ▌
+▐        | |___ | |            ▌
+▐         \____|___|           ▌
+▐                              ▌
+▐▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▌
+"#
+    );
+
+    // read data
+    let geometry = Geometry::retrieve(basename);
+    let orbitals = Orbitals::retrieve(basename);
+    let h = FMatrix::retrieve(OneElectronKernel::HCore.to_filename());
+    let eri = FMatrixContainer::retrieve(TwoElectronKernel::ERI.to_filename());
+    let atoms = geometry.molecule.atoms();
+    if !orbitals.restricted() {
+        return Err("CI requires a restricted (RHF) reference".into());
+    }
+    if orbitals.kind() == OrbitalType::KohnSham {
+        return Err("CI requires Hartree-Fock or natural orbitals, not Kohn-Sham orbitals".into());
+    }
+
+    let n_core = match ci_input.frozen_core {
+        true => atoms
+            .iter()
+            .map(|atom| usize::from(atom.el.core_electrons()) / 2)
+            .sum(),
+        false => 0,
+    };
+    let n_available = orbitals.c(0).cols - n_core;
+    let n_active = ci_input.active_orbitals.unwrap_or(n_available);
+    let (n_alpha, n_beta) = (
+        geometry.n_electrons_alpha - n_core,
+        geometry.n_electrons_beta - n_core,
+    );
+    if n_active > n_available {
+        return Err(format!(
+            "{} active orbitals requested, but only {} are available",
+            n_active, n_available
+        )
+        .into());
+    }
+    if n_alpha.max(n_beta) > n_active {
+        return Err(format!(
+            "{} active orbitals cannot hold {} alpha and {} beta electrons",
+            n_active, n_alpha, n_beta
+        )
+        .into());
+    }
+    if n_active > MAX_ORBITALS {
+        return Err(format!(
+            "CI supports at most {} active orbitals, reduce them with ActiveOrbitals",
+            MAX_ORBITALS
+        )
+        .into());
+    }
+
+    let max_excitation = ci_input.method.max_excitation();
+    let n_determinants = DeterminantSpace::count(n_active, n_alpha, n_beta, max_excitation)
+        .filter(|n| *n <= ci_input.max_determinants)
+        .ok_or_else(|| {
+            format!(
+                "More than {} determinants, reduce the active space with ActiveOrbitals or \
+                 raise MaxDeterminants",
+                ci_input.max_determinants
+            )
+        })?;
+
+    println!("Reference:                        RHF");
+    println!("Method:                           {}", ci_input.method);
+    println!("Frozen Core Orbitals:             {}", n_core);
+    println!("Active Orbitals:                  {}", n_active);
+    println!("Active Electrons (Alpha, Beta):   {} {}", n_alpha, n_beta);
+    println!("Determinants:                     {}", n_determinants);
+    println!("Eigensolver:                      {}", ci_input.solver);
+    println!(
+        "Integral Buffer:                  {}",
+        if ci_input.disk_buffer {
+            "disk"
+        } else {
+            "memory"
+        }
+    );
+    println!("Number of Roots:                  {}\n", ci_input.n_roots);
+
+    // half-transformed integrals in memory or in a scratch file
+    let buffer = match ci_input.disk_buffer {
+        true => TransformBuffer::Disk(format!("{basename}.mo.tmp")),
+        false => TransformBuffer::Memory,
+    };
+    let t = Instant::now();
+    let active = ActiveSpace::new(&h, &eri, orbitals.c(0), n_core, n_active, &buffer);
+    let space = DeterminantSpace::new(n_active, n_alpha, n_beta, max_excitation);
+    let states = ci(&active, &space, &ci_input.options());
+    println!("CI done ({:?})\n", t.elapsed());
+
+    let e_nuc = nuclear_repulsion(atoms);
+    let e_ground = states[0].energy;
+    println!(
+        "Root {:^20} {:^20} {:^12} {:^8}",
+        "E (Hartree)", "E - E(SCF)", "ΔE (eV)", "<S^2>"
+    );
+    for (root, state) in states.iter().enumerate() {
+        println!(
+            "{:4} {:20.9} {:20.9} {:12.5} {:8.4}",
+            root,
+            state.energy + e_nuc,
+            state.energy + e_nuc - e_scf,
+            (state.energy - e_ground) * AU_EV,
+            state.s2
+        );
+    }
+
+    println!("\nLeading determinants (|c| > {}):", PRINT_THRESHOLD);
+    for (root, state) in states.iter().enumerate() {
+        println!("Root {}", root);
+        let mut leading: Vec<(usize, f64)> = state
+            .coefficients
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, c)| c.abs() > PRINT_THRESHOLD)
+            .collect();
+        leading.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        for (k, c) in leading {
+            println!(
+                "    {}  {:12.6}",
+                Occupation(&space.determinants()[k], n_active),
+                c
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use libferric::correlation::ci::{CIOptions, CISolver, CIType};
+use serde_yaml::Value;
+
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct CIInput {
+    // truncation of the determinant space
+    pub method: CIType,
+
+    // keep core orbitals doubly occupied
+    pub frozen_core: bool,
+    // correlated orbitals above the core, all by default
+    pub active_orbitals: Option<usize>,
+    // refuse larger determinant spaces
+    pub max_determinants: usize,
+
+    // eigensolver
+    pub solver: CISolver,
+    pub n_roots: usize,
+    pub max_iter: usize,
+    pub threshold: f64,
+
+    // half-transformed integrals on disk instead of in memory
+    pub disk_buffer: bool,
+}
+
+impl Default for CIInput {
+    fn default() -> Self {
+        let options = CIOptions::default();
+        Self {
+            method: CIType::FCI,
+
+            frozen_core: false,
+            active_orbitals: None,
+            max_determinants: 100_000,
+
+            solver: options.solver,
+            n_roots: options.n_roots,
+            max_iter: options.max_iter,
+            threshold: options.threshold,
+
+            disk_buffer: false,
+        }
+    }
+}
+
+impl CIInput {
+    pub fn parse(input: &Value) -> CIInput {
+        // initialise CIInput with default values
+        let mut ci = CIInput::default();
+
+        // an empty block uses the defaults
+        if input.is_null() {
+            return ci;
+        }
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "method" => ci.method = CIType::from_str(value.as_str().unwrap()).unwrap(),
+                "frozencore" => ci.frozen_core = value.as_bool().unwrap(),
+                "activeorbitals" => ci.active_orbitals = Some(value.as_u64().unwrap() as usize),
+                "maxdeterminants" => ci.max_determinants = value.as_u64().unwrap() as usize,
+                "solver" => ci.solver = CISolver::from_str(value.as_str().unwrap()).unwrap(),
+                "nroots" => ci.n_roots = value.as_u64().unwrap() as usize,
+                "maxiter" => ci.max_iter = value.as_u64().unwrap() as usize,
+                "threshold" => ci.threshold = value.as_f64().unwrap(),
+                "diskbuffer" => ci.disk_buffer = value.as_bool().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        // return CIInput
+        ci
+    }
+
+    pub fn options(&self) -> CIOptions {
+        CIOptions {
+            n_roots: self.n_roots,
+            max_iter: self.max_iter,
+            threshold: self.threshold,
+            solver: self.solver,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::input::CubeInput;
+
+use libferric::{
+    data::orbitals::Orbitals,
+    export::cube::{store_cube, CubeGrid, CubeProperty},
+    geometry::Geometry,
+    gto_basis_sets::basis::Basis,
+};
+
+use std::{error, time::Instant};
+
+pub fn driver(basename: &str, cube_input: &CubeInput) -> Result<(), Box<dyn error::Error>> {
+    println!(
+        r#"
+▐▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▌
+▐                               ▌
+▐      ____      _              ▌
+▐     / ___|   _| |__   ___     ▌
+▐    | |  | | | | '_ \ / _ \    ▌
+▐    | |__| |_| | |_) |  __/    ▌
+▐     \____\__,_|_.__/ \___|    ▌
+▐                               ▌
+▐▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▌
+"#
+    );
+
+    // read data
+    let geometry = Geometry::retrieve(basename);
+    let basis = Basis::retrieve(basename);
+    let orbitals = Orbitals::retrieve(basename);
+    let atoms = geometry.molecule.atoms();
+
+    let grid = CubeGrid::new(atoms, cube_input.padding, cube_input.spacing);
+    println!("Grid Origin (Bohr):   {:?}", grid.origin());
+    println!("Grid Spacing (Bohr):  {}", grid.spacing());
+    println!("Grid Points:          {:?}\n", grid.n());
+
+    // collect requested properties
+    let mut properties = Vec::new();
+    // orbitals are numbered from 1, linear dependencies may remove some of the basis
+    let n_mo = orbitals.c(0).cols;
+    for mo in &cube_input.orbitals {
+        if *mo > n_mo {
+            return Err(format!("Orbital {} exceeds the number of orbitals ({})", mo, n_mo).into());
+        }
+        for op in 0..orbitals.num_op() {
+            properties.push(CubeProperty::Orbital { op, mo: mo - 1 });
+        }
+    }
+    if cube_input.density {
+        properties.push(CubeProperty::Density);
+    }
+    if cube_input.spin_density {
+        match orbitals.restricted() {
+            true => println!("Spin density vanishes for restricted wave functions, skipped"),
+            false => properties.push(CubeProperty::SpinDensity),
+        }
+    }
+    if cube_input.esp {
+        properties.push(CubeProperty::ESP);
+    }
+
+    for property in properties {
+        let t = Instant::now();
+        let filename = store_cube(basename, &property, &grid, atoms, &basis, &orbitals);
+        println!(
+            "{:<32} {} ({:?})",
+            property.to_string(),
+            filename,
+            t.elapsed()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use serde_yaml::Value;
+
+use std::error;
+
+#[derive(Clone)]
+pub struct CubeInput {
+    // orbitals to plot (1-based), alpha and beta for unrestricted wave functions
+    pub orbitals: Vec<usize>,
+
+    // densities and potential
+    pub density: bool,
+    pub spin_density: bool,
+    pub esp: bool,
+
+    // grid in bohr
+    pub padding: f64,
+    pub spacing: f64,
+}
+
+impl Default for CubeInput {
+    fn default() -> Self {
+        Self {
+            orbitals: Vec::new(),
+
+            density: false,
+            spin_density: false,
+            esp: false,
+
+            padding: 4.0,
+            spacing: 0.2,
+        }
+    }
+}
+
+impl CubeInput {
+    pub fn parse(input: &Value) -> Result<CubeInput, Box<dyn error::Error>> {
+        // initialise CubeInput with default values
+        let mut cube = CubeInput::default();
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "orbitals" => {
+                    cube.orbitals = value
+                        .as_sequence()
+                        .expect("Orbitals have to be given as a list")
+                        .iter()
+                        .map(|mo| mo.as_u64().unwrap() as usize)
+                        .collect()
+                }
+                "density" => cube.density = value.as_bool().unwrap(),
+                "spindensity" => cube.spin_density = value.as_bool().unwrap(),
+                "esp" => cube.esp = value.as_bool().unwrap(),
+                "padding" => cube.padding = value.as_f64().unwrap(),
+                "spacing" => cube.spacing = value.as_f64().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        if cube.orbitals.contains(&0) {
+            return Err("Orbitals are counted from 1".into());
+        }
+
+        // return CubeInput
+        Ok(cube)
+    }
+}
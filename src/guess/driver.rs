@@ -22,7 +22,12 @@ pub fn driver(input: &FerricInput) -> Result<(), Box<dyn error::Error>> {
     );
 
     match input.guess {
-        Guess::HCore => hcore::guess(&input.base_name, &input.scf.hf, &input.geometry),
+        Guess::HCore => hcore::guess(
+            &input.base_name,
+            &input.scf.hf,
+            input.scf.lindep,
+            &input.geometry,
+        ),
     }
 
     Ok(())
@@ -1,24 +1,29 @@
 use libferric::{
     geometry::Geometry,
     gto_integrals::one_electron::OneElectronKernel,
-    linear_algebra::{diagonalize::DiagonalizeSym, matrix::FMatrix, power::PowerSym},
+    linear_algebra::{
+        diagonalize::DiagonalizeSym, matrix::FMatrix, orthogonalization::orthogonalizer,
+    },
     HFType,
 };
 
-pub fn guess(basename: &str, hf: &HFType, geometry: &Geometry) {
+pub fn guess(basename: &str, hf: &HFType, lindep: f64, geometry: &Geometry) {
     print!("Loading integrals  ... ");
     let h = FMatrix::retrieve(OneElectronKernel::HCore.to_filename());
     let s = FMatrix::retrieve(OneElectronKernel::Overlap.to_filename());
-    let s12 = s.powf_sym(-0.5);
+    let (x, _) = orthogonalizer(&s, lindep);
     println!("done");
 
     print!("Construction guess ... ");
+    // CASSCF starts from an RHF (singlet) or UHF reference
+    let restricted = geometry.multiplicity == 1;
     match hf {
-        HFType::RHF => guess_rhf(basename, &h, &s12, &geometry.n_electrons),
-        HFType::UHF => guess_uhf(
+        HFType::RHF => guess_rhf(basename, &h, &x, &geometry.n_electrons),
+        HFType::CASSCF if restricted => guess_rhf(basename, &h, &x, &geometry.n_electrons),
+        HFType::UHF | HFType::CASSCF => guess_uhf(
             basename,
             &h,
-            &s12,
+            &x,
             &[geometry.n_electrons_alpha, geometry.n_electrons_beta],
         ),
         _ => panic!("Guess: unsupported HFType for guess"),
@@ -26,24 +31,22 @@ pub fn guess(basename: &str, hf: &HFType, geometry: &Geometry) {
     print!("done");
 }
 
-fn guess_rhf(basename: &str, h: &FMatrix, s12: &FMatrix, nel: &usize) {
+fn guess_rhf(basename: &str, h: &FMatrix, x: &FMatrix, nel: &usize) {
     let homo = nel / 2;
 
     // --------------------------------
     // build initial guess density
     // orthogonalise H
     // --------------------------------
-    // core fock matrix: F' = S^{-1/2}^T H S^{-1/2} = S^{-1/2} H S^{-1/2}
-    // S-1/2 = symmetric
-    let f = s12 * (h * s12);
-    // S^{-1/2}^T H S^{-1/2}
-    let h_ortho = s12 * (h * s12);
+    // core fock matrix: F' = X^T H X, with X = S^{-1/2} unless linear dependencies
+    // are removed
+    let h_ortho = x.transposed() * (h * x);
 
     // --------------------------------
     // diagonalize H': C'^T H' C' = eps
     // --------------------------------
     let (ev, cprime) = h_ortho.diagonalize_sym();
-    let c = s12 * &cprime;
+    let c = x * &cprime;
 
     // --------------------------------
     // Initial density
@@ -58,18 +61,17 @@ fn guess_rhf(basename: &str, h: &FMatrix, s12: &FMatrix, nel: &usize) {
     d.store(&format!("{basename}.p.tmp"));
 }
 
-fn guess_uhf(basename: &str, h: &FMatrix, s12: &FMatrix, homo: &[usize; 2]) {
+fn guess_uhf(basename: &str, h: &FMatrix, x: &FMatrix, homo: &[usize; 2]) {
     (0..2).for_each(|op| {
         // --------------------------------
         // build initial guess density
         // --------------------------------
-        // core fock matrix: F' = S^{-1/2}^T H S^{-1/2} = S^{-1/2} H S^{-1/2}
-        // S-1/2 = symmetric
-        let f = s12 * (h * s12);
+        // core fock matrix: F' = X^T H X
+        let f = x.transposed() * (h * x);
 
         // diagonalize F': C'^T F' C' = eps
         let (_, cprime) = f.diagonalize_sym();
-        let c = s12 * cprime;
+        let c = x * cprime;
 
         // --------------------------------
         // Initial density
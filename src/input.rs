@@ -3,7 +3,15 @@ use libferric::{
     gto_basis_sets::BasisSet,
 };
 
-use crate::{guess::Guess, scf::input::SCFInput};
+use crate::{
+    cc::input::CCInput,
+    ci::input::CIInput,
+    cube::input::CubeInput,
+    guess::Guess,
+    mp2::input::MP2Input,
+    scf::input::{DFTInput, SCFInput},
+    tdhf::input::TDHFInput,
+};
 
 use std::{env::Args, fs, str::FromStr};
 
@@ -15,6 +23,7 @@ pub struct FerricInput {
 
     // Basis
     pub basis_set: BasisSet,
+    pub cartesian: bool,
 
     // Geometry
     pub geometry: Geometry,
@@ -22,6 +31,12 @@ pub struct FerricInput {
     // Modules
     pub guess: Guess,
     pub scf: SCFInput,
+    pub dft: Option<DFTInput>,
+    pub mp2: Option<MP2Input>,
+    pub cc: Option<CCInput>,
+    pub ci: Option<CIInput>,
+    pub tdhf: Option<TDHFInput>,
+    pub cube: Option<CubeInput>,
 }
 
 // todo: use Serialize/Deserialize?
@@ -43,6 +58,14 @@ impl FerricInput {
             match key.as_str().unwrap().to_lowercase().as_str() {
                 "guess" => ferric_input.parse_guess(value),
                 "scf" => ferric_input.scf = SCFInput::parse(value),
+                "dft" => ferric_input.dft = Some(DFTInput::parse(value)),
+                "mp2" => ferric_input.mp2 = Some(MP2Input::parse(value)),
+                "ccsd" => ferric_input.cc = Some(CCInput::parse(value)),
+                "ci" => ferric_input.ci = Some(CIInput::parse(value)),
+                "tdhf" => ferric_input.tdhf = Some(TDHFInput::parse(value)),
+                "cube" => {
+                    ferric_input.cube = Some(CubeInput::parse(value).expect("Invalid cube input"))
+                }
                 "basis" => ferric_input.parse_basis(value),
                 "geometry" => ferric_input.parse_geometry(value),
                 _ => panic!("Invalid block {:?}", key),
@@ -64,6 +87,17 @@ impl FerricInput {
     fn parse_basis(&mut self, input: &Value) {
         match input {
             Value::String(s) => self.basis_set = BasisSet::from_str(s).unwrap(),
+            Value::Mapping(mapping) => {
+                for (key, value) in mapping {
+                    match key.as_str().unwrap().to_lowercase().as_str() {
+                        "name" => {
+                            self.basis_set = BasisSet::from_str(value.as_str().unwrap()).unwrap()
+                        }
+                        "cartesian" => self.cartesian = value.as_bool().unwrap(),
+                        _ => panic!("Unknown basis option: {:?}", key),
+                    }
+                }
+            }
             _ => panic!("Invalid basis {:?}", input),
         }
     }
@@ -2,8 +2,13 @@
 mod input;
 
 // submodules
+mod cc;
+mod ci;
+mod cube;
 mod guess;
+mod mp2;
 mod scf;
+mod tdhf;
 
 use input::FerricInput;
 
@@ -59,7 +64,8 @@ fn main() {
     // --------------------------------------------------
     // initialize basis set
     // --------------------------------------------------
-    let basis = load_basis_set(&input.basis_set, input.geometry.molecule.atoms());
+    let mut basis = load_basis_set(&input.basis_set, input.geometry.molecule.atoms());
+    basis.set_cartesian(input.cartesian);
     basis.print_layout(input.geometry.molecule.atoms());
     basis.print_orca(input.geometry.molecule.atoms());
     basis.store(&input.base_name);
@@ -86,6 +92,7 @@ fn main() {
     let integrals = IntegralInterface::new(&basis, input.geometry.molecule.atoms());
     let _h = integrals.calc_one_electron_integral(OneElectronKernel::Overlap);
     let _s = integrals.calc_one_electron_integral(OneElectronKernel::HCore);
+    let _t = integrals.calc_one_electron_integral(OneElectronKernel::Kinetic);
     let _eri = integrals.calc_two_electron_integral(TwoElectronKernel::ERI);
 
     // --------------------------------------------------
@@ -96,9 +103,49 @@ fn main() {
     // --------------------------------------------------
     // SCF Calculation
     // --------------------------------------------------
-    scf::driver::driver(&input.base_name, input.scf)
+    let e_scf = scf::driver::driver(&input.base_name, input.scf, input.dft)
         .expect("SCF calculation did not finish succesfully");
 
+    // --------------------------------------------------
+    // MP2 Calculation
+    // --------------------------------------------------
+    if let Some(mp2_input) = &input.mp2 {
+        mp2::driver::driver(&input.base_name, mp2_input, e_scf)
+            .expect("MP2 calculation did not finish succesfully");
+    }
+
+    // --------------------------------------------------
+    // Coupled-Cluster Calculation
+    // --------------------------------------------------
+    if let Some(cc_input) = &input.cc {
+        cc::driver::driver(&input.base_name, cc_input, e_scf)
+            .expect("CCSD calculation did not finish succesfully");
+    }
+
+    // --------------------------------------------------
+    // Configuration Interaction
+    // --------------------------------------------------
+    if let Some(ci_input) = &input.ci {
+        ci::driver::driver(&input.base_name, ci_input, e_scf)
+            .expect("CI calculation did not finish succesfully");
+    }
+
+    // --------------------------------------------------
+    // Excited States
+    // --------------------------------------------------
+    if let Some(tdhf_input) = &input.tdhf {
+        tdhf::driver::driver(&input.base_name, tdhf_input)
+            .expect("TDHF calculation did not finish succesfully");
+    }
+
+    // --------------------------------------------------
+    // Cube files
+    // --------------------------------------------------
+    if let Some(cube_input) = &input.cube {
+        cube::driver::driver(&input.base_name, cube_input)
+            .expect("Cube files could not be written");
+    }
+
     // --------------------------------------------------
     // clean-up
     // --------------------------------------------------
@@ -0,0 +1,133 @@
+use super::input::MP2Input;
+
+use libferric::{
+    correlation::{mp2::mp2, ri_mp2::ri_mp2},
+    data::orbitals::Orbitals,
+    geometry::Geometry,
+    gto_basis_sets::basis::Basis,
+    gto_integrals::{mo_transform::TransformBuffer, two_electron::TwoElectronKernel},
+    linear_algebra::{constants::AU_EV, matrix_container::FMatrixContainer},
+};
+
+use std::{error, time::Instant};
+
+pub fn driver(
+    basename: &str,
+    mp2_input: &MP2Input,
+    e_scf: f64,
+) -> Result<(), Box<dyn error::Error>> {
+    println!(
+        r#"
+
+▐▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▌
+▐                              ▌
+▐    __  __ ____  ____         ▌
+▐   |  \/  |  _ \|___ \        ▌
+▐   | |\/| | |_) | __) |       ▌
+▐   | |  | |  __/ / __/        ▌
+▐   |_|  |_|_|   |_____|       ▌
+▐                              ▌
+▐▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▌
+"#
+    );
+
+    // read data
+    let geometry = Geometry::retrieve(basename);
+    let orbitals = Orbitals::retrieve(basename);
+
+    let n_frozen = match mp2_input.frozen_core {
+        true => geometry
+            .molecule
+            .atoms()
+            .iter()
+            .map(|atom| usize::from(atom.el.core_electrons()) / 2)
+            .sum(),
+        false => 0,
+    };
+
+    println!(
+        "Reference:                        {}",
+        if orbitals.restricted() { "RHF" } else { "UHF" }
+    );
+    println!(
+        "Method:                           {}",
+        if mp2_input.ri { "RI-MP2" } else { "MP2" }
+    );
+    if mp2_input.ri {
+        println!("Auxiliary Basis:                  {}", mp2_input.aux_basis);
+    } else {
+        println!(
+            "Integral Buffer:                  {}",
+            if mp2_input.disk_buffer {
+                "disk"
+            } else {
+                "memory"
+            }
+        );
+    }
+    println!("Frozen Core Orbitals:             {}", n_frozen);
+    println!(
+        "SCS Parameters (OS, SS):          {:.4} {:.4}\n",
+        mp2_input.c_os, mp2_input.c_ss
+    );
+
+    let t = Instant::now();
+    let energy = match mp2_input.ri {
+        true => {
+            let basis = Basis::retrieve(basename);
+            let aux = mp2_input.aux_basis.load(&basis, geometry.molecule.atoms());
+            println!("Auxiliary Basis Functions:        {}\n", aux.dim());
+            ri_mp2(&orbitals, &basis, &aux, n_frozen)
+        }
+        false => {
+            let eri = FMatrixContainer::retrieve(TwoElectronKernel::ERI.to_filename());
+            let buffer = match mp2_input.disk_buffer {
+                true => TransformBuffer::Disk(format!("{basename}.mo.tmp")),
+                false => TransformBuffer::Memory,
+            };
+            mp2(&orbitals, &eri, n_frozen, &buffer)
+        }
+    };
+    println!("MP2 done ({:?})\n", t.elapsed());
+
+    let e_scs = energy.scaled(mp2_input.c_os, mp2_input.c_ss);
+
+    println!("                          {:^20}  {:^20}", "Hartree", "eV");
+    println!(
+        "Opposite Spin:       {:20.9}  {:20.5}",
+        energy.os,
+        energy.os * AU_EV
+    );
+    println!(
+        "Same Spin:           {:20.9}  {:20.5}",
+        energy.ss,
+        energy.ss * AU_EV
+    );
+    println!(
+        "MP2 Correlation:     {:20.9}  {:20.5}",
+        energy.total(),
+        energy.total() * AU_EV
+    );
+    println!(
+        "SCS-MP2 Correlation: {:20.9}  {:20.5}",
+        e_scs,
+        e_scs * AU_EV
+    );
+    println!(
+        "SOS-MP2 Correlation: {:20.9}  {:20.5}\n",
+        energy.sos(),
+        energy.sos() * AU_EV
+    );
+    println!(
+        "Total MP2 Energy:    {:20.9}  {:20.5}",
+        e_scf + energy.total(),
+        (e_scf + energy.total()) * AU_EV
+    );
+    println!(
+        "Total SCS-MP2 Energy:{:20.9}  {:20.5}",
+        e_scf + e_scs,
+        (e_scf + e_scs) * AU_EV
+    );
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use libferric::gto_basis_sets::auxiliary::AuxiliaryBasis;
+use serde_yaml::Value;
+
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct MP2Input {
+    // keep core orbitals uncorrelated
+    pub frozen_core: bool,
+
+    // spin-component scaling (SCS-MP2 by default)
+    pub c_os: f64,
+    pub c_ss: f64,
+
+    // resolution of the identity with an auxiliary basis
+    pub ri: bool,
+    pub aux_basis: AuxiliaryBasis,
+
+    // half-transformed integrals on disk instead of in memory
+    pub disk_buffer: bool,
+}
+
+impl Default for MP2Input {
+    fn default() -> Self {
+        Self {
+            frozen_core: false,
+
+            c_os: 6.0 / 5.0,
+            c_ss: 1.0 / 3.0,
+
+            ri: false,
+            aux_basis: AuxiliaryBasis::EvenTempered,
+
+            disk_buffer: false,
+        }
+    }
+}
+
+impl MP2Input {
+    pub fn parse(input: &Value) -> MP2Input {
+        // initialise MP2Input with default values
+        let mut mp2 = MP2Input::default();
+
+        // an empty block uses the defaults
+        if input.is_null() {
+            return mp2;
+        }
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "frozencore" => mp2.frozen_core = value.as_bool().unwrap(),
+                "cos" => mp2.c_os = value.as_f64().unwrap(),
+                "css" => mp2.c_ss = value.as_f64().unwrap(),
+                "ri" => mp2.ri = value.as_bool().unwrap(),
+                // an auxiliary basis implies RI
+                "auxbasis" => {
+                    mp2.ri = true;
+                    mp2.aux_basis = AuxiliaryBasis::from_str(value.as_str().unwrap()).unwrap();
+                }
+                "diskbuffer" => mp2.disk_buffer = value.as_bool().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        // return MP2Input
+        mp2
+    }
+}
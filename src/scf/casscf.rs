@@ -0,0 +1,193 @@
+use super::{input::SCFInput, rhf::RHFSolver, solver::HFSolver, uhf::UHFSolver};
+
+use libferric::{
+    correlation::casscf::{CASSCFOptions, CASSCF},
+    data::orbitals::Orbitals,
+    geometry::Geometry,
+    gto_integrals::nuclear_repulsion::nuclear_repulsion,
+    linear_algebra::{
+        constants::AU_EV,
+        diagonalize::DiagonalizeSym,
+        orthogonalization::orthogonalizer,
+        {matrix::FMatrix, matrix_container::FMatrixContainer},
+    },
+    HFType,
+};
+
+pub struct CASSCFSolver {
+    // RHF (singlet) or UHF reference for the starting orbitals
+    reference: Box<dyn HFSolver>,
+    restricted: bool,
+
+    // active space
+    n_core: usize,
+    n_active: usize,
+    n_alpha: usize,
+    n_beta: usize,
+
+    casscf: Option<CASSCF>,
+    nuclear_repulsion: f64,
+
+    // overlap eigenvalue threshold of the orthogonalization
+    lindep: f64,
+}
+
+impl CASSCFSolver {
+    pub fn new(h: &FMatrix, geometry: &Geometry, input: SCFInput) -> Self {
+        let (n_electrons, n_active) = input.cas.expect("CASSCF requires an active space (cas)");
+        assert!(
+            n_electrons <= geometry.n_electrons
+                && (geometry.n_electrons - n_electrons).is_multiple_of(2),
+            "Invalid active space: {} electrons in a molecule with {} electrons",
+            n_electrons,
+            geometry.n_electrons
+        );
+        let n_core = (geometry.n_electrons - n_electrons) / 2;
+        assert!(
+            geometry.n_electrons_beta >= n_core,
+            "Invalid active space: the core holds more beta electrons than the molecule"
+        );
+
+        let restricted = geometry.multiplicity == 1;
+        let lindep = input.lindep;
+        let mut reference_input = input;
+        let reference: Box<dyn HFSolver> = match restricted {
+            true => {
+                reference_input.hf = HFType::RHF;
+                Box::new(RHFSolver::new(h, geometry, reference_input, None))
+            }
+            false => {
+                reference_input.hf = HFType::UHF;
+                Box::new(UHFSolver::new(
+                    &[h.clone(), h.clone()],
+                    geometry,
+                    reference_input,
+                    None,
+                ))
+            }
+        };
+
+        Self {
+            reference,
+            restricted,
+            n_core,
+            n_active,
+            n_alpha: geometry.n_electrons_alpha - n_core,
+            n_beta: geometry.n_electrons_beta - n_core,
+            casscf: None,
+            nuclear_repulsion: nuclear_repulsion(geometry.molecule.atoms()),
+            lindep,
+        }
+    }
+
+    /// Natural orbitals of the total UHF density, the eigenvectors of X^T S D S X
+    /// transformed back with the orthogonalization X, largest occupation first
+    fn unrestricted_natural_orbitals(orbitals: &Orbitals, s: &FMatrix, lindep: f64) -> FMatrix {
+        let d = orbitals.density(0) + orbitals.density(1);
+        let (x, _) = orthogonalizer(s, lindep);
+        let sx = s * &x;
+        let (_, u) = (sx.transposed() * &(&d * &sx) * -1.0).diagonalize_sym();
+        x * u
+    }
+}
+
+impl HFSolver for CASSCFSolver {
+    fn solve(&mut self, basename: &str, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix) {
+        self.reference.solve(basename, h, eri, s);
+
+        let orbitals = self.reference.orbitals();
+        let c = match self.restricted {
+            true => orbitals.c(0).clone(),
+            false => Self::unrestricted_natural_orbitals(&orbitals, s, self.lindep),
+        };
+
+        println!("------");
+        println!("CASSCF");
+        println!("------\n");
+        println!(
+            "Starting Orbitals:     {}",
+            if self.restricted {
+                "RHF"
+            } else {
+                "UHF natural orbitals"
+            }
+        );
+        let mut casscf = CASSCF::new(&c, self.n_core, self.n_active, self.n_alpha, self.n_beta);
+        casscf.solve(h, eri, &CASSCFOptions::default());
+        self.casscf = Some(casscf);
+
+        self.print_energy(h);
+    }
+
+    fn guess(&mut self, basename: &str) {
+        self.reference.guess(basename);
+    }
+
+    fn density(&mut self, x: &FMatrix) {
+        self.reference.density(x);
+    }
+
+    fn fock(&mut self, h: &FMatrix, eri: &FMatrixContainer) {
+        self.reference.fock(h, eri);
+    }
+
+    fn energy(&mut self, h: &FMatrix) {
+        self.reference.energy(h);
+    }
+
+    fn print_energy(&self, _h: &FMatrix) {
+        let casscf = self.casscf.as_ref().expect("CASSCF not solved");
+        let e_casci = casscf.e_casci() + self.nuclear_repulsion;
+        let e_casscf = self.total_energy();
+
+        println!("\n-------------------");
+        println!("Total CASSCF Energy");
+        println!("-------------------\n");
+        println!("                          {:^20}  {:^20}", "Hartree", "eV");
+        println!(
+            "Total Energy:        {:20.9}  {:20.5}\n",
+            e_casscf,
+            e_casscf * AU_EV
+        );
+        println!("Components:");
+        println!(
+            "Nuclear Repulsion:   {:20.9}  {:20.5}",
+            self.nuclear_repulsion,
+            self.nuclear_repulsion * AU_EV
+        );
+        println!(
+            "Electronic Energy:   {:20.9}  {:20.5}",
+            casscf.e_casscf(),
+            casscf.e_casscf() * AU_EV
+        );
+        println!(
+            "Reference Energy:    {:20.9}  {:20.5}",
+            self.reference.total_energy(),
+            self.reference.total_energy() * AU_EV
+        );
+        println!(
+            "CASCI Energy:        {:20.9}  {:20.5}",
+            e_casci,
+            e_casci * AU_EV
+        );
+        println!("<S^2>:               {:20.6}", casscf.s2());
+
+        println!("\n\n--------------------------");
+        println!("Active Natural Occupations");
+        println!("--------------------------\n");
+        let occupations = casscf.occupations();
+        for t in self.n_core..self.n_core + self.n_active {
+            println!("{:4} {:12.6}", t + 1, occupations[t]);
+        }
+        println!();
+    }
+
+    fn total_energy(&self) -> f64 {
+        let casscf = self.casscf.as_ref().expect("CASSCF not solved");
+        casscf.e_casscf() + self.nuclear_repulsion
+    }
+
+    fn orbitals(&self) -> Orbitals {
+        self.casscf.as_ref().expect("CASSCF not solved").orbitals()
+    }
+}
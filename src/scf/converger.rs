@@ -0,0 +1,84 @@
+use super::input::SCFInput;
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// SCF converger: DIIS extrapolation of the Fock matrix, second-order trust-region
+/// steps (TRAH), or DIIS that hands over to TRAH once it stalls
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Converger {
+    #[default]
+    DIIS,
+    TRAH,
+    Auto,
+}
+
+impl FromStr for Converger {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let converger = match s.to_uppercase().as_str() {
+            "DIIS" => Converger::DIIS,
+            "TRAH" | "SOSCF" => Converger::TRAH,
+            "AUTO" => Converger::Auto,
+            _ => return Err("Unknown converger"),
+        };
+
+        Ok(converger)
+    }
+}
+
+impl Display for Converger {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Converger::DIIS => write!(f, "DIIS"),
+            Converger::TRAH => write!(f, "TRAH"),
+            Converger::Auto => write!(f, "DIIS -> TRAH"),
+        }
+    }
+}
+
+/// Decides when the trust-region converger takes over from the DIIS iterations
+pub struct Switch {
+    converger: Converger,
+    iter_start: usize,
+    stall: usize,
+
+    best_rms: f64,
+    since_best: usize,
+}
+
+impl Switch {
+    pub fn new(input: &SCFInput) -> Self {
+        Self {
+            converger: input.converger,
+            iter_start: input.diis_iter_start,
+            stall: input.trah_stall,
+            best_rms: f64::MAX,
+            since_best: 0,
+        }
+    }
+
+    /// True once TRAH should take over. TRAH alone starts from the first DIIS iteration,
+    /// as second-order steps from the core guess may end in a non-aufbau minimum. Auto
+    /// switches when the density change has not reached a new minimum for `trah_stall`
+    /// iterations.
+    pub fn update(&mut self, iter: usize, rms: f64) -> bool {
+        match self.converger {
+            Converger::DIIS => false,
+            Converger::TRAH => iter >= self.iter_start,
+            Converger::Auto => {
+                if rms < self.best_rms {
+                    self.best_rms = rms;
+                    self.since_best = 0;
+                } else {
+                    self.since_best += 1;
+                }
+                iter >= self.iter_start && self.since_best >= self.stall
+            }
+        }
+    }
+}
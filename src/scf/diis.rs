@@ -1,50 +1,103 @@
-use libferric::linear_algebra::{
-    linear_solve::LinearSolve, matrix::FMatrix, traits::Dot, vector::FVector,
+use super::input::SCFInput;
+
+use libferric::{
+    linear_algebra::{self, matrix::FMatrix},
+    scf::ediis::{adiis, blend, ediis},
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
 };
 
-use std::cmp::Ordering;
+/// Extrapolation scheme. EDIIS and ADIIS are used for the early iterations and blended
+/// into commutator DIIS (CDIIS) as the error decreases, K. N. Kudin, G. E. Scuseria and
+/// E. Cancès, J. Chem. Phys. 116, 8255 (2002); X. Hu and W. Yang, J. Chem. Phys. 132,
+/// 054109 (2010); A. J. Garza and G. E. Scuseria, J. Chem. Phys. 137, 054110 (2012).
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DIISType {
+    #[default]
+    CDIIS,
+    EDIIS,
+    ADIIS,
+}
+
+impl FromStr for DIISType {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let diis = match s.to_uppercase().as_str() {
+            "DIIS" | "CDIIS" => DIISType::CDIIS,
+            "EDIIS" => DIISType::EDIIS,
+            "ADIIS" => DIISType::ADIIS,
+            _ => return Err("Unknown DIIS type"),
+        };
+
+        Ok(diis)
+    }
+}
+
+impl Display for DIISType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DIISType::CDIIS => write!(f, "DIIS"),
+            DIISType::EDIIS => write!(f, "EDIIS/DIIS"),
+            DIISType::ADIIS => write!(f, "ADIIS/DIIS"),
+        }
+    }
+}
 
 pub struct DIIS {
+    kind: DIISType,
+    // error matrices in the orthonormal basis X^T [F,P] X
+    orthogonal: bool,
+
     dim_max: usize,
-    dim: usize,
     pub iter_start: usize,
-    iter_current: usize,
 
     s: FMatrix,
-    s12: FMatrix,
+    x: FMatrix,
 
     pub damp_factor: f64,
     error_max: f64,
 
     active: bool,
 
-    error: Vec<FMatrix>,
-    fock: Vec<FMatrix>,
+    // Fock matrices and errors of all spins, flattened into one vector per iteration
+    cdiis: linear_algebra::diis::DIIS,
+    // densities of all spins and energies belonging to the stored Fock matrices
+    density: Vec<Vec<f64>>,
+    energy: Vec<f64>,
 }
 
 impl DIIS {
-    pub fn new(dim_max: usize, iter_start: usize, s: &FMatrix, s12: &FMatrix) -> Self {
+    pub fn new(input: &SCFInput, s: &FMatrix, x: &FMatrix) -> Self {
         Self {
-            dim_max,
-            dim: 0,
-            iter_start,
-            iter_current: 0,
+            kind: input.diis_type,
+            // the AO error does not vanish in the directions removed from the orbital space
+            orthogonal: input.diis_orthogonal || x.cols < x.rows,
+            dim_max: input.diis_dim_max,
+            iter_start: input.diis_iter_start,
             s: s.clone(),
-            s12: s12.clone(),
-            damp_factor: 0.7,
+            x: x.clone(),
+            damp_factor: input.damping,
             error_max: f64::MAX,
             active: false,
-            error: vec![FMatrix::default(); dim_max],
-            fock: vec![FMatrix::default(); dim_max],
+            cdiis: linear_algebra::diis::DIIS::new(input.diis_dim_max),
+            density: Vec::new(),
+            energy: Vec::new(),
         }
     }
 
-    pub fn do_diis(&mut self, f: &mut FMatrix, p: &FMatrix, iter: usize) {
-        // don't use DIIS if dim = 0
-        if self.dim_max == 0 {
-            return;
-        }
+    /// Largest element of the last error matrices
+    pub fn error_max(&self) -> f64 {
+        self.error_max
+    }
 
+    /// Replaces the Fock matrices of every spin by the extrapolation from previous
+    /// iterations. Densities and energy belong to the Fock matrices on input.
+    pub fn do_diis(&mut self, f: &mut [FMatrix], p: &[FMatrix], energy: f64, iter: usize) {
         // damping becomes counter-productive towards convergence
         let damp_error = 0.1;
         if self.active && iter >= self.iter_start && self.error_max < damp_error {
@@ -52,58 +105,64 @@ impl DIIS {
         }
 
         // calculate error
-        let error = self.calc_error_matrix(f, p);
+        let error: Vec<f64> = f
+            .iter()
+            .zip(p)
+            .flat_map(|(f, p)| self.calc_error_matrix(f, p).to_vec())
+            .collect();
 
         // determine max error
-        self.error_max = *error
-            .iter()
-            .max_by(|a, b| a.abs().total_cmp(&b.abs()))
-            .unwrap();
+        self.error_max = error.iter().fold(0.0, |max: f64, e| max.max(e.abs()));
+
+        // don't use DIIS if dim = 0
+        if self.dim_max == 0 {
+            return;
+        }
 
         // check start of DIIS
         if !self.active && iter == self.iter_start {
             self.active = true;
-            println!("{:^60}", "*** Turning on AO-DIIS ***");
+            let basis = if self.orthogonal { "" } else { "AO-" };
+            let message = format!("*** Turning on {}{} ***", basis, self.kind);
+            println!("{:^60}", message);
         }
 
-        // in any case store the Fock matrix
-        if self.dim < self.dim_max {
-            self.dim += 1;
+        // in any case store Fock, density and error matrices
+        let flatten = |m: &[FMatrix]| -> Vec<f64> { m.iter().flat_map(|m| m.to_vec()).collect() };
+        if self.energy.len() == self.dim_max {
+            self.density.remove(0);
+            self.energy.remove(0);
         }
-
-        // store Fock and error matrix
-        let pos = iter % self.dim_max;
-        self.fock[pos] = f.clone();
-        self.error[pos] = error;
+        self.cdiis.push(&flatten(f), &error);
+        self.density.push(flatten(p));
+        self.energy.push(energy);
 
         // don't extrapolate if DIIS hasn't been activated yet
         if !self.active {
             return;
         }
 
-        // form DIIS matrix
-        let dim = self.dim + 1;
-        let mut b = FMatrix::new_with_value(dim, dim, -1.0);
-        b[(self.dim, self.dim)] = 0.0;
-        for i in 0..self.dim {
-            for j in 0..=i {
-                let bij = self.error[i].dot(&self.error[j]);
-                b[(i, j)] = bij;
-                if i != j {
-                    b[(j, i)] = bij;
-                }
+        // blend energy-based and commutator coefficients by the size of the error
+        let coefficients = match self.kind {
+            DIISType::CDIIS => self.cdiis.coefficients(),
+            DIISType::EDIIS | DIISType::ADIIS => {
+                let fock = self.cdiis.parameters();
+                let energy = match self.kind {
+                    DIISType::EDIIS => ediis(&self.energy, &self.density, fock),
+                    _ => adiis(&self.density, fock),
+                };
+                blend(&energy, &self.cdiis.coefficients(), self.error_max)
             }
-        }
-
-        // solve linear equation
-        let mut rhs = FVector::zero(dim);
-        rhs[self.dim] = -1.0;
-        b.linear_solve_mut(&mut rhs);
+        };
 
         // construct new guess: F' = \sum_i c_i F_i
-        f.init(0.0);
-        for i in 0..self.dim {
-            *f += rhs[i] * &self.fock[i];
+        let mut fock = flatten(f);
+        self.cdiis.combine(&coefficients, &mut fock);
+        let mut offset = 0;
+        for f in f.iter_mut() {
+            let len = f.len();
+            f.copy_from_slice(&fock[offset..offset + len]);
+            offset += len;
         }
     }
 
@@ -111,8 +170,9 @@ impl DIIS {
     fn calc_error_matrix(&self, f: &FMatrix, p: &FMatrix) -> FMatrix {
         let mut error = f * (p * &self.s);
         error -= &self.s * (p * f);
-        // why do I transform this to an orthonormal basis?
-        // &self.s12 * error * &self.s12
-        error
+        match self.orthogonal {
+            true => self.x.transposed() * error * &self.x,
+            false => error,
+        }
     }
 }
@@ -1,19 +1,40 @@
-use super::{input::SCFInput, rhf::RHFSolver, solver::HFSolver, uhf::UHFSolver};
+use super::{
+    casscf::CASSCFSolver,
+    converger::Converger,
+    input::{DFTInput, SCFInput},
+    ks::KohnSham,
+    rhf::RHFSolver,
+    solver::HFSolver,
+    uhf::UHFSolver,
+};
 
 use libferric::{
+    data::orbitals::Orbitals,
+    export::{molden::store_molden, mwfn::store_mwfn, wfx::store_wfx},
     geometry::Geometry,
     gto_basis_sets::basis::Basis,
     gto_integrals::{
         integral_interface::IntegralInterface, one_electron::OneElectronKernel,
         two_electron::TwoElectronKernel,
     },
-    linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer},
-    HFType::{RHF, UHF},
+    linear_algebra::{
+        constants::AU_EV, matrix::FMatrix, matrix_container::FMatrixContainer,
+        orthogonalization::orthogonalizer, traits::Dot,
+    },
+    response::stability::{follow, stability, StabilityOptions},
+    HFType::{CASSCF, RHF, UHF},
 };
 
 use std::error;
 
-pub fn driver(basename: &str, scf_input: SCFInput) -> Result<(), Box<dyn error::Error>> {
+/// largest number of SCF restarts along unstable modes
+const MAX_FOLLOW: usize = 5;
+
+pub fn driver(
+    basename: &str,
+    scf_input: SCFInput,
+    dft_input: Option<DFTInput>,
+) -> Result<f64, Box<dyn error::Error>> {
     println!(
         r#"
 
@@ -57,33 +78,349 @@ pub fn driver(basename: &str, scf_input: SCFInput) -> Result<(), Box<dyn error::
                 geometry.n_electrons_beta
             );
         }
+        CASSCF => {
+            println!("Number of Electrons:              {}", geometry.n_electrons);
+            if let Some((n_electrons, n_orbitals)) = scf_input.cas {
+                println!(
+                    "Active Space:                     CAS({}, {})",
+                    n_electrons, n_orbitals
+                );
+            }
+        }
         _ => panic!("Unsupported HF Type: {}", scf_input.hf),
     }
     println!("Basis Dimension:                  {}", basis.dim());
 
+    // near-linear dependencies are removed by canonical orthogonalization
+    let s = FMatrix::retrieve(OneElectronKernel::Overlap.to_filename());
+    let (x, removed) = orthogonalizer(&s, scf_input.lindep);
+    if !removed.is_empty() {
+        println!(
+            "Linear Dependencies:              {} functions removed (S eigenvalues < {:.1e})",
+            removed.len(),
+            scf_input.lindep
+        );
+        for eigenvalue in &removed {
+            println!("  Removed Eigenvalue:             {:.3e}", eigenvalue);
+        }
+        println!("Orbital Space Dimension:          {}", x.cols);
+    }
+
+    // exchange-correlation functional and integration grid
+    let ks = dft_input
+        .as_ref()
+        .map(|dft| KohnSham::new(dft, &basis, &geometry));
+    if let (Some(dft), Some(ks)) = (&dft_input, &ks) {
+        println!("\nKohn-Sham DFT\n-------------");
+        println!("Functional:            {}", ks.functional());
+        println!("Exact Exchange:        {:5.3}", ks.exact_exchange());
+        println!("Radial Grid:           {}", dft.grid.radial_scheme);
+        println!(
+            "Grid Size:             {} x {} ({})",
+            dft.grid.n_radial,
+            dft.grid.n_angular,
+            if dft.grid.pruning {
+                "pruned"
+            } else {
+                "unpruned"
+            }
+        );
+        println!("Partitioning:          {}", dft.grid.partition);
+        println!("Grid Points:           {}", ks.grid().len());
+    }
+
     println!("\nConverger\n---------");
     println!("Maximum Iterations:    {}", scf_input.max_iter);
-    println!("Acceleration:          DIIS");
+    println!("Acceleration:          {}", scf_input.converger);
+    if scf_input.converger != Converger::TRAH {
+        println!(
+            "DIIS:                  {} ({} error)",
+            scf_input.diis_type,
+            if scf_input.diis_orthogonal || !removed.is_empty() {
+                "orthogonal"
+            } else {
+                "AO"
+            }
+        );
+    }
+    if scf_input.damping > 0.0 {
+        println!("Damping:               {:5.3}", scf_input.damping);
+    }
+    if scf_input.level_shift > 0.0 {
+        println!("Level Shift:           {:5.3} Eh", scf_input.level_shift);
+    }
+    if scf_input.smearing > 0.0 {
+        assert!(
+            scf_input.converger == Converger::DIIS,
+            "Fermi smearing requires the DIIS converger"
+        );
+        // the shift of the unoccupied orbital energies would enter the occupations
+        assert!(
+            scf_input.level_shift == 0.0,
+            "Fermi smearing cannot be combined with a level shift"
+        );
+        println!("Fermi Smearing:        {:.1} K", scf_input.smearing);
+    }
+    if scf_input.converger != Converger::DIIS {
+        println!("Trust Radius:          {:5.3}", scf_input.trah_radius);
+    }
+    if scf_input.converger == Converger::Auto {
+        println!("Switch after Stall:    {} iterations", scf_input.trah_stall);
+    }
+
+    if !scf_input.excitations.is_empty() {
+        assert!(
+            scf_input.hf != CASSCF,
+            "ΔSCF is only available for RHF and UHF"
+        );
+        assert!(
+            scf_input.converger == Converger::DIIS && scf_input.smearing == 0.0,
+            "ΔSCF requires the DIIS converger without smearing"
+        );
+        println!("\nΔSCF (Maximum Overlap Method)\n-----------------------------");
+        for excitation in &scf_input.excitations {
+            println!("Excitation:            {}", excitation);
+        }
+    }
 
     println!("\nConvergence Thresholds\n----------------------");
     println!("Energy Change:         {:5.3e}", scf_input.e_threshold);
     println!("RMS:                   {:5.3e}", scf_input.rms_threshold);
 
+    let check_stability = scf_input.stability || scf_input.follow_instability;
+    if check_stability {
+        assert!(
+            dft_input.is_none() && scf_input.hf != CASSCF,
+            "Stability analysis is only available for RHF and UHF"
+        );
+        println!("\nStability Analysis\n------------------");
+        println!(
+            "Follow Instabilities:  {}",
+            if scf_input.follow_instability {
+                "yes"
+            } else {
+                "no"
+            }
+        );
+    }
+
     // read integrals from disk
     let h = FMatrix::retrieve(OneElectronKernel::HCore.to_filename());
-    let s = FMatrix::retrieve(OneElectronKernel::Overlap.to_filename());
     let eri = FMatrixContainer::retrieve(TwoElectronKernel::ERI.to_filename());
 
-    let mut solver = set_solver(scf_input.clone(), &h, &geometry);
-    solver.solve(&h, &eri, &s);
+    // ground state, the reference of the occupation changes
+    let mut ground_input = scf_input.clone();
+    ground_input.excitations.clear();
+    let mut solver = set_solver(ground_input.clone(), &h, &geometry, ks);
+    solver.solve(basename, &h, &eri, &s);
+
+    if check_stability {
+        solver = stability_analysis(basename, solver, &ground_input, &h, &s, &eri, &geometry);
+    }
+
+    if !scf_input.excitations.is_empty() {
+        let e_ground = solver.total_energy();
+        let input = excite(basename, &solver.orbitals(), &scf_input)?;
+        println!("\nRestarting {} with changed occupations\n", input.hf);
+        let ks = dft_input
+            .as_ref()
+            .map(|dft| KohnSham::new(dft, &basis, &geometry));
+        solver = set_solver(input, &h, &geometry, ks);
+        solver.solve(basename, &h, &eri, &s);
+
+        let delta = solver.total_energy() - e_ground;
+        println!("\nΔSCF Excitation Energy");
+        println!("----------------------");
+        println!("Ground State:          {:.9} Eh", e_ground);
+        println!("Excited State:         {:.9} Eh", solver.total_energy());
+        println!(
+            "ΔE:                    {:.9} Eh = {:.4} eV",
+            delta,
+            delta * AU_EV
+        );
+    }
+
+    // store final orbitals for subsequent modules and visualization
+    let orbitals = solver.orbitals();
+    orbitals.store(basename);
+    store_molden(basename, geometry.molecule.atoms(), &basis, &orbitals);
+    println!("\nOrbitals written to {basename}.molden");
 
-    Ok(())
+    // virial ratio -V/T from the kinetic energy of the final density
+    let t = FMatrix::retrieve(OneElectronKernel::Kinetic.to_filename());
+    let e_kin: f64 = (0..orbitals.num_op())
+        .map(|op| orbitals.density(op).dot(&t))
+        .sum();
+    let e_tot = solver.total_energy();
+    let virial_ratio = -(e_tot - e_kin) / e_kin;
+
+    store_wfx(basename, &geometry, &basis, &orbitals, e_tot, virial_ratio);
+    store_mwfn(basename, &geometry, &basis, &orbitals, e_tot, virial_ratio);
+    println!("Wave function written to {basename}.wfx and {basename}.mwfn");
+
+    Ok(e_tot)
+}
+
+/// Lowest eigenvalues of the stability matrices, optionally restarting the SCF from
+/// orbitals rotated along the most unstable mode until the solution is stable
+fn stability_analysis(
+    basename: &str,
+    mut solver: Box<dyn HFSolver>,
+    scf_input: &SCFInput,
+    h: &FMatrix,
+    s: &FMatrix,
+    eri: &FMatrixContainer,
+    geometry: &Geometry,
+) -> Box<dyn HFSolver> {
+    for cycle in 0..=MAX_FOLLOW {
+        println!("\n------------------");
+        println!("Stability Analysis");
+        println!("------------------\n");
+        let orbitals = solver.orbitals();
+        let roots = stability(&orbitals, eri, &StabilityOptions::default());
+
+        println!(
+            "{:^12} {:>4} {:>16}  {:<8}",
+            "Rotation", "Root", "Eigenvalue", "Status"
+        );
+        let mut index = 0;
+        for (k, root) in roots.iter().enumerate() {
+            index = match k > 0 && roots[k - 1].kind == root.kind {
+                true => index + 1,
+                false => 1,
+            };
+            println!(
+                "{:^12} {:4} {:16.9}  {}",
+                root.kind,
+                index,
+                root.eigenvalue,
+                if root.unstable() {
+                    "unstable"
+                } else {
+                    "stable"
+                }
+            );
+        }
+
+        let unstable = roots
+            .iter()
+            .filter(|root| root.unstable())
+            .min_by(|a, b| a.eigenvalue.total_cmp(&b.eigenvalue));
+        let root = match unstable {
+            None => {
+                println!("\nThe wave function is stable");
+                break;
+            }
+            Some(root) => root,
+        };
+        println!("\nThe wave function has a {} instability", root.kind);
+        if !scf_input.follow_instability {
+            break;
+        }
+        if cycle == MAX_FOLLOW {
+            println!(
+                "Warning: wave function still unstable after {} restarts",
+                MAX_FOLLOW
+            );
+            break;
+        }
+
+        // restart from the rotated orbitals
+        let (rotated, _) = follow(&orbitals, root, h, eri);
+        let mut input = scf_input.clone();
+        match rotated.restricted() {
+            true => {
+                input.hf = RHF;
+                rotated.density(0).store(&format!("{basename}.p.tmp"));
+            }
+            false => {
+                input.hf = UHF;
+                (0..2).for_each(|op| rotated.density(op).store(&format!("{basename}.p{op}.tmp")));
+            }
+        }
+        println!("Restarting {} from the rotated orbitals\n", input.hf);
+        solver = set_solver(input, h, geometry, None);
+        solver.solve(basename, h, eri, s);
+    }
+
+    solver
+}
+
+/// Applies the ΔSCF occupation changes to the ground-state orbitals and stores the
+/// resulting density as guess. Spin-specific changes of a restricted ground state
+/// continue unrestricted. Returns the input of the excited-state SCF.
+fn excite(
+    basename: &str,
+    ground: &Orbitals,
+    scf_input: &SCFInput,
+) -> Result<SCFInput, Box<dyn error::Error>> {
+    let mut input = scf_input.clone();
+    let restricted = ground.restricted() && scf_input.excitations.iter().all(|x| x.spin.is_none());
+    let mut orbitals = match restricted || !ground.restricted() {
+        true => ground.clone(),
+        false => {
+            let (c, e, homo) = (ground.c(0), ground.e(0), ground.homo(0));
+            Orbitals::new_unrestricted([c.clone(), c.clone()], [e.clone(), e.clone()], [homo; 2])
+        }
+    };
+
+    // electrons of a spin, or an electron pair of restricted orbitals
+    let electrons = if restricted { 2.0 } else { 1.0 };
+    for excitation in &scf_input.excitations {
+        let spins = match excitation.spin {
+            Some(op) => vec![op],
+            None => (0..orbitals.num_op()).collect(),
+        };
+        for op in spins {
+            let homo = orbitals.homo(op);
+            let (Some(from), Some(to)) = (excitation.from.index(homo), excitation.to.index(homo))
+            else {
+                return Err(format!("Excitation {} without occupied orbitals", excitation).into());
+            };
+            let mut on = orbitals.on(op).clone();
+            if from >= on.len() || on[from] < electrons {
+                return Err(format!("Excitation {} from an unoccupied orbital", excitation).into());
+            }
+            if to >= on.len() || on[to] != 0.0 {
+                return Err(format!("Excitation {} into an occupied orbital", excitation).into());
+            }
+            on[from] -= electrons;
+            on[to] += electrons;
+            orbitals.set_occupations(op, on);
+        }
+    }
+
+    match restricted {
+        true => {
+            input.hf = RHF;
+            orbitals.density(0).store(&format!("{basename}.p.tmp"));
+        }
+        false => {
+            input.hf = UHF;
+            (0..2).for_each(|op| orbitals.density(op).store(&format!("{basename}.p{op}.tmp")));
+        }
+    }
+    Ok(input)
 }
 
-fn set_solver(scf_input: SCFInput, h: &FMatrix, geometry: &Geometry) -> Box<dyn HFSolver> {
+fn set_solver(
+    scf_input: SCFInput,
+    h: &FMatrix,
+    geometry: &Geometry,
+    ks: Option<KohnSham>,
+) -> Box<dyn HFSolver> {
     match scf_input.hf {
-        RHF => Box::new(RHFSolver::new(h, geometry, scf_input)),
-        UHF => Box::new(UHFSolver::new(&[h.clone(), h.clone()], geometry, scf_input)),
+        RHF => Box::new(RHFSolver::new(h, geometry, scf_input, ks)),
+        UHF => Box::new(UHFSolver::new(
+            &[h.clone(), h.clone()],
+            geometry,
+            scf_input,
+            ks,
+        )),
+        CASSCF => {
+            assert!(ks.is_none(), "CASSCF is not available with Kohn-Sham DFT");
+            Box::new(CASSCFSolver::new(h, geometry, scf_input))
+        }
         _ => panic!("{} not implemented", scf_input.hf),
     }
 }
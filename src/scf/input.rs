@@ -1,6 +1,13 @@
 use std::str::FromStr;
 
-use libferric::HFType;
+use super::{converger::Converger, diis::DIISType};
+
+use libferric::{
+    dft::functional::Functional,
+    grid::{partition::Partition, radial::RadialScheme, GridSettings},
+    scf::occupation::{Excitation, Orbital},
+    HFType,
+};
 use serde_yaml::Value;
 
 #[derive(Clone)]
@@ -15,9 +22,38 @@ pub struct SCFInput {
     // iterations
     pub max_iter: usize,
 
+    // smallest eigenvalue of the overlap kept by canonical orthogonalization
+    pub lindep: f64,
+
     // diis
     pub diis_iter_start: usize,
     pub diis_dim_max: usize,
+    pub diis_type: DIISType,
+    pub diis_orthogonal: bool,
+
+    // density damping D = (1 - α) D_new + α D_old until DIIS has converged far enough
+    pub damping: f64,
+
+    // level shift of the virtual orbitals (Hartree) and electronic temperature (K) of
+    // Fermi-Dirac smearing
+    pub level_shift: f64,
+    pub smearing: f64,
+
+    // ΔSCF occupation changes, kept by the maximum overlap method
+    pub excitations: Vec<Excitation>,
+
+    // second-order trust-region converger: iterations without progress before Auto
+    // switches from DIIS and the initial trust radius
+    pub converger: Converger,
+    pub trah_stall: usize,
+    pub trah_radius: f64,
+
+    // CASSCF active space: (number of electrons, number of orbitals)
+    pub cas: Option<(usize, usize)>,
+
+    // stability analysis of the converged wave function
+    pub stability: bool,
+    pub follow_instability: bool,
 }
 
 impl Default for SCFInput {
@@ -30,8 +66,28 @@ impl Default for SCFInput {
 
             max_iter: 40,
 
+            lindep: 1e-7,
+
             diis_iter_start: 2,
             diis_dim_max: 6,
+            diis_type: DIISType::CDIIS,
+            diis_orthogonal: false,
+
+            damping: 0.0,
+
+            level_shift: 0.0,
+            smearing: 0.0,
+
+            excitations: Vec::new(),
+
+            converger: Converger::DIIS,
+            trah_stall: 5,
+            trah_radius: 0.5,
+
+            cas: None,
+
+            stability: false,
+            follow_instability: false,
         }
     }
 }
@@ -46,10 +102,45 @@ impl SCFInput {
             match key.as_str().unwrap().to_lowercase().as_str() {
                 "hf" => scf.hf = HFType::from_str(value.as_str().unwrap()).unwrap(),
                 "maxiter" => scf.max_iter = value.as_u64().unwrap() as usize,
+                "lindep" => scf.lindep = value.as_f64().unwrap(),
                 "diisiterstart" => scf.diis_iter_start = value.as_u64().unwrap() as usize,
                 "diisdimmax" => scf.diis_dim_max = value.as_u64().unwrap() as usize,
+                "converger" => {
+                    scf.converger = Converger::from_str(value.as_str().unwrap()).unwrap()
+                }
+                "trahstall" => scf.trah_stall = value.as_u64().unwrap() as usize,
+                "trahradius" => scf.trah_radius = value.as_f64().unwrap(),
+                "diistype" => scf.diis_type = DIISType::from_str(value.as_str().unwrap()).unwrap(),
+                "diisorthogonal" => scf.diis_orthogonal = value.as_bool().unwrap(),
+                "damping" => scf.damping = value.as_f64().unwrap(),
+                "levelshift" => scf.level_shift = value.as_f64().unwrap(),
+                "smearing" => scf.smearing = value.as_f64().unwrap(),
+                "excitations" => {
+                    scf.excitations = value
+                        .as_sequence()
+                        .expect("excitations: list of [spin, from, to] or [from, to]")
+                        .iter()
+                        .map(parse_excitation)
+                        .collect()
+                }
                 "thresholde" => scf.e_threshold = value.as_f64().unwrap(),
                 "thresholdrms" => scf.rms_threshold = value.as_f64().unwrap(),
+                "cas" => {
+                    let cas: Vec<usize> = value
+                        .as_sequence()
+                        .expect("cas: [number of electrons, number of orbitals]")
+                        .iter()
+                        .map(|x| x.as_u64().unwrap() as usize)
+                        .collect();
+                    assert_eq!(
+                        cas.len(),
+                        2,
+                        "cas: [number of electrons, number of orbitals]"
+                    );
+                    scf.cas = Some((cas[0], cas[1]));
+                }
+                "stability" => scf.stability = value.as_bool().unwrap(),
+                "followinstability" => scf.follow_instability = value.as_bool().unwrap(),
                 _ => panic!("Unknown option: {:?}", key),
             }
         }
@@ -58,3 +149,99 @@ impl SCFInput {
         scf
     }
 }
+
+/// Occupation change [spin, from, to], or [from, to] for both spins. Orbitals are numbered
+/// from 1 or named HOMO and LUMO.
+fn parse_excitation(value: &Value) -> Excitation {
+    let usage = "excitation: [spin, from, to] or [from, to]";
+    let items = value.as_sequence().expect(usage);
+    let orbital = |value: &Value| match value {
+        Value::Number(n) => {
+            let n = n.as_u64().expect(usage) as usize;
+            assert!(n > 0, "Orbitals are numbered from 1");
+            Orbital::Number(n)
+        }
+        Value::String(name) => match name.to_uppercase().as_str() {
+            "HOMO" => Orbital::HOMO,
+            "LUMO" => Orbital::LUMO,
+            _ => panic!("Unknown orbital: {}", name),
+        },
+        _ => panic!("{}", usage),
+    };
+
+    match items.len() {
+        2 => Excitation {
+            spin: None,
+            from: orbital(&items[0]),
+            to: orbital(&items[1]),
+        },
+        3 => {
+            let spin = match items[0].as_str().expect(usage).to_uppercase().as_str() {
+                "ALPHA" | "A" => 0,
+                "BETA" | "B" => 1,
+                _ => panic!("Unknown spin: {:?}", items[0]),
+            };
+            Excitation {
+                spin: Some(spin),
+                from: orbital(&items[1]),
+                to: orbital(&items[2]),
+            }
+        }
+        _ => panic!("{}", usage),
+    }
+}
+
+#[derive(Clone)]
+pub struct DFTInput {
+    // exchange-correlation functional
+    pub functional: Functional,
+
+    // integration grid
+    pub grid: GridSettings,
+}
+
+impl Default for DFTInput {
+    fn default() -> Self {
+        Self {
+            functional: Functional::PBE,
+
+            grid: GridSettings::default(),
+        }
+    }
+}
+
+impl DFTInput {
+    pub fn parse(input: &Value) -> DFTInput {
+        // initialise DFTInput with default values
+        let mut dft = DFTInput::default();
+
+        // a plain string only selects the functional
+        if let Value::String(functional) = input {
+            dft.functional = Functional::from_str(functional).unwrap();
+            return dft;
+        }
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "functional" => {
+                    dft.functional = Functional::from_str(value.as_str().unwrap()).unwrap()
+                }
+                "radial" => dft.grid.n_radial = value.as_u64().unwrap() as usize,
+                "angular" => dft.grid.n_angular = value.as_u64().unwrap() as usize,
+                "radialgrid" => {
+                    dft.grid.radial_scheme =
+                        RadialScheme::from_str(value.as_str().unwrap()).unwrap()
+                }
+                "partition" => {
+                    dft.grid.partition = Partition::from_str(value.as_str().unwrap()).unwrap()
+                }
+                "pruning" => dft.grid.pruning = value.as_bool().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        // return DFTInput
+        dft
+    }
+}
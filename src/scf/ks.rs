@@ -0,0 +1,73 @@
+use super::input::DFTInput;
+
+use libferric::{
+    dft::{functional::Functional, xc::xc},
+    geometry::Geometry,
+    grid::MolecularGrid,
+    gto_basis_sets::basis::Basis,
+    linear_algebra::{matrix::FMatrix, traits::Dot},
+};
+
+/// Exchange-correlation part of a Kohn-Sham calculation, added on top of the
+/// (scaled) Hartree-Fock Fock matrix
+pub struct KohnSham {
+    functional: Functional,
+    basis: Basis,
+    grid: MolecularGrid,
+
+    // results of the last evaluation
+    e_xc: f64,
+    v_xc: Vec<FMatrix>,
+}
+
+impl KohnSham {
+    pub fn new(input: &DFTInput, basis: &Basis, geometry: &Geometry) -> Self {
+        let grid = MolecularGrid::new(geometry, &input.grid);
+
+        Self {
+            functional: input.functional,
+            basis: basis.clone(),
+            grid,
+            e_xc: 0.0,
+            v_xc: Vec::new(),
+        }
+    }
+
+    /// Evaluate the exchange-correlation energy and potential for the given density,
+    /// the total density for restricted and (Dα, Dβ) for unrestricted calculations
+    pub fn evaluate(&mut self, d: &[FMatrix]) -> &[FMatrix] {
+        (self.e_xc, self.v_xc) = xc(&self.basis, &self.grid, &self.functional, d);
+        &self.v_xc
+    }
+
+    /// Energy correction to 0.5 \sum_{μν} Dμν ( Hμν + Fμν ) for a Fock matrix which
+    /// includes the exchange-correlation potential
+    pub fn energy_correction(&self, d: &[FMatrix]) -> f64 {
+        self.e_xc
+            - 0.5
+                * d.iter()
+                    .zip(&self.v_xc)
+                    .map(|(d, v_xc)| d.dot(v_xc))
+                    .sum::<f64>()
+    }
+}
+
+/// Getters
+impl KohnSham {
+    pub fn functional(&self) -> &Functional {
+        &self.functional
+    }
+
+    pub fn grid(&self) -> &MolecularGrid {
+        &self.grid
+    }
+
+    pub fn e_xc(&self) -> f64 {
+        self.e_xc
+    }
+
+    /// fraction of exact exchange
+    pub fn exact_exchange(&self) -> f64 {
+        self.functional.exact_exchange()
+    }
+}
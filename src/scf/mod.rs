@@ -1,7 +1,9 @@
+mod casscf;
+mod converger;
 mod diis;
 pub mod driver;
-mod fock;
 pub mod input;
+mod ks;
 mod rhf;
 mod solver;
 mod uhf;
@@ -1,16 +1,28 @@
-use super::{diis::DIIS, fock::fock, input::SCFInput, solver::HFSolver};
+use super::{
+    converger::Switch,
+    diis::DIIS,
+    input::SCFInput,
+    ks::KohnSham,
+    solver::HFSolver,
+};
 
 use libferric::{
+    data::orbitals::{OrbitalType, Orbitals},
     geometry::Geometry,
     gto_integrals::nuclear_repulsion::nuclear_repulsion,
     linear_algebra::{
         constants::AU_EV,
         diagonalize::DiagonalizeSym,
-        power::PowerSym,
+        orthogonalization::orthogonalizer,
         traits::Dot,
         vector::FVector,
         {matrix::FMatrix, matrix_container::FMatrixContainer},
     },
+    scf::{
+        fock::fock,
+        occupation::{aufbau, density, fermi_dirac, max_overlap},
+        trah::{canonicalize, rotate, OrbitalHessian, TrustRegion},
+    },
 };
 
 pub struct RHFSolver {
@@ -20,14 +32,22 @@ pub struct RHFSolver {
     f: FMatrix,
     d: FMatrix,
     eps: FVector,
+    // occupation numbers (0 to 2), fractional with Fermi smearing
+    occ: FVector,
 
     homo: usize,
     e: f64,
     nuclear_repulsion: f64,
+
+    // overlap for level shifting
+    s: FMatrix,
+
+    // exchange-correlation for Kohn-Sham DFT
+    ks: Option<KohnSham>,
 }
 
 impl RHFSolver {
-    pub fn new(c: &FMatrix, geometry: &Geometry, input: SCFInput) -> Self {
+    pub fn new(c: &FMatrix, geometry: &Geometry, input: SCFInput, ks: Option<KohnSham>) -> Self {
         let homo = geometry.n_electrons / 2;
         let nuclear_repulsion = nuclear_repulsion(geometry.molecule.atoms());
 
@@ -37,9 +57,12 @@ impl RHFSolver {
             f: FMatrix::new(c.rows, c.cols),
             d: FMatrix::new(c.rows, c.cols),
             eps: FVector::new(c.rows),
+            occ: FVector::new(c.rows),
             homo,
             e: 0.0,
             nuclear_repulsion,
+            s: FMatrix::default(),
+            ks,
         }
     }
 
@@ -53,59 +76,102 @@ impl RHFSolver {
         }
         rms.sqrt()
     }
+
+    /// Density of the current orbitals, Dμν = 2 \sum_i^{n_occ} Cμi Cνi^T
+    fn density_from_orbitals(&mut self) {
+        let c_occ = self.c.slice(0, self.c.rows - 1, 0, self.homo - 1);
+        self.d = 2.0 * &c_occ * c_occ.transposed();
+    }
 }
 
 impl HFSolver for RHFSolver {
     /// Read guess from disk
-    fn guess(&mut self) {
-        // todo!() basename!
-        let basename = "input";
+    fn guess(&mut self, basename: &str) {
         self.d = FMatrix::retrieve(&format!("{basename}.p.tmp"));
     }
 
-    fn density(&mut self, s12: &FMatrix) {
-        // Orthogonalize F' = S-1/2 F S-1/2
-        let f_prime = s12 * &self.f * s12;
+    fn density(&mut self, x: &FMatrix) {
+        // Level shift: F + b (S - 1/2 S D S) raises the orbitals unoccupied in the last
+        // iteration by b
+        let shift = self.input.level_shift;
+        let mut f = self.f.clone();
+        if shift > 0.0 {
+            let sds = &self.s * &(&self.d * &self.s);
+            f += &((&self.s - &sds * 0.5) * shift);
+        }
+
+        // Orthogonalize F' = X^T F X
+        let f_prime = x.transposed() * &f * x;
 
         // Diagonalize F' C' -> C' ε
         let (eps, cprime) = f_prime.diagonalize_sym();
+
+        // Backtransform: C = X C'
+        self.c = x * cprime;
         self.eps = eps;
 
-        // Backtransform: C = S-1/2 C'
-        self.c = s12 * cprime;
+        // ΔSCF: occupy the orbitals of maximum overlap with the last occupied space
+        if !self.input.excitations.is_empty() {
+            let p = &self.d * 0.5;
+            (self.c, self.eps) = max_overlap(&self.c, &self.eps, &self.s, &p, self.homo);
+        }
+        for i in self.homo..self.eps.len() {
+            self.eps[i] -= shift;
+        }
+
+        // Occupations: aufbau or Fermi-Dirac smearing
+        self.occ = match self.input.smearing > 0.0 {
+            true => fermi_dirac(&self.eps, 2.0 * self.homo as f64, self.input.smearing, 2.0),
+            false => aufbau(self.eps.len(), self.homo, 2.0),
+        };
 
-        // Compute density: Dμν = \sum_i^{n_occ} Cμi Cνi^T
-        let c_occ = self.c.slice(0, self.c.rows - 1, 0, self.homo - 1);
-        self.d = 2.0 * &c_occ * c_occ.transposed();
+        // Compute density: Dμν = \sum_i n_i Cμi Cνi^T
+        self.d = density(&self.c, &self.occ);
     }
 
     fn fock(&mut self, h: &FMatrix, eri: &FMatrixContainer) {
-        self.f = fock(&self.d, h, eri, 1.0, 0.5);
+        let facx = self.ks.as_ref().map_or(1.0, |ks| ks.exact_exchange());
+        self.f = fock(&self.d, h, eri, 1.0, 0.5 * facx);
+
+        if let Some(ks) = &mut self.ks {
+            self.f += &ks.evaluate(std::slice::from_ref(&self.d))[0];
+        }
     }
 
     fn energy(&mut self, h: &FMatrix) {
         let x = h + self.f.clone();
         self.e = 0.5 * self.d.dot(&x) + self.nuclear_repulsion;
+
+        if let Some(ks) = &self.ks {
+            self.e += ks.energy_correction(std::slice::from_ref(&self.d));
+        }
     }
 
-    fn solve(&mut self, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix) {
+    fn solve(&mut self, basename: &str, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix) {
         // --------------------------------
         // build orthogonalization matrix
         // --------------------------------
-        let s12 = s.powf_sym(-0.5);
+        let (x, _) = orthogonalizer(s, self.input.lindep);
+        self.s = s.clone();
 
         // --------------------------------
         // Guess
         // --------------------------------
-        self.guess();
+        self.guess(basename);
 
         let mut ΔE;
         let mut converged = false;
 
-        let mut diis = DIIS::new(self.input.diis_dim_max, self.input.diis_iter_start, s, &s12);
+        let mut diis = DIIS::new(&self.input, s, &x);
+        let mut switch = Switch::new(&self.input);
+        let facx = self.ks.as_ref().map_or(1.0, |ks| ks.exact_exchange());
+
+        // second-order converger, once active, and the state before its last step
+        let mut trah: Option<TrustRegion> = None;
+        let mut previous: Option<(FMatrix, FMatrix, FMatrix, f64)> = None;
         println!(
-            "\nIter {:^16} {:^16} {:^16} {:^4}",
-            "E", "ΔE", "D(rms)", "Damp"
+            "\nIter {:^16} {:^16} {:^16} {:^16} {:^4}",
+            "E", "ΔE", "D(rms)", "Error", "Damp"
         );
         for iter in 0..self.input.max_iter {
             // --------------------------------
@@ -123,23 +189,70 @@ impl HFSolver for RHFSolver {
             ΔE = -self.e;
             self.energy(h);
 
-            // --------------------------------
-            // DIIS for better convergence
-            // --------------------------------
-            diis.do_diis(&mut self.f, &self.d, iter);
-
-            // --------------------------------
-            // build new density
-            // --------------------------------
             let d_old = self.d.clone();
-            self.density(&s12);
+            let mut rejected = false;
+            // largest element of the DIIS error or of the orbital gradient
+            let error = match &mut trah {
+                None => {
+                    // --------------------------------
+                    // DIIS for better convergence
+                    // --------------------------------
+                    diis.do_diis(
+                        std::slice::from_mut(&mut self.f),
+                        std::slice::from_ref(&self.d),
+                        self.e,
+                        iter,
+                    );
 
-            // --------------------------------
-            // damp density
-            // --------------------------------
-            if diis.damp_factor < 1e-12 {
-                self.d = (1.0 - diis.damp_factor) * self.d.clone() - diis.damp_factor * &d_old;
-            }
+                    // --------------------------------
+                    // build new density
+                    // --------------------------------
+                    self.density(&x);
+
+                    // --------------------------------
+                    // damp density
+                    // --------------------------------
+                    if diis.damp_factor > 1e-12 {
+                        self.d =
+                            (1.0 - diis.damp_factor) * self.d.clone() + diis.damp_factor * &d_old;
+                    }
+                    diis.error_max()
+                }
+                Some(trust) => {
+                    // --------------------------------
+                    // accept or reject the last step
+                    // --------------------------------
+                    if let Some((c, d, f, e)) = previous.take() {
+                        if !trust.update(self.e - e) {
+                            let message =
+                                format!("*** Step rejected, radius {:.2e} ***", trust.radius());
+                            println!("{:^60}", message);
+                            (self.c, self.d, self.f, self.e) = (c, d, f, e);
+                            rejected = true;
+                        }
+                    }
+
+                    // --------------------------------
+                    // rotate orbitals within the trust region
+                    // --------------------------------
+                    let hessian = OrbitalHessian::new(
+                        std::slice::from_ref(&self.c),
+                        &[self.homo],
+                        std::slice::from_ref(&self.f),
+                        eri,
+                        facx,
+                    );
+                    let error = hessian
+                        .gradient()
+                        .iter()
+                        .fold(0.0, |max: f64, g| max.max(g.abs()));
+                    let step = trust.step(&hessian);
+                    previous = Some((self.c.clone(), self.d.clone(), self.f.clone(), self.e));
+                    self.c = rotate(std::slice::from_ref(&self.c), &[self.homo], &step).remove(0);
+                    self.density_from_orbitals();
+                    error
+                }
+            };
 
             // --------------------------------
             // check for convergence
@@ -148,13 +261,27 @@ impl HFSolver for RHFSolver {
 
             let rms = self.d_rms(&d_old);
             println!(
-                "{:3} {:16.9} {:16.5e} {:16.5e} {:4.1}",
-                iter, self.e, ΔE, rms, diis.damp_factor
+                "{:3} {:16.9} {:16.5e} {:16.5e} {:16.5e} {:4.1}",
+                iter, self.e, ΔE, rms, error, diis.damp_factor
             );
-            if ΔE.abs() < self.input.e_threshold && rms < self.input.rms_threshold {
+            if !rejected && ΔE.abs() < self.input.e_threshold && rms < self.input.rms_threshold {
                 converged = true;
                 break;
             }
+
+            // --------------------------------
+            // hand over to the second-order converger
+            // --------------------------------
+            if trah.is_none() && switch.update(iter, rms) {
+                println!("{:^60}", "*** Switching to TRAH ***");
+                trah = Some(TrustRegion::new(self.input.trah_radius));
+                self.density_from_orbitals();
+            }
+        }
+
+        // canonical orbitals after second-order steps
+        if trah.is_some() {
+            (self.c, self.eps) = canonicalize(&self.c, &self.f, self.homo);
         }
 
         if converged {
@@ -194,11 +321,45 @@ impl HFSolver for RHFSolver {
             (self.e - self.nuclear_repulsion) * AU_EV
         );
         println!("One Electron Energy: {:20.9}  {:20.5}", e1, e1 * AU_EV);
-        println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV);
+        match &self.ks {
+            Some(ks) => {
+                let e_xc = ks.e_xc();
+                let e2 = e2 - e_xc;
+                println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV);
+                println!("XC Energy:           {:20.9}  {:20.5}", e_xc, e_xc * AU_EV);
+            }
+            None => println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV),
+        }
 
         println!("\n\n----------------");
         println!("Orbital Energies");
         println!("----------------\n");
         println!("{}", self.eps);
+
+        if self.input.smearing > 0.0 {
+            println!("\n-------------------");
+            println!("Orbital Occupations");
+            println!("-------------------\n");
+            for (i, (e, n)) in self.eps.iter().zip(self.occ.iter()).enumerate() {
+                println!("{:4} {:14.9} {:10.6}", i + 1, e, n);
+            }
+            println!();
+        }
+    }
+
+    fn total_energy(&self) -> f64 {
+        self.e
+    }
+
+    fn orbitals(&self) -> Orbitals {
+        let mut orbitals =
+            Orbitals::new_restricted(self.c.clone(), self.eps.clone(), 2 * self.homo);
+        if self.input.smearing > 0.0 {
+            orbitals.set_occupations(0, self.occ.clone());
+        }
+        if self.ks.is_some() {
+            orbitals.set_kind(OrbitalType::KohnSham);
+        }
+        orbitals
     }
 }
@@ -1,12 +1,17 @@
-use libferric::linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer};
+use libferric::{
+    data::orbitals::Orbitals,
+    linear_algebra::{matrix::FMatrix, matrix_container::FMatrixContainer},
+};
 
 pub trait HFSolver {
-    fn solve(&mut self, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix);
+    fn solve(&mut self, basename: &str, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix);
 
-    fn guess(&mut self);
+    /// Load the guess density stored under the basename
+    fn guess(&mut self, basename: &str);
 
-    /// Build density as Dμν = \sum_i^{n_occ} Cμi Cνi^T
-    fn density(&mut self, s12: &FMatrix);
+    /// Build density as Dμν = \sum_i^{n_occ} Cμi Cνi^T from the Fock matrix diagonalized in
+    /// the orthonormal basis of X (X^T S X = 1)
+    fn density(&mut self, x: &FMatrix);
 
     fn fock(&mut self, h: &FMatrix, eri: &FMatrixContainer);
 
@@ -15,4 +20,10 @@ pub trait HFSolver {
 
     // fn d_rms(&self, d_old: &FMatrix) -> f64;
     fn print_energy(&self, h: &FMatrix);
+
+    /// Total energy including the nuclear repulsion
+    fn total_energy(&self) -> f64;
+
+    /// Final orbitals, coefficients, energies and occupations
+    fn orbitals(&self) -> Orbitals;
 }
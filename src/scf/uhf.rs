@@ -1,18 +1,33 @@
-use super::{diis::DIIS, fock::fock, input::SCFInput, solver::HFSolver};
+use super::{
+    converger::Switch,
+    diis::DIIS,
+    input::SCFInput,
+    ks::KohnSham,
+    solver::HFSolver,
+};
 
 use libferric::{
+    data::orbitals::{OrbitalType, Orbitals},
     geometry::Geometry,
     gto_integrals::nuclear_repulsion::nuclear_repulsion,
     linear_algebra::{
         constants::AU_EV,
         diagonalize::DiagonalizeSym,
-        power::PowerSym,
+        orthogonalization::orthogonalizer,
         traits::Dot,
         vector::FVector,
         {matrix::FMatrix, matrix_container::FMatrixContainer},
     },
+    scf::{
+        fock::fock,
+        occupation::{aufbau, density, fermi_dirac, max_overlap},
+        trah::{canonicalize, rotate, OrbitalHessian, TrustRegion},
+    },
 };
 
+// orbitals, densities, Fock matrices and energy before a second-order step
+type Snapshot = ([FMatrix; 2], [FMatrix; 2], [FMatrix; 2], f64);
+
 pub struct UHFSolver {
     input: SCFInput,
 
@@ -20,14 +35,27 @@ pub struct UHFSolver {
     f: [FMatrix; 2],
     d: [FMatrix; 2],
     eps: [FVector; 2],
+    // occupation numbers (0 or 1), fractional with Fermi smearing
+    occ: [FVector; 2],
 
     homo: [usize; 2],
     e: f64,
     nuclear_repulsion: f64,
+
+    // overlap for level shifting
+    s: FMatrix,
+
+    // exchange-correlation for Kohn-Sham DFT
+    ks: Option<KohnSham>,
 }
 
 impl UHFSolver {
-    pub fn new(c: &[FMatrix; 2], geometry: &Geometry, input: SCFInput) -> Self {
+    pub fn new(
+        c: &[FMatrix; 2],
+        geometry: &Geometry,
+        input: SCFInput,
+        ks: Option<KohnSham>,
+    ) -> Self {
         let homo = [geometry.n_electrons_alpha, geometry.n_electrons_beta];
         let nuclear_repulsion = nuclear_repulsion(geometry.molecule.atoms());
         Self {
@@ -42,9 +70,12 @@ impl UHFSolver {
                 FMatrix::new(c[0].rows, c[0].cols),
             ],
             eps: [FVector::new(c[0].rows), FVector::new(c[0].rows)],
+            occ: [FVector::new(c[0].rows), FVector::new(c[0].rows)],
             homo,
             e: 0.0,
             nuclear_repulsion,
+            s: FMatrix::default(),
+            ks,
         }
     }
 
@@ -62,39 +93,85 @@ impl UHFSolver {
             })
             .sum()
     }
+
+    /// Densities of the current orbitals, Dμν = \sum_i^{n_occ} Cμi Cνi^T
+    fn density_from_orbitals(&mut self) {
+        for op in 0..2 {
+            let c_occ = self.c[op].slice(0, self.c[op].rows - 1, 0, self.homo[op] - 1);
+            self.d[op] = &c_occ * c_occ.transposed();
+        }
+    }
 }
 
 impl HFSolver for UHFSolver {
-    fn guess(&mut self) {
-        // todo!() basename!
-        let basename = "input";
+    fn guess(&mut self, basename: &str) {
         self.d = [
             FMatrix::retrieve(&format!("{basename}.p0.tmp")),
             FMatrix::retrieve(&format!("{basename}.p1.tmp")),
         ];
     }
 
-    fn density(&mut self, s12: &FMatrix) {
+    fn density(&mut self, x: &FMatrix) {
+        let shift = self.input.level_shift;
         (0..2).for_each(|op| {
-            // Orthogonalize F' = S-1/2 F S-1/2
-            let f_prime = s12 * &self.f[op] * s12;
+            // Level shift: F + b (S - S D S) raises the orbitals unoccupied in the last
+            // iteration by b
+            let mut f = self.f[op].clone();
+            if shift > 0.0 {
+                let sds = &self.s * &(&self.d[op] * &self.s);
+                f += &((&self.s - &sds) * shift);
+            }
+
+            // Orthogonalize F' = X^T F X
+            let f_prime = x.transposed() * &f * x;
 
             // Diagonalize F' C' -> C' ε
             let (eps, cprime) = f_prime.diagonalize_sym();
-            self.eps[op] = eps.clone();
 
-            // Backtransform: C = S-1/2 C'
-            self.c[op] = s12 * cprime;
+            // Backtransform: C = X C'
+            self.c[op] = x * cprime;
+            self.eps[op] = eps;
 
-            //  Compute density: Dμν = \sum_i^{n_occ} Cμi Cνi^T
-            let c_occ = self.c[op].slice(0, self.c[op].rows - 1, 0, self.homo[op] - 1);
-            self.d[op] = &c_occ * c_occ.transposed();
+            // ΔSCF: occupy the orbitals of maximum overlap with the last occupied space
+            if !self.input.excitations.is_empty() {
+                (self.c[op], self.eps[op]) = max_overlap(
+                    &self.c[op],
+                    &self.eps[op],
+                    &self.s,
+                    &self.d[op],
+                    self.homo[op],
+                );
+            }
+            for i in self.homo[op]..self.eps[op].len() {
+                self.eps[op][i] -= shift;
+            }
+
+            // Occupations: aufbau or Fermi-Dirac smearing
+            let n = self.homo[op];
+            self.occ[op] = match self.input.smearing > 0.0 {
+                true => fermi_dirac(&self.eps[op], n as f64, self.input.smearing, 1.0),
+                false => aufbau(self.eps[op].len(), n, 1.0),
+            };
+
+            //  Compute density: Dμν = \sum_i n_i Cμi Cνi^T
+            self.d[op] = density(&self.c[op], &self.occ[op]);
         });
     }
 
     fn fock(&mut self, h: &FMatrix, eri: &FMatrixContainer) {
-        self.f[0] = fock(&self.d[0], h, eri, 2.0, 1.0);
-        self.f[1] = fock(&self.d[1], h, eri, 2.0, 1.0);
+        // Coulomb of both spins, exchange of the same spin only
+        let zero = FMatrix::zero(h.rows, h.cols);
+        let j = [0, 1].map(|op| fock(&self.d[op], &zero, eri, 1.0, 0.0));
+        let facx = self.ks.as_ref().map_or(1.0, |ks| ks.exact_exchange());
+        for op in 0..2 {
+            self.f[op] = fock(&self.d[op], h, eri, 1.0, facx) + &j[1 - op];
+        }
+
+        if let Some(ks) = &mut self.ks {
+            for (f, v_xc) in self.f.iter_mut().zip(ks.evaluate(&self.d)) {
+                *f += v_xc;
+            }
+        }
     }
 
     fn energy(&mut self, h: &FMatrix) {
@@ -105,29 +182,37 @@ impl HFSolver for UHFSolver {
             })
             .sum::<f64>()
             + self.nuclear_repulsion;
+
+        if let Some(ks) = &self.ks {
+            self.e += ks.energy_correction(&self.d);
+        }
     }
 
-    fn solve(&mut self, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix) {
+    fn solve(&mut self, basename: &str, h: &FMatrix, eri: &FMatrixContainer, s: &FMatrix) {
         // --------------------------------
         // build orthogonalization matrix
         // --------------------------------
-        let s12 = s.powf_sym(-0.5);
+        let (x, _) = orthogonalizer(s, self.input.lindep);
+        self.s = s.clone();
 
         // --------------------------------
         // Load guess
         // --------------------------------
-        self.guess();
+        self.guess(basename);
 
         let mut ΔE;
         let mut converged = false;
 
-        let mut diis = [
-            DIIS::new(self.input.diis_dim_max, self.input.diis_iter_start, s, &s12),
-            DIIS::new(self.input.diis_dim_max, self.input.diis_iter_start, s, &s12),
-        ];
+        let mut diis = DIIS::new(&self.input, s, &x);
+        let mut switch = Switch::new(&self.input);
+        let facx = self.ks.as_ref().map_or(1.0, |ks| ks.exact_exchange());
+
+        // second-order converger, once active, and the state before its last step
+        let mut trah: Option<TrustRegion> = None;
+        let mut previous: Option<Snapshot> = None;
         println!(
-            "\nIter {:^16} {:^16} {:^16} {:^4}",
-            "E", "ΔE", "D(rms)", "Damp"
+            "\nIter {:^16} {:^16} {:^16} {:^16} {:^4}",
+            "E", "ΔE", "D(rms)", "Error", "Damp"
         );
         for iter in 0..self.input.max_iter {
             // --------------------------------
@@ -141,27 +226,62 @@ impl HFSolver for UHFSolver {
             ΔE = -self.e;
             self.energy(h);
 
-            // --------------------------------
-            // DIIS for better convergence
-            // --------------------------------
-            (0..2).for_each(|op| diis[op].do_diis(&mut self.f[op], &self.d[op], iter));
-
-            // --------------------------------
-            // build new density
-            // --------------------------------
             let d_old = self.d.clone();
-            self.density(&s12);
+            let mut rejected = false;
+            // largest element of the DIIS error or of the orbital gradient
+            let error = match &mut trah {
+                None => {
+                    // --------------------------------
+                    // DIIS for better convergence
+                    // --------------------------------
+                    diis.do_diis(&mut self.f, &self.d, self.e, iter);
 
-            // --------------------------------
-            // damp density
-            // --------------------------------
-            // damping causes DIIS convergence issues
-            (0..2).for_each(|op| {
-                if diis[op].damp_factor < 1e-12 {
-                    self.d[op] = (1.0 - diis[op].damp_factor) * self.d[op].clone()
-                        - diis[op].damp_factor * &d_old[op];
+                    // --------------------------------
+                    // build new density
+                    // --------------------------------
+                    self.density(&x);
+
+                    // --------------------------------
+                    // damp density
+                    // --------------------------------
+                    if diis.damp_factor > 1e-12 {
+                        (0..2).for_each(|op| {
+                            self.d[op] = (1.0 - diis.damp_factor) * self.d[op].clone()
+                                + diis.damp_factor * &d_old[op];
+                        });
+                    }
+                    diis.error_max()
+                }
+                Some(trust) => {
+                    // --------------------------------
+                    // accept or reject the last step
+                    // --------------------------------
+                    if let Some((c, d, f, e)) = previous.take() {
+                        if !trust.update(self.e - e) {
+                            let message =
+                                format!("*** Step rejected, radius {:.2e} ***", trust.radius());
+                            println!("{:^60}", message);
+                            (self.c, self.d, self.f, self.e) = (c, d, f, e);
+                            rejected = true;
+                        }
+                    }
+
+                    // --------------------------------
+                    // rotate orbitals within the trust region
+                    // --------------------------------
+                    let hessian = OrbitalHessian::new(&self.c, &self.homo, &self.f, eri, facx);
+                    let error = hessian
+                        .gradient()
+                        .iter()
+                        .fold(0.0, |max: f64, g| max.max(g.abs()));
+                    let step = trust.step(&hessian);
+                    previous = Some((self.c.clone(), self.d.clone(), self.f.clone(), self.e));
+                    let c = rotate(&self.c, &self.homo, &step);
+                    self.c = [c[0].clone(), c[1].clone()];
+                    self.density_from_orbitals();
+                    error
                 }
-            });
+            };
 
             // --------------------------------
             // check for convergence
@@ -170,17 +290,29 @@ impl HFSolver for UHFSolver {
 
             let rms = self.d_rms(&d_old);
             println!(
-                "{:3} {:16.9} {:16.5e} {:16.5e}",
-                iter,
-                self.e,
-                ΔE,
-                rms // "{:3} {:16.9} {:16.9} {:16.9} {:16.9}",
-                    // iter, e0, ΔE, comm_norm, rms
+                "{:3} {:16.9} {:16.5e} {:16.5e} {:16.5e} {:4.1}",
+                iter, self.e, ΔE, rms, error, diis.damp_factor
             );
-            if ΔE.abs() < self.input.e_threshold && rms < self.input.rms_threshold {
+            if !rejected && ΔE.abs() < self.input.e_threshold && rms < self.input.rms_threshold {
                 converged = true;
                 break;
             }
+
+            // --------------------------------
+            // hand over to the second-order converger
+            // --------------------------------
+            if trah.is_none() && switch.update(iter, rms) {
+                println!("{:^60}", "*** Switching to TRAH ***");
+                trah = Some(TrustRegion::new(self.input.trah_radius));
+                self.density_from_orbitals();
+            }
+        }
+
+        // canonical orbitals after second-order steps
+        if trah.is_some() {
+            for op in 0..2 {
+                (self.c[op], self.eps[op]) = canonicalize(&self.c[op], &self.f[op], self.homo[op]);
+            }
         }
 
         if converged {
@@ -219,7 +351,15 @@ impl HFSolver for UHFSolver {
             (self.e - self.nuclear_repulsion) * AU_EV
         );
         println!("One Electron Energy: {:20.9}  {:20.5}", e1, e1 * AU_EV);
-        println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV);
+        match &self.ks {
+            Some(ks) => {
+                let e_xc = ks.e_xc();
+                let e2 = e2 - e_xc;
+                println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV);
+                println!("XC Energy:           {:20.9}  {:20.5}", e_xc, e_xc * AU_EV);
+            }
+            None => println!("Two Electron Energy: {:20.9}  {:20.5}", e2, e2 * AU_EV),
+        }
 
         println!("\n\n----------------");
         println!("Orbital Energies (Alpha)");
@@ -230,5 +370,43 @@ impl HFSolver for UHFSolver {
         println!("Orbital Energies (Beta)");
         println!("----------------\n");
         println!("{}", self.eps[1]);
+
+        if self.input.smearing > 0.0 {
+            println!("\n-------------------");
+            println!("Orbital Occupations");
+            println!("-------------------\n");
+            println!(
+                "{:4} {:^14} {:^10} {:^14} {:^10}",
+                "", "ε(α)", "n(α)", "ε(β)", "n(β)"
+            );
+            for i in 0..self.eps[0].len() {
+                println!(
+                    "{:4} {:14.9} {:10.6} {:14.9} {:10.6}",
+                    i + 1,
+                    self.eps[0][i],
+                    self.occ[0][i],
+                    self.eps[1][i],
+                    self.occ[1][i]
+                );
+            }
+            println!();
+        }
+    }
+
+    fn total_energy(&self) -> f64 {
+        self.e
+    }
+
+    fn orbitals(&self) -> Orbitals {
+        let mut orbitals = Orbitals::new_unrestricted(self.c.clone(), self.eps.clone(), self.homo);
+        if self.input.smearing > 0.0 {
+            for op in 0..2 {
+                orbitals.set_occupations(op, self.occ[op].clone());
+            }
+        }
+        if self.ks.is_some() {
+            orbitals.set_kind(OrbitalType::KohnSham);
+        }
+        orbitals
     }
 }
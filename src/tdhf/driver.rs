@@ -0,0 +1,114 @@
+use super::input::TDHFInput;
+
+use libferric::{
+    data::orbitals::{OrbitalType, Orbitals},
+    geometry::Geometry,
+    gto_basis_sets::basis::Basis,
+    gto_integrals::{
+        integral_interface::IntegralInterface, one_electron::OneElectronKernel,
+        two_electron::TwoElectronKernel,
+    },
+    linear_algebra::{constants::AU_EV, matrix_container::FMatrixContainer},
+    response::tdhf::{ExcitedState, Spin, TDHF},
+};
+
+use std::{error, time::Instant};
+
+/// hc in eV nm for the conversion of excitation energies to wavelengths
+const EV_NM: f64 = 1239.84198;
+
+pub fn driver(basename: &str, tdhf_input: &TDHFInput) -> Result<(), Box<dyn error::Error>> {
+    println!(
+        r#"
+
+▐▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▀▌
+▐                              ▌
+▐    _____ ____  _   _ _____   ▌
+▐   |_   _|  _ \| | | |  ___|  ▌
+▐     | | | | | | |_| | |_     ▌
+▐     | | | |_| |  _  |  _|    ▌
+▐     |_| |____/|_| |_|_|      ▌
+▐                              ▌
+▐▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▄▌
+"#
+    );
+
+    // read data
+    let geometry = Geometry::retrieve(basename);
+    let orbitals = Orbitals::retrieve(basename);
+    if orbitals.kind() != OrbitalType::HartreeFock {
+        return Err(format!(
+            "TDHF requires Hartree-Fock orbitals, not {} orbitals",
+            orbitals.kind()
+        )
+        .into());
+    }
+    let eri = FMatrixContainer::retrieve(TwoElectronKernel::ERI.to_filename());
+
+    // dipole integrals for the transition moments
+    let integrals = IntegralInterface::new(&Basis::retrieve(basename), geometry.molecule.atoms());
+    let dipole = [
+        OneElectronKernel::DipoleX,
+        OneElectronKernel::DipoleY,
+        OneElectronKernel::DipoleZ,
+    ]
+    .map(|kernel| integrals.calc_one_electron_integral(kernel));
+
+    let tdhf = TDHF::new(&orbitals, &eri);
+    let spins = match (orbitals.restricted(), tdhf_input.triplets) {
+        (true, true) => vec![Spin::Singlet, Spin::Triplet],
+        _ => vec![Spin::Singlet],
+    };
+
+    println!(
+        "\nReference:                        {}",
+        if orbitals.restricted() { "RHF" } else { "UHF" }
+    );
+    println!("Method:                           {}", tdhf_input.method);
+    println!("Occupied-Virtual Pairs:           {}", tdhf.dim());
+    println!(
+        "Number of States:                 {}\n",
+        tdhf_input.n_states
+    );
+
+    let t = Instant::now();
+    let mut states: Vec<ExcitedState> = Vec::new();
+    for spin in spins {
+        states.extend(tdhf.solve(tdhf_input.method, spin, &dipole, &tdhf_input.options()));
+    }
+    states.sort_by(|a, b| a.energy.total_cmp(&b.energy));
+    println!("{} done ({:?})\n", tdhf_input.method, t.elapsed());
+
+    println!(
+        "State {:^8} {:^14} {:^12} {:^10} {:^10}  {:<14} {:^5}",
+        "Spin", "ω (Hartree)", "ω (eV)", "λ (nm)", "f", "Dominant", "X^2"
+    );
+    for (k, state) in states.iter().enumerate() {
+        // largest amplitude
+        let (pair, x) = state
+            .x
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .unwrap();
+        let (op, i, a) = tdhf.pair(pair);
+        let excitation = match orbitals.restricted() {
+            true => format!("{} -> {}", i + 1, a + 1),
+            false => format!("{}{} -> {}{}", i + 1, ["a", "b"][op], a + 1, ["a", "b"][op]),
+        };
+        println!(
+            "{:5} {:^8} {:14.8} {:12.5} {:10.2} {:10.6}  {:<14} {:5.3}",
+            k + 1,
+            state.spin.map_or("-".to_string(), |spin| spin.to_string()),
+            state.energy,
+            state.energy * AU_EV,
+            EV_NM / (state.energy * AU_EV),
+            state.oscillator_strength,
+            excitation,
+            x * x
+        );
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use libferric::response::tdhf::{ResponseType, TDHFOptions};
+use serde_yaml::Value;
+
+use std::str::FromStr;
+
+#[derive(Clone)]
+pub struct TDHFInput {
+    // TDA (CIS) or RPA
+    pub method: ResponseType,
+
+    // number of states per spin
+    pub n_states: usize,
+
+    // triplet states of a restricted reference
+    pub triplets: bool,
+
+    // Davidson
+    pub max_iter: usize,
+    pub threshold: f64,
+}
+
+impl Default for TDHFInput {
+    fn default() -> Self {
+        let options = TDHFOptions::default();
+        Self {
+            method: ResponseType::default(),
+
+            n_states: options.n_states,
+
+            triplets: true,
+
+            max_iter: options.max_iter,
+            threshold: options.threshold,
+        }
+    }
+}
+
+impl TDHFInput {
+    pub fn parse(input: &Value) -> TDHFInput {
+        // initialise TDHFInput with default values
+        let mut tdhf = TDHFInput::default();
+
+        // an empty block uses the defaults
+        if input.is_null() {
+            return tdhf;
+        }
+
+        // overwrite defaults with input values
+        for (key, value) in input.as_mapping().unwrap() {
+            match key.as_str().unwrap().to_lowercase().as_str() {
+                "method" => tdhf.method = ResponseType::from_str(value.as_str().unwrap()).unwrap(),
+                "nstates" => tdhf.n_states = value.as_u64().unwrap() as usize,
+                "triplets" => tdhf.triplets = value.as_bool().unwrap(),
+                "maxiter" => tdhf.max_iter = value.as_u64().unwrap() as usize,
+                "threshold" => tdhf.threshold = value.as_f64().unwrap(),
+                _ => panic!("Unknown option: {:?}", key),
+            }
+        }
+
+        // return TDHFInput
+        tdhf
+    }
+
+    pub fn options(&self) -> TDHFOptions {
+        TDHFOptions {
+            n_states: self.n_states,
+            max_iter: self.max_iter,
+            threshold: self.threshold,
+        }
+    }
+}